@@ -0,0 +1,79 @@
+//! Timing for the sorting methods, run with `cargo bench`
+//! there is no benchmark harness dependency, each case is timed with Instant
+use rust_dict::dict::Dictionary;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// a fixed pseudo random sequence so runs are comparable
+fn shuffled(n: u64) -> Dictionary<u64, u64> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..n)
+        .map(|i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (i, state % (n * 4))
+        })
+        .collect()
+}
+
+// the bubble sort sort_by_values used before the permutation sort, kept as the
+// baseline, the index is rebuilt by from_parts like it was by recompute_map
+fn bubble_sort_by_values(dict: Dictionary<u64, u64>) -> Dictionary<u64, u64> {
+    let (mut keys, mut values) = dict.into_parts();
+    let len = keys.len();
+    for i in 0..len {
+        let mut swapped = false;
+        for j in 0..len - i - 1 {
+            if values[j] > values[j + 1] {
+                swapped = true;
+                keys.swap(j, j + 1);
+                values.swap(j, j + 1);
+            }
+        }
+        if !swapped {
+            break;
+        }
+    }
+    Dictionary::from_parts(keys, values).unwrap()
+}
+
+fn time<F: FnMut()>(name: &str, runs: u32, mut f: F) {
+    let mut best = Duration::MAX;
+    for _ in 0..runs {
+        let start = Instant::now();
+        f();
+        best = best.min(start.elapsed());
+    }
+    println!("{:<32} {:>12.3?}", name, best);
+}
+
+fn main() {
+    for n in [1_000, 10_000, 50_000] {
+        let dict = shuffled(n);
+        // quadratic, a single run is slow enough at the larger sizes
+        time(&format!("bubble_sort_by_values/{}", n), 1, || {
+            black_box(bubble_sort_by_values(dict.clone()));
+        });
+        time(&format!("sort_by_values/{}", n), 5, || {
+            let mut dict = dict.clone();
+            dict.sort_by_values();
+            black_box(dict);
+        });
+        time(&format!("sort_by_keys/{}", n), 5, || {
+            let mut dict = dict.clone();
+            dict.sort_by_keys();
+            black_box(dict);
+        });
+        time(&format!("sort_unstable_by_values/{}", n), 5, || {
+            let mut dict = dict.clone();
+            dict.sort_unstable_by_values();
+            black_box(dict);
+        });
+        time(&format!("sort_unstable_by_keys/{}", n), 5, || {
+            let mut dict = dict.clone();
+            dict.sort_unstable_by_keys();
+            black_box(dict);
+        });
+    }
+}
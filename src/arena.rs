@@ -0,0 +1,181 @@
+use crate::dict::Dictionary;
+use std::hash::Hash;
+
+/// Handle to a dictionary owned by a DictionaryArena
+/// handles from before the last `reset` are no longer valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaId {
+    index: usize,
+    generation: u64,
+}
+
+/// An object pool of many small dictionaries that are created and freed together
+/// Dictionaries are never dropped by the arena, `reset` empties them all at once
+/// and the next round of `alloc` calls hands them out again with their Vec and map
+/// allocations intact. For a parser that builds a dictionary per record this turns
+/// a malloc and free per dictionary into a handful of allocations for the whole run.
+/// Each dictionary still owns its own allocations, they are not carved out of a
+/// shared pool.
+/// # Example
+/// ```
+/// use rust_dict::arena::DictionaryArena;
+///
+/// let mut arena = DictionaryArena::<u32, u32>::with_dict_capacity(4);
+/// for record in 0..3 {
+///     let id = arena.alloc();
+///     arena.get_mut(id).unwrap().push_back(record, record * 2);
+/// }
+/// assert_eq!(arena.len(), 3);
+/// arena.reset();
+/// assert!(arena.is_empty());
+/// ```
+pub struct DictionaryArena<K: Hash + Eq, V> {
+    dicts: Vec<Dictionary<K, V>>,
+    live: usize,
+    generation: u64,
+    dict_capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> DictionaryArena<K, V> {
+    pub fn new() -> DictionaryArena<K, V> {
+        DictionaryArena::with_dict_capacity(0)
+    }
+
+    /// newly created dictionaries reserve room for this many entries
+    pub fn with_dict_capacity(dict_capacity: usize) -> DictionaryArena<K, V> {
+        DictionaryArena {
+            dicts: Vec::new(),
+            live: 0,
+            generation: 0,
+            dict_capacity,
+        }
+    }
+
+    /// hand out an empty dictionary, reusing one freed by `reset` when possible
+    pub fn alloc(&mut self) -> ArenaId {
+        if self.live == self.dicts.len() {
+            self.dicts
+                .push(Dictionary::with_capacity(self.dict_capacity));
+        }
+        let id = ArenaId {
+            index: self.live,
+            generation: self.generation,
+        };
+        self.live += 1;
+        id
+    }
+
+    pub fn get(&self, id: ArenaId) -> Option<&Dictionary<K, V>> {
+        if !self.is_live(id) {
+            return None;
+        }
+        Some(&self.dicts[id.index])
+    }
+
+    pub fn get_mut(&mut self, id: ArenaId) -> Option<&mut Dictionary<K, V>> {
+        if !self.is_live(id) {
+            return None;
+        }
+        Some(&mut self.dicts[id.index])
+    }
+
+    /// the number of dictionaries handed out since the last reset
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// iterate the live dictionaries in the order they were handed out
+    pub fn iter(&self) -> std::slice::Iter<'_, Dictionary<K, V>> {
+        self.dicts[..self.live].iter()
+    }
+
+    /// free every dictionary at once
+    /// their memory is kept for the next round of `alloc` and all existing ids become invalid.
+    /// Anything set on a dictionary, such as a seal, an observer or a key limit, is
+    /// dropped, `alloc` always hands out a dictionary in its default state
+    pub fn reset(&mut self) {
+        for dict in &mut self.dicts[..self.live] {
+            dict.recycle();
+        }
+        self.live = 0;
+        self.generation += 1;
+    }
+
+    /// drop the pooled memory of dictionaries that are not currently handed out
+    pub fn shrink(&mut self) {
+        self.dicts.truncate(self.live);
+        self.dicts.shrink_to_fit();
+    }
+
+    fn is_live(&self, id: ArenaId) -> bool {
+        id.generation == self.generation && id.index < self.live
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for DictionaryArena<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_reuses_dictionaries() {
+        let mut arena = DictionaryArena::<i32, i32>::new();
+        let a = arena.alloc();
+        let b = arena.alloc();
+        arena.get_mut(a).unwrap().extend((0..10).map(|i| (i, i)));
+        arena.get_mut(b).unwrap().push_back(1, 1);
+        assert_eq!(arena.get(a).unwrap().len(), 10);
+        let capacity = arena.get(a).unwrap().capacity();
+
+        arena.reset();
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena.get(b), None);
+
+        let c = arena.alloc();
+        assert_ne!(a, c);
+        let dict = arena.get(c).unwrap();
+        assert!(dict.is_empty());
+        assert_eq!(dict.capacity(), capacity);
+        assert_eq!(arena.iter().count(), 1);
+    }
+
+    #[test]
+    fn reset_drops_settings() {
+        let mut arena = DictionaryArena::<i32, i32>::new();
+        let id = arena.alloc();
+        let dict = arena.get_mut(id).unwrap();
+        dict.extend((0..8).map(|i| (i, i)));
+        dict.observe(|_| panic!("observer of the last round"));
+        dict.seal();
+        let capacity = dict.capacity();
+
+        arena.reset();
+        let id = arena.alloc();
+        let dict = arena.get_mut(id).unwrap();
+        assert!(!dict.is_sealed());
+        assert_eq!(dict.capacity(), capacity);
+        dict.push_back(1, 1);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn shrink_drops_free_dictionaries() {
+        let mut arena = DictionaryArena::<i32, i32>::new();
+        arena.alloc();
+        arena.alloc();
+        arena.reset();
+        let id = arena.alloc();
+        arena.shrink();
+        assert!(arena.get(id).is_some());
+        assert_eq!(arena.len(), 1);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::fmt::{Debug, Write};
+
+/// Describe how two dictionaries differ, None if they are equal
+/// this is the message `assert_dict_eq!` fails with, listing keys missing from right,
+/// extra keys in right, values that differ and keys that moved, each with its positions
+/// # Example
+/// ```
+/// use rust_dict::assert::mismatch_report;
+/// use rust_dict::dict::Dictionary;
+///
+/// let left = Dictionary::from([("a", 1), ("b", 2)]);
+/// let right = Dictionary::from([("a", 1), ("b", 3)]);
+/// let report = mismatch_report(&left, &right).unwrap();
+/// assert!(report.contains("\"b\": left[1] = 2, right[1] = 3"));
+/// assert_eq!(mismatch_report(&left, &left.clone()), None);
+/// ```
+pub fn mismatch_report<K, V, S>(
+    left: &Dictionary<K, V, S>,
+    right: &Dictionary<K, V, S>,
+) -> Option<String>
+where
+    K: Eq + Clone + Debug,
+    V: Clone + PartialEq + Debug,
+    S: BuildIndex<K>,
+{
+    let mut missing = String::new();
+    let mut mismatched = String::new();
+    let mut moved = String::new();
+    for (i, (key, value)) in left.iter().enumerate() {
+        match right.get_full(key) {
+            None => {
+                let _ = writeln!(missing, "  {:?}: left[{}] = {:?}", key, i, value);
+            }
+            Some((j, _, other)) if other != value => {
+                let _ = writeln!(
+                    mismatched,
+                    "  {:?}: left[{}] = {:?}, right[{}] = {:?}",
+                    key, i, value, j, other
+                );
+            }
+            Some((j, _, _)) if j != i => {
+                let _ = writeln!(moved, "  {:?}: left[{}], right[{}]", key, i, j);
+            }
+            Some(_) => {}
+        }
+    }
+    let mut extra = String::new();
+    for (j, (key, value)) in right.iter().enumerate() {
+        if !left.contains_key(key) {
+            let _ = writeln!(extra, "  {:?}: right[{}] = {:?}", key, j, value);
+        }
+    }
+
+    if missing.is_empty() && extra.is_empty() && mismatched.is_empty() && moved.is_empty() {
+        return None;
+    }
+    let mut report = format!(
+        "dictionaries differ (left has {} entries, right has {})\n",
+        left.len(),
+        right.len()
+    );
+    for (title, lines) in [
+        ("missing keys, in left but not right", missing),
+        ("extra keys, in right but not left", extra),
+        ("value mismatches", mismatched),
+        ("position mismatches", moved),
+    ] {
+        if !lines.is_empty() {
+            let _ = writeln!(report, "{}:", title);
+            report.push_str(&lines);
+        }
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_sections() {
+        let left = Dictionary::from([(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+        let right = Dictionary::from([(2, "b"), (1, "a"), (3, "x"), (5, "e")]);
+        let report = mismatch_report(&left, &right).unwrap();
+        assert_eq!(
+            report,
+            "dictionaries differ (left has 4 entries, right has 4)\n\
+             missing keys, in left but not right:\n  4: left[3] = \"d\"\n\
+             extra keys, in right but not left:\n  5: right[3] = \"e\"\n\
+             value mismatches:\n  3: left[2] = \"c\", right[2] = \"x\"\n\
+             position mismatches:\n  1: left[0], right[1]\n  2: left[1], right[0]\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "value mismatches")]
+    fn assert_dict_eq_panics() {
+        let left = Dictionary::from([("a", 1)]);
+        assert_dict_eq!(left, Dictionary::from([("a", 2)]));
+    }
+}
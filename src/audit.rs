@@ -0,0 +1,158 @@
+use std::collections::vec_deque::{self, VecDeque};
+use std::time::SystemTime;
+
+/// What happened to an insert whose key was already in the dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// the new value replaced the old one
+    Overwritten,
+    /// the old value was kept and the new one dropped
+    KeptExisting,
+    /// the new value was merged into the old one
+    Combined,
+}
+
+/// One attempted insert of a key that was already present
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateInsert<K> {
+    pub key: K,
+    pub at: SystemTime,
+    pub resolution: Resolution,
+}
+
+/// A ring buffer of the most recent duplicate key inserts
+/// enabled with `DictionaryBuilder::audit_duplicates` and read with `Dictionary::duplicate_audit`
+/// once full the oldest record is dropped for each new one
+/// # Example
+/// ```
+/// use rust_dict::audit::Resolution;
+/// use rust_dict::dict::Dictionary;
+///
+/// let mut dict = Dictionary::<&str, i32>::builder().audit_duplicates(8).build();
+/// dict.push_back("id", 1);
+/// dict.push_back("id", 2);
+/// let audit = dict.duplicate_audit().unwrap();
+/// assert_eq!(audit.len(), 1);
+/// let record = audit.iter().next().unwrap();
+/// assert_eq!((record.key, record.resolution), ("id", Resolution::Overwritten));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DuplicateAudit<K> {
+    records: VecDeque<DuplicateInsert<K>>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl<K> DuplicateAudit<K> {
+    pub(crate) fn with_capacity(capacity: usize) -> DuplicateAudit<K> {
+        DuplicateAudit {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, key: K, resolution: Resolution) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+            self.dropped += 1;
+        }
+        self.records.push_back(DuplicateInsert {
+            key,
+            at: SystemTime::now(),
+            resolution,
+        });
+    }
+
+    /// the records held, oldest first
+    pub fn iter(&self) -> vec_deque::Iter<'_, DuplicateInsert<K>> {
+        self.records.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// the most records the buffer holds
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// number of records pushed out of the buffer since it was created
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// remove every record, the dropped count is kept
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::{Dictionary, MergePolicy};
+
+    #[test]
+    fn records_every_resolution() {
+        let mut dict = Dictionary::<i32, i32>::builder()
+            .audit_duplicates(10)
+            .build();
+        dict.push_back(1, 1);
+        dict.push_back(1, 2);
+        dict.push_front(1, 3);
+        assert_eq!(dict.insert(1, 4, 0), Ok(false));
+        dict.update_from([(1, 5)], MergePolicy::KeepExisting);
+        dict.update_from([(1, 6)], MergePolicy::Combine(|old, new| *old += new));
+        dict.push_back(2, 1);
+        let resolutions: Vec<Resolution> = dict
+            .duplicate_audit()
+            .unwrap()
+            .iter()
+            .map(|r| r.resolution)
+            .collect();
+        assert_eq!(
+            resolutions,
+            vec![
+                Resolution::Overwritten,
+                Resolution::Overwritten,
+                Resolution::KeptExisting,
+                Resolution::KeptExisting,
+                Resolution::Combined,
+            ]
+        );
+        assert_eq!(
+            Dictionary::<i32, i32>::new()
+                .duplicate_audit()
+                .map(|a| a.len()),
+            None
+        );
+    }
+
+    #[test]
+    fn ring_buffer_drops_oldest() {
+        let mut dict = Dictionary::<i32, i32>::builder()
+            .audit_duplicates(2)
+            .build();
+        dict.push_back(1, 0);
+        for i in 0..5 {
+            dict.push_back(1, i);
+        }
+        let audit = dict.duplicate_audit().unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit.dropped(), 3);
+        let times: Vec<SystemTime> = audit.iter().map(|r| r.at).collect();
+        assert!(times[0] <= times[1]);
+        dict.clear_duplicate_audit();
+        assert!(dict.duplicate_audit().unwrap().is_empty());
+    }
+}
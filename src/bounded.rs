@@ -0,0 +1,228 @@
+use crate::dict::Dictionary;
+use crate::map_like::MapLike;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+
+/// Which entry a BoundedDictionary drops when a new key does not fit
+/// the dropped entry is always the front one, the policies differ in what moves to the back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// the oldest inserted key goes first, reads and replacements do not reorder
+    Fifo,
+    /// the least recently used key goes first, `get`, `get_mut` and replacing
+    /// a value move the key to the back
+    Lru,
+}
+
+/// An ordered dictionary that holds at most max_len entries, a ready made cache
+/// pushing a new key when it is full evicts the front entry. The entries are kept
+/// in eviction order, front first. Evicting and refreshing move entries in the
+/// Vecs behind the dictionary so they are O(n), like `Dictionary::pop_front`
+/// # Example
+/// ```
+/// use rust_dict::bounded::{BoundedDictionary, EvictionPolicy};
+///
+/// let mut cache = BoundedDictionary::new(2, EvictionPolicy::Lru);
+/// cache.push_back("a", 1);
+/// cache.push_back("b", 2);
+/// assert_eq!(cache.get(&"a"), Some(&1));
+/// assert_eq!(cache.push_back_evicting("c", 3), Some(("b", 2)));
+/// assert_eq!(cache.keys(), &vec!["a", "c"]);
+/// ```
+pub struct BoundedDictionary<K: Hash + Eq, V> {
+    dict: Dictionary<K, V>,
+    max_len: usize,
+    policy: EvictionPolicy,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> BoundedDictionary<K, V> {
+    /// # Panics
+    /// panics if max_len is 0
+    pub fn new(max_len: usize, policy: EvictionPolicy) -> BoundedDictionary<K, V> {
+        assert!(max_len != 0, "bounded dictionary max_len must be non-zero");
+        BoundedDictionary {
+            dict: Dictionary::new(),
+            max_len,
+            policy,
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// change the limit, evicting from the front until the entries fit
+    /// # Panics
+    /// panics if max_len is 0
+    pub fn set_max_len(&mut self, max_len: usize) {
+        assert!(max_len != 0, "bounded dictionary max_len must be non-zero");
+        self.max_len = max_len;
+        while self.dict.len() > max_len {
+            self.dict.pop_front();
+        }
+    }
+
+    /// add or replace a value, returning the previous value
+    /// a new key evicts the front entry when the dictionary is full
+    pub fn push_back(&mut self, key: K, value: V) -> Option<V> {
+        self.put(key, value).0
+    }
+
+    /// add or replace a value like push_back, returning the entry evicted to make room
+    pub fn push_back_evicting(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.put(key, value).1
+    }
+
+    // (previous value, evicted entry)
+    fn put(&mut self, key: K, value: V) -> (Option<V>, Option<(K, V)>) {
+        if self.dict.contains_key(&key) {
+            if self.policy == EvictionPolicy::Lru {
+                self.dict.move_to_back(key.clone());
+            }
+            return (self.dict.push_back(key, value), None);
+        }
+        let evicted = if self.dict.len() >= self.max_len {
+            self.dict.pop_front()
+        } else {
+            None
+        };
+        self.dict.push_back(key, value);
+        (None, evicted)
+    }
+
+    /// the value for the key, with Lru this marks the key as most recently used
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.touch(key);
+        self.peek(key)
+    }
+
+    /// the value for the key, with Lru this marks the key as most recently used
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.touch(key);
+        self.dict.get_mut(key.clone())
+    }
+
+    /// the value for the key without changing the eviction order
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.dict.get_full(key).map(|(_, _, v)| v)
+    }
+
+    fn touch(&mut self, key: &K) {
+        if self.policy == EvictionPolicy::Lru {
+            self.dict.move_to_back(key.clone());
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.dict.remove(key.clone())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.dict.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+
+    /// the keys in eviction order, the next to be evicted first
+    pub fn keys(&self) -> &Vec<K> {
+        self.dict.keys()
+    }
+
+    pub fn values(&self) -> &Vec<V> {
+        self.dict.values()
+    }
+
+    pub fn as_dictionary(&self) -> &Dictionary<K, V> {
+        &self.dict
+    }
+
+    /// drop the limit and keep the entries in eviction order
+    pub fn into_dictionary(self) -> Dictionary<K, V> {
+        self.dict
+    }
+}
+
+impl<K: Hash + Eq + Debug, V: Debug> Debug for BoundedDictionary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dict, f)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> MapLike<K, V> for BoundedDictionary<K, V> {
+    /// reads through MapLike do not change the eviction order, like peek
+    fn get(&self, key: &K) -> Option<V> {
+        self.peek(key).cloned()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.push_back(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BoundedDictionary::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        BoundedDictionary::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        MapLike::iter(&self.dict)
+    }
+
+    fn for_each_entry<G: FnMut(&K, &V)>(&self, f: G) {
+        self.dict.for_each_entry(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedDictionary, EvictionPolicy};
+    use crate::map_like::MapLike;
+
+    #[test]
+    fn fifo_ignores_reads() {
+        let mut cache = BoundedDictionary::new(3, EvictionPolicy::Fifo);
+        for i in 0..3 {
+            assert_eq!(cache.push_back_evicting(i, i * 10), None);
+        }
+        assert_eq!(BoundedDictionary::get(&mut cache, &0), Some(&0));
+        assert_eq!(cache.push_back(1, 11), Some(10));
+        assert_eq!(cache.push_back_evicting(3, 30), Some((0, 0)));
+        assert_eq!(cache.keys(), &vec![1, 2, 3]);
+        cache.set_max_len(1);
+        assert_eq!(cache.keys(), &vec![3]);
+        assert_eq!(MapLike::get(&cache, &3), Some(30));
+    }
+
+    #[test]
+    fn lru_refreshes_on_use() {
+        let mut cache = BoundedDictionary::new(3, EvictionPolicy::Lru);
+        cache.push_back("a", 1);
+        cache.push_back("b", 2);
+        cache.push_back("c", 3);
+        *cache.get_mut(&"a").unwrap() += 10;
+        cache.push_back("b", 20);
+        assert_eq!(cache.keys(), &vec!["c", "a", "b"]);
+        assert_eq!(cache.peek(&"c"), Some(&3));
+        assert_eq!(cache.push_back_evicting("d", 4), Some(("c", 3)));
+        assert_eq!(BoundedDictionary::get(&mut cache, &"missing"), None);
+        assert_eq!(cache.remove(&"a"), Some(11));
+        assert_eq!(cache.push_back_evicting("e", 5), None);
+        assert_eq!(cache.len(), 3);
+    }
+}
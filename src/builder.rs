@@ -0,0 +1,167 @@
+use crate::canonical::KeyCanonicalize;
+use crate::dict::Dictionary;
+use crate::format::FormatOptions;
+use crate::growth::GrowthStrategy;
+use crate::key_limit::{Truncate, TruncateKey, HASH_SUFFIX_LEN};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Configure a Dictionary before it is created
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+///
+/// let mut dict = Dictionary::<String, i32>::builder()
+///     .capacity(16)
+///     .canonicalize_keys()
+///     .build();
+/// dict.push_back(" Content-Type".into(), 1);
+/// dict.push_back("content-type ".into(), 2);
+/// assert_eq!(dict.len(), 1);
+/// assert_eq!(dict.get("CONTENT-TYPE".into()), Some(2));
+/// ```
+pub struct DictionaryBuilder<K, V> {
+    capacity: usize,
+    canonicalize: Option<fn(K) -> K>,
+    audit_capacity: Option<usize>,
+    format_options: FormatOptions,
+    key_limit: Option<(usize, Truncate<K>)>,
+    growth: GrowthStrategy,
+    _values: PhantomData<V>,
+}
+
+impl<K, V> Default for DictionaryBuilder<K, V> {
+    fn default() -> Self {
+        DictionaryBuilder {
+            capacity: 0,
+            canonicalize: None,
+            audit_capacity: None,
+            format_options: FormatOptions::default(),
+            key_limit: None,
+            growth: GrowthStrategy::Doubling,
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> DictionaryBuilder<K, V> {
+    pub fn new() -> DictionaryBuilder<K, V> {
+        DictionaryBuilder::default()
+    }
+
+    /// reserve capacity for this many entries up front
+    pub fn capacity(mut self, size: usize) -> Self {
+        self.capacity = size;
+        self
+    }
+
+    /// canonicalize every key with KeyCanonicalize on insert and lookup
+    pub fn canonicalize_keys(mut self) -> Self
+    where
+        K: KeyCanonicalize,
+    {
+        self.canonicalize = Some(K::canonicalize);
+        self
+    }
+
+    /// canonicalize every key with a custom function on insert and lookup
+    pub fn canonicalize_with(mut self, canonicalize: fn(K) -> K) -> Self {
+        self.canonicalize = Some(canonicalize);
+        self
+    }
+
+    /// keep the last `capacity` inserts that hit an existing key,
+    /// readable with `Dictionary::duplicate_audit`
+    pub fn audit_duplicates(mut self, capacity: usize) -> Self {
+        self.audit_capacity = Some(capacity);
+        self
+    }
+
+    /// how FormatValue output such as `Dictionary::formatted` writes values
+    pub fn format_options(mut self, options: FormatOptions) -> Self {
+        self.format_options = options;
+        self
+    }
+
+    /// truncate keys longer than max_len with TruncateKey on insert and lookup,
+    /// the original of a truncated key is kept for `Dictionary::original_key`
+    /// # Panics
+    /// panics if max_len is too short to hold the hash suffix of a truncated String
+    pub fn max_key_len(mut self, max_len: usize) -> Self
+    where
+        K: TruncateKey,
+    {
+        assert!(
+            max_len > HASH_SUFFIX_LEN,
+            "max key length must be more than {}",
+            HASH_SUFFIX_LEN
+        );
+        self.key_limit = Some((max_len, K::truncate_key_nth));
+        self
+    }
+
+    /// how the dictionary grows when it runs out of room, Doubling by default
+    pub fn growth(mut self, strategy: GrowthStrategy) -> Self {
+        self.growth = strategy;
+        self
+    }
+
+    pub fn build(self) -> Dictionary<K, V> {
+        let mut dict = Dictionary::with_capacity(self.capacity);
+        if let Some(canonicalize) = self.canonicalize {
+            dict.set_canonicalizer(canonicalize);
+        }
+        if let Some(capacity) = self.audit_capacity {
+            dict.set_duplicate_audit(capacity);
+        }
+        dict.set_format_options(self.format_options);
+        if let Some((max_len, truncate)) = self.key_limit {
+            dict.set_key_limit(max_len, truncate);
+        }
+        dict.set_growth_strategy(self.growth);
+        dict
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
+    /// start configuring a new dictionary
+    pub fn builder() -> DictionaryBuilder<K, V> {
+        DictionaryBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_keys() {
+        let mut dict = Dictionary::<String, i32>::builder()
+            .canonicalize_keys()
+            .build();
+        dict.push_back("Name".into(), 1);
+        dict.push_back("Age ".into(), 2);
+        dict.push_back(" NAME".into(), 3);
+        assert_eq!(
+            dict.keys(),
+            &vec![String::from("name"), String::from("age")]
+        );
+        assert_eq!(dict.get(" age".into()), Some(2));
+        assert_eq!(dict.get_or("AGE".into(), 0), 2);
+        assert_eq!(dict.remove("NaMe".into()), Some(3));
+        assert_eq!(dict.len(), 1);
+    }
+
+    #[test]
+    fn canonicalize_with() {
+        let mut dict = Dictionary::<i32, i32>::builder()
+            .capacity(4)
+            .canonicalize_with(|k| k.abs())
+            .build();
+        assert_eq!(dict.capacity(), 4);
+        dict.push_back(-3, 1);
+        assert_eq!(dict.get(3), Some(1));
+        assert_eq!(dict.swap_remove(-3), Some(1));
+        assert!(dict.is_empty());
+    }
+}
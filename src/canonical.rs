@@ -0,0 +1,34 @@
+/// A normal form for keys, applied on insert and lookup when enabled with
+/// `DictionaryBuilder::canonicalize_keys`
+/// keys that canonicalize to the same value are treated as the same key,
+/// so near duplicates from messy sources unify deterministically
+/// implementations should be idempotent, canonicalizing twice must give the same key
+pub trait KeyCanonicalize {
+    fn canonicalize(self) -> Self;
+}
+
+/// trims surrounding whitespace and lowercases
+impl KeyCanonicalize for String {
+    fn canonicalize(self) -> Self {
+        self.trim().to_lowercase()
+    }
+}
+
+/// lowercases ascii characters
+impl KeyCanonicalize for char {
+    fn canonicalize(self) -> Self {
+        self.to_ascii_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_canonicalize() {
+        assert_eq!(String::from("  User_ID \n").canonicalize(), "user_id");
+        let once = String::from(" MiXeD ").canonicalize();
+        assert_eq!(once.clone().canonicalize(), once);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+/// Error returned by `Dictionary::cast_values`
+/// lists every key whose value did not convert, in dictionary order,
+/// with the conversion's error message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastError<K> {
+    pub failures: Vec<(K, String)>,
+}
+
+impl<K: Debug> Display for CastError<K> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} values failed to convert", self.failures.len())?;
+        for (key, message) in &self.failures {
+            write!(f, "\nkey {:?}: {}", key, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: Debug> Error for CastError<K> {}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K> + Clone> Dictionary<K, V, S> {
+    /// convert every value with TryFrom, keeping the keys and their order
+    /// all the values are tried, so the error names every key that failed
+    /// rather than stopping at the first
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let loaded = Dictionary::from([("retries", 3i64), ("port", 8080)]);
+    /// let typed = loaded.clone().cast_values::<u16>().unwrap();
+    /// assert_eq!(typed.get("port"), Some(8080u16));
+    ///
+    /// let err = loaded.cast_values::<u8>().unwrap_err();
+    /// assert_eq!(err.failures.len(), 1);
+    /// assert_eq!(err.failures[0].0, "port");
+    /// ```
+    pub fn cast_values<U>(self) -> Result<Dictionary<K, U, S>, CastError<K>>
+    where
+        U: TryFrom<V> + Clone,
+        <U as TryFrom<V>>::Error: Display,
+    {
+        let mut cast =
+            Dictionary::with_capacity_and_index(self.len(), self.index_builder().clone());
+        let mut failures = Vec::new();
+        for (key, value) in self {
+            match U::try_from(value) {
+                Ok(value) if failures.is_empty() => {
+                    cast.push_back(key, value);
+                }
+                Ok(_) => {}
+                Err(e) => failures.push((key, e.to_string())),
+            }
+        }
+        if failures.is_empty() {
+            Ok(cast)
+        } else {
+            Err(CastError { failures })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cast_keeps_order() {
+        let dict = Dictionary::from([("b", 2u64), ("a", 1), ("c", 3)]);
+        let cast: Dictionary<&str, u8> = dict.cast_values().unwrap();
+        assert_eq!(cast.keys(), &vec!["b", "a", "c"]);
+        assert_eq!(cast.values(), &vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn every_failure_is_reported() {
+        let dict = Dictionary::from([("low", -1i32), ("ok", 5), ("high", 300)]);
+        let err = dict.cast_values::<u8>().unwrap_err();
+        let keys: Vec<&str> = err.failures.iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["low", "high"]);
+        assert_eq!(
+            err.to_string(),
+            "2 values failed to convert\n\
+             key \"low\": out of range integral type conversion attempted\n\
+             key \"high\": out of range integral type conversion attempted"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+/// One logged mutation, replayed with `Dictionary::apply_change`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeEvent<K, V> {
+    /// push_back, adding the key or replacing its value in place
+    PushBack {
+        key: K,
+        value: V,
+    },
+    /// push_front, adding the key or replacing its value in place
+    PushFront {
+        key: K,
+        value: V,
+    },
+    /// remove, pop_back or pop_front of the key
+    Remove {
+        key: K,
+    },
+    Clear,
+}
+
+/// Error returned by `Dictionary::changes_since` when the log can not bring a
+/// replica at that version up to date, the replica has to resync in full
+/// carries the oldest version the log can catch up from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooOld {
+    pub oldest: u64,
+}
+
+impl Display for TooOld {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "changes are only available since version {}",
+            self.oldest
+        )
+    }
+}
+
+impl Error for TooOld {}
+
+/// The last changes of a Dictionary, enabled with `Dictionary::track_changes`
+/// the events are consecutive, event i moved the dictionary from version start + i
+/// to start + i + 1
+#[derive(Debug, Clone)]
+pub(crate) struct ChangeLog<K, V> {
+    capacity: usize,
+    start: u64,
+    events: VecDeque<ChangeEvent<K, V>>,
+}
+
+impl<K, V> ChangeLog<K, V> {
+    /// log the mutation that moved the dictionary to version
+    pub(crate) fn record(&mut self, event: ChangeEvent<K, V>, version: u64) {
+        // a mutation that is not logged happened since the last event,
+        // nothing before it can be replayed
+        if version != self.end().wrapping_add(1) {
+            self.events.clear();
+            self.start = version.wrapping_sub(1);
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.start = self.start.wrapping_add(1);
+        }
+        self.events.push_back(event);
+    }
+
+    // the version after the last event
+    fn end(&self) -> u64 {
+        self.start.wrapping_add(self.events.len() as u64)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// keep the last capacity changes so `changes_since` can catch replicas up
+    /// push_back, push_front, remove, pop_back, pop_front and clear are logged,
+    /// any other mutation can not be replayed and restarts the log after it
+    /// # Panics
+    /// panics if capacity is 0
+    pub fn track_changes(&mut self, capacity: usize) {
+        assert!(capacity != 0, "change log capacity must be non-zero");
+        let log = ChangeLog {
+            capacity,
+            start: self.version(),
+            events: VecDeque::with_capacity(capacity),
+        };
+        self.set_change_log(Some(log));
+    }
+
+    /// stop logging changes and drop the log
+    pub fn untrack_changes(&mut self) {
+        self.set_change_log(None);
+    }
+
+    /// the changes that bring a copy taken at version up to date, oldest first
+    /// applying them in order with `apply_change` gives the entries and order of
+    /// this dictionary, whose version is then `version + changes.len()`.
+    /// Fails when the changes since version are no longer all in the log,
+    /// or version is not one this dictionary has had
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut primary = Dictionary::from([("a", 1)]);
+    /// primary.track_changes(64);
+    /// let mut replica = primary.clone();
+    /// let synced = primary.version();
+    ///
+    /// primary.push_back("b", 2);
+    /// primary.push_front("z", 0);
+    /// primary.remove("a");
+    /// for change in primary.changes_since(synced).unwrap() {
+    ///     replica.apply_change(change);
+    /// }
+    /// assert_eq!(replica, primary);
+    /// ```
+    pub fn changes_since(&self, version: u64) -> Result<Vec<ChangeEvent<K, V>>, TooOld> {
+        let current = self.version();
+        let log = self.change_log().filter(|log| log.end() == current);
+        let oldest = log.map_or(current, |log| log.start);
+        if version < oldest || version > current {
+            return Err(TooOld { oldest });
+        }
+        let skip = (version - oldest) as usize;
+        Ok(log
+            .map(|log| log.events.iter().skip(skip).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    /// replay a change logged by another dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn apply_change(&mut self, change: ChangeEvent<K, V>) {
+        match change {
+            ChangeEvent::PushBack { key, value } => {
+                self.push_back(key, value);
+            }
+            ChangeEvent::PushFront { key, value } => {
+                self.push_front(key, value);
+            }
+            ChangeEvent::Remove { key } => {
+                self.remove(key);
+            }
+            ChangeEvent::Clear => self.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replica_catches_up() {
+        let mut primary: Dictionary<u32, u32> = Dictionary::new();
+        primary.track_changes(8);
+        let mut replica = primary.clone();
+        let mut synced = primary.version();
+        for round in 0..3 {
+            primary.push_back(round, round);
+            primary.push_back(round + 10, round);
+            primary.push_back(round, round * 100);
+            primary.pop_front();
+            let changes = primary.changes_since(synced).unwrap();
+            assert_eq!(changes.len(), 4);
+            synced += changes.len() as u64;
+            changes.into_iter().for_each(|c| replica.apply_change(c));
+            assert_eq!(replica, primary);
+        }
+        assert_eq!(synced, primary.version());
+        assert_eq!(primary.changes_since(synced), Ok(vec![]));
+        primary.clear();
+        assert_eq!(primary.changes_since(synced), Ok(vec![ChangeEvent::Clear]));
+    }
+
+    #[test]
+    fn too_old() {
+        let mut dict = Dictionary::new();
+        let untracked = dict.version();
+        dict.push_back("a", 1);
+        assert_eq!(
+            dict.changes_since(untracked),
+            Err(TooOld {
+                oldest: untracked + 1
+            })
+        );
+        dict.track_changes(2);
+        let start = dict.version();
+        for i in 0..3 {
+            dict.push_back("b", i);
+        }
+        // the ring holds the last two changes
+        assert_eq!(dict.changes_since(start), Err(TooOld { oldest: start + 1 }));
+        assert_eq!(dict.changes_since(start + 1).unwrap().len(), 2);
+        assert!(dict.changes_since(dict.version() + 1).is_err());
+
+        // a sort can not be replayed, only later versions can catch up
+        dict.sort_by_keys();
+        let sorted = dict.version();
+        assert_eq!(
+            dict.changes_since(sorted - 1),
+            Err(TooOld { oldest: sorted })
+        );
+        dict.remove("a");
+        assert_eq!(
+            dict.changes_since(sorted - 1),
+            Err(TooOld { oldest: sorted })
+        );
+        assert_eq!(
+            dict.changes_since(sorted),
+            Ok(vec![ChangeEvent::Remove { key: "a" }])
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use crate::dict::{DictIntoIter, Dictionary};
+use crate::map_like::MapLike;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// the number of shards `ConcurrentDictionary::new` creates
+pub const DEFAULT_SHARDS: usize = 16;
+
+// a key's value and the sequence number it was first inserted with
+type Shard<K, V> = HashMap<K, (u64, V)>;
+
+/// A thread safe ordered dictionary
+/// keys are spread over shards that each have their own lock, so readers and
+/// writers of different shards never wait on each other. Every key is stamped with
+/// a global sequence number when it is first inserted, the snapshot orders the
+/// entries by it to give the overall insertion order.
+/// # Example
+/// ```
+/// use rust_dict::concurrent::ConcurrentDictionary;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let dict = Arc::new(ConcurrentDictionary::new());
+/// dict.push_back(0, "main");
+/// let handles: Vec<_> = (1..4)
+///     .map(|i| {
+///         let dict = Arc::clone(&dict);
+///         thread::spawn(move || {
+///             dict.push_back(i, "worker");
+///         })
+///     })
+///     .collect();
+/// handles.into_iter().for_each(|h| h.join().unwrap());
+/// assert_eq!(dict.len(), 4);
+/// assert_eq!(dict.get(&2), Some("worker"));
+/// assert_eq!(dict.snapshot().keys()[0], 0);
+/// ```
+pub struct ConcurrentDictionary<K, V> {
+    shards: Vec<RwLock<Shard<K, V>>>,
+    hasher: RandomState,
+    next_seq: AtomicU64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ConcurrentDictionary<K, V> {
+    pub fn new() -> ConcurrentDictionary<K, V> {
+        ConcurrentDictionary::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// a dictionary with a given number of shards, more shards means less contention
+    /// # Panics
+    /// panics if shards is 0
+    pub fn with_shards(shards: usize) -> ConcurrentDictionary<K, V> {
+        assert!(shards != 0, "shard count must be non-zero");
+        ConcurrentDictionary {
+            shards: (0..shards).map(|_| RwLock::new(HashMap::new())).collect(),
+            hasher: RandomState::new(),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// add a key value pair at the back, a key already present keeps its position
+    /// and has its value replaced, the previous value is returned
+    pub fn push_back(&self, key: K, value: V) -> Option<V> {
+        let mut shard = self.write_shard(&key);
+        match shard.get_mut(&key) {
+            Some((_, current)) => Some(std::mem::replace(current, value)),
+            None => {
+                let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+                shard.insert(key, (seq, value));
+                None
+            }
+        }
+    }
+
+    /// a copy of the value for the key
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.read_shard(key).get(key).map(|(_, v)| v.clone())
+    }
+
+    /// change the value of a key in place under the shard lock
+    /// returns false if the key is not in the dictionary.
+    /// f must not use this dictionary, the shard stays write locked while it runs
+    /// and a call on the same shard deadlocks
+    /// # Panics
+    /// a panic in f poisons the shard, as the value may be left half updated every
+    /// later call that touches the shard panics too
+    pub fn update<F: FnOnce(&mut V)>(&self, key: &K, f: F) -> bool {
+        match self.write_shard(key).get_mut(key) {
+            Some((_, value)) => {
+                f(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.read_shard(key).contains_key(key)
+    }
+
+    /// remove a key, the other entries keep their order
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.write_shard(key).remove(key).map(|(_, v)| v)
+    }
+
+    /// the number of entries across all shards
+    /// other threads may change it as soon as it is read
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            write(shard).clear();
+        }
+    }
+
+    /// copy the entries into an ordinary Dictionary, in insertion order
+    /// every shard is read locked for the duration of the copy, so the snapshot is
+    /// consistent, writes wait until it is taken
+    pub fn snapshot(&self) -> Dictionary<K, V> {
+        let guards: Vec<RwLockReadGuard<Shard<K, V>>> = self.shards.iter().map(read).collect();
+        let mut entries: Vec<(u64, &K, &V)> = guards
+            .iter()
+            .flat_map(|shard| shard.iter().map(|(k, (seq, v))| (*seq, k, v)))
+            .collect();
+        entries.sort_unstable_by_key(|&(seq, _, _)| seq);
+        let mut dict = Dictionary::with_capacity(entries.len());
+        for (_, key, value) in entries {
+            dict.push_back(key.clone(), value.clone());
+        }
+        dict
+    }
+
+    /// iterate over a snapshot of the entries in insertion order
+    pub fn iter_snapshot(&self) -> DictIntoIter<K, V> {
+        self.snapshot().into_iter()
+    }
+
+    /// the entries in insertion order
+    pub fn into_dictionary(self) -> Dictionary<K, V> {
+        self.snapshot()
+    }
+
+    fn shard_of(&self, key: &K) -> &RwLock<Shard<K, V>> {
+        let i = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[i]
+    }
+
+    fn read_shard(&self, key: &K) -> RwLockReadGuard<'_, Shard<K, V>> {
+        read(self.shard_of(key))
+    }
+
+    fn write_shard(&self, key: &K) -> RwLockWriteGuard<'_, Shard<K, V>> {
+        write(self.shard_of(key))
+    }
+}
+
+// a panic in an update closure can leave a value half updated, so a poisoned shard
+// is not used again
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().expect("a shard was poisoned by a panic")
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().expect("a shard was poisoned by a panic")
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for ConcurrentDictionary<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> MapLike<K, V> for ConcurrentDictionary<K, V> {
+    fn get(&self, key: &K) -> Option<V> {
+        ConcurrentDictionary::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.push_back(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        ConcurrentDictionary::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        ConcurrentDictionary::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    /// a snapshot, in insertion order
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        Box::new(self.iter_snapshot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn order_survives_sharding() {
+        let dict = ConcurrentDictionary::with_shards(4);
+        for i in (0..50).rev() {
+            dict.push_back(i, i * 2);
+        }
+        assert_eq!(dict.push_back(10, 0), Some(20));
+        assert_eq!(dict.remove(&49), Some(98));
+        assert!(dict.update(&0, |v| *v = 7));
+        let snapshot = dict.snapshot();
+        assert_eq!(snapshot.keys(), &(0..49).rev().collect::<Vec<i32>>());
+        assert_eq!(snapshot.get(10), Some(0));
+        assert_eq!(dict.iter_snapshot().next_back(), Some((0, 7)));
+    }
+
+    #[test]
+    fn concurrent_writers() {
+        let dict = Arc::new(ConcurrentDictionary::new());
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let dict = Arc::clone(&dict);
+                thread::spawn(move || {
+                    for i in 0..250 {
+                        dict.push_back(t * 1000 + i, i);
+                        dict.update(&(t * 1000), |v| *v += 1);
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+        assert_eq!(dict.len(), 1000);
+        assert_eq!(dict.get(&2000), Some(250));
+        let mut map = Arc::try_unwrap(dict).ok().unwrap();
+        assert_eq!(MapLike::remove(&mut map, &3249), Some(249));
+        assert_eq!(map.into_dictionary().len(), 999);
+    }
+
+    #[test]
+    fn panicking_update_poisons_the_shard() {
+        let dict = Arc::new(ConcurrentDictionary::with_shards(1));
+        dict.push_back(1, vec![1, 2]);
+        let worker = Arc::clone(&dict);
+        let result = thread::spawn(move || {
+            worker.update(&1, |v| {
+                v.clear();
+                panic!("half way");
+            })
+        })
+        .join();
+        assert!(result.is_err());
+        let reader = Arc::clone(&dict);
+        assert!(thread::spawn(move || reader.get(&1)).join().is_err());
+    }
+}
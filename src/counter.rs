@@ -0,0 +1,224 @@
+use crate::dict::Dictionary;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+use std::ops::{Add, BitAnd, BitOr, Sub};
+
+/// An ordered tally of how many times each key was seen
+/// like `collections.Counter` in python, keys keep the order they were first counted in
+/// and a missing key counts as zero
+/// # Example
+/// ```
+/// use rust_dict::counter::Counter;
+///
+/// let mut words: Counter<&str> = "a b a c b a".split(' ').collect();
+/// words.subtract("c");
+/// assert_eq!(words.get(&"a"), 3);
+/// assert_eq!(words.most_common(2), vec![("a", 3), ("b", 2)]);
+/// ```
+#[derive(Clone)]
+pub struct Counter<K: Hash + Eq> {
+    counts: Dictionary<K, i64>,
+}
+
+impl<K: Hash + Eq + Clone> Counter<K> {
+    pub fn new() -> Counter<K> {
+        Counter {
+            counts: Dictionary::new(),
+        }
+    }
+
+    /// count the key once
+    pub fn add(&mut self, key: K) {
+        self.add_n(key, 1);
+    }
+
+    /// count the key n times, a new key is pushed to the back
+    pub fn add_n(&mut self, key: K, n: i64) {
+        match self.counts.get_mut(key.clone()) {
+            Some(count) => *count += n,
+            None => {
+                self.counts.push_back(key, n);
+            }
+        }
+    }
+
+    /// take one from the count of the key, counts can go to zero and below
+    pub fn subtract(&mut self, key: K) {
+        self.add_n(key, -1);
+    }
+
+    /// take n from the count of the key
+    pub fn subtract_n(&mut self, key: K, n: i64) {
+        self.add_n(key, -n);
+    }
+
+    /// the count of the key, zero if it was never counted
+    pub fn get(&self, key: &K) -> i64 {
+        self.counts.get_copied(key).unwrap_or(0)
+    }
+
+    /// forget the key, returning its count
+    pub fn remove(&mut self, key: &K) -> Option<i64> {
+        self.counts.remove(key.clone())
+    }
+
+    /// the number of distinct keys
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// the sum of every count
+    pub fn total(&self) -> i64 {
+        self.counts.values_copied().sum()
+    }
+
+    /// the n keys with the highest counts, highest first
+    /// keys with the same count are in the order they were first counted
+    pub fn most_common(&self, n: usize) -> Vec<(K, i64)> {
+        let mut sorted = self.counts.clone();
+        sorted.sort_by(|(_, a), (_, b)| b.cmp(a));
+        sorted.into_iter().take(n).collect()
+    }
+
+    /// drop every key whose count is zero or below
+    pub fn retain_positive(&mut self) {
+        self.counts.retain(|_, count| *count > 0);
+    }
+
+    /// every key with its count, in order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, i64)> + '_ {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+
+    pub fn as_dictionary(&self) -> &Dictionary<K, i64> {
+        &self.counts
+    }
+
+    pub fn into_dictionary(self) -> Dictionary<K, i64> {
+        self.counts
+    }
+
+    // keys of self then new keys of rhs, each combined count kept if it is positive
+    fn combine<F: Fn(i64, i64) -> i64>(&self, rhs: &Counter<K>, f: F) -> Counter<K> {
+        let mut combined = Counter::new();
+        let keys = self.counts.keys().iter().chain(
+            rhs.counts
+                .keys()
+                .iter()
+                .filter(|k| !self.counts.contains_key(k)),
+        );
+        for key in keys {
+            let count = f(self.get(key), rhs.get(key));
+            if count > 0 {
+                combined.counts.push_back(key.clone(), count);
+            }
+        }
+        combined
+    }
+}
+
+impl<K: Hash + Eq + Clone> Default for Counter<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone> PartialEq for Counter<K> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.counts == rhs.counts
+    }
+}
+
+impl<K: Hash + Eq + Clone + Debug> Debug for Counter<K> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.counts, f)
+    }
+}
+
+impl<K: Hash + Eq + Clone> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Counter<K> {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<K: Hash + Eq + Clone> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> Add for Counter<K> {
+    type Output = Counter<K>;
+    /// counts added together, like `c1 + c2` in python
+    fn add(self, rhs: Self) -> Counter<K> {
+        self.combine(&rhs, |a, b| a + b)
+    }
+}
+
+impl<K: Hash + Eq + Clone> Sub for Counter<K> {
+    type Output = Counter<K>;
+    /// counts of rhs taken away, keys that drop to zero or below are left out
+    fn sub(self, rhs: Self) -> Counter<K> {
+        self.combine(&rhs, |a, b| a - b)
+    }
+}
+
+impl<K: Hash + Eq + Clone> BitOr for Counter<K> {
+    type Output = Counter<K>;
+    /// the larger count of each key, like `c1 | c2` in python
+    fn bitor(self, rhs: Self) -> Counter<K> {
+        self.combine(&rhs, i64::max)
+    }
+}
+
+impl<K: Hash + Eq + Clone> BitAnd for Counter<K> {
+    type Output = Counter<K>;
+    /// the smaller count of each key, like `c1 & c2` in python
+    fn bitand(self, rhs: Self) -> Counter<K> {
+        self.combine(&rhs, i64::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting() {
+        let mut letters: Counter<char> = "mississippi".chars().collect();
+        assert_eq!(letters.get(&'s'), 4);
+        assert_eq!(letters.get(&'z'), 0);
+        assert_eq!(letters.total(), 11);
+        assert_eq!(letters.most_common(3), vec![('i', 4), ('s', 4), ('p', 2)]);
+        letters.subtract_n('m', 2);
+        assert_eq!(letters.get(&'m'), -1);
+        letters.retain_positive();
+        assert_eq!(letters.len(), 3);
+        assert_eq!(letters.most_common(10).len(), 3);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a: Counter<&str> = ["x", "x", "y"].into_iter().collect();
+        let b: Counter<&str> = ["y", "y", "z"].into_iter().collect();
+        let sum = a.clone() + b.clone();
+        assert_eq!(
+            sum.iter().collect::<Vec<_>>(),
+            vec![(&"x", 2), (&"y", 3), (&"z", 1)]
+        );
+        let diff = a.clone() - b.clone();
+        assert_eq!(diff.iter().collect::<Vec<_>>(), vec![(&"x", 2)]);
+        let union = a.clone() | b.clone();
+        assert_eq!(union.get(&"y"), 2);
+        let both = a & b;
+        assert_eq!(both.iter().collect::<Vec<_>>(), vec![(&"y", 1)]);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::dict::Dictionary;
+use crate::format::FormatValue;
+use crate::index::BuildIndex;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read, Write};
+
+/// Errors raised while reading CSV
+/// rows are numbered from 1 with the header as row 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    /// the input has no header row
+    MissingHeader,
+    /// the header has no column with this name
+    MissingColumn { name: String },
+    /// the row has fewer fields than it needs for the key and value columns
+    ShortRow { row: usize },
+    /// a quoted field was never closed
+    UnterminatedQuote { row: usize },
+    /// there was something other than a comma or line end after a closing quote
+    TrailingCharacters { row: usize },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CsvError::MissingHeader => write!(f, "no header row"),
+            CsvError::MissingColumn { name } => write!(f, "no column named {:?}", name),
+            CsvError::ShortRow { row } => write!(f, "row {}: too few fields", row),
+            CsvError::UnterminatedQuote { row } => {
+                write!(f, "row {}: unterminated quoted field", row)
+            }
+            CsvError::TrailingCharacters { row } => {
+                write!(f, "row {}: unexpected characters after quoted field", row)
+            }
+        }
+    }
+}
+
+impl Error for CsvError {}
+
+impl Dictionary<String, String> {
+    /// Read two columns of a CSV table into a Dictionary, rows keep their order
+    /// the first row is the header and the columns are picked by name.
+    /// Fields may be "quoted", with "" for a quote and line breaks allowed inside.
+    /// When a key is repeated the last value wins but the key keeps its first position.
+    /// Malformed input fails with `io::ErrorKind::InvalidData` wrapping a CsvError
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let table = "code,name,region\nFR,France,eu\nJP,Japan,asia\n";
+    /// let names = Dictionary::from_csv(table.as_bytes(), "code", "name").unwrap();
+    /// assert_eq!(names.keys(), &vec![String::from("FR"), String::from("JP")]);
+    /// assert_eq!(names.get("JP".into()), Some(String::from("Japan")));
+    /// ```
+    pub fn from_csv<R: Read>(
+        mut reader: R,
+        key_col: &str,
+        value_col: &str,
+    ) -> io::Result<Dictionary<String, String>> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        parse_csv(&input, key_col, value_col)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl<K: FormatValue, V: FormatValue, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// Write the dictionary as a two column CSV table with a header row
+    /// keys and values are written with FormatValue and the dictionary's FormatOptions.
+    /// Fields holding a comma, quote or line break are quoted,
+    /// so a string dictionary reads back to the same dictionary with from_csv
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(String::from("a"), String::from("x, y"))]);
+    /// let mut output = Vec::new();
+    /// dict.to_csv(&mut output, "key", "value").unwrap();
+    /// assert_eq!(String::from_utf8(output).unwrap(), "key,value\na,\"x, y\"\n");
+    /// ```
+    pub fn to_csv<W: Write>(
+        &self,
+        mut writer: W,
+        key_col: &str,
+        value_col: &str,
+    ) -> io::Result<()> {
+        write_record(&mut writer, key_col, value_col)?;
+        let options = self.format_options();
+        let (mut key, mut value) = (String::new(), String::new());
+        for (k, v) in self.keys().iter().zip(self.values()) {
+            key.clear();
+            value.clear();
+            // writing to a String can not fail
+            k.format_value(options, &mut key).unwrap();
+            v.format_value(options, &mut value).unwrap();
+            write_record(&mut writer, &key, &value)?;
+        }
+        writer.flush()
+    }
+}
+
+fn parse_csv(
+    input: &str,
+    key_col: &str,
+    value_col: &str,
+) -> Result<Dictionary<String, String>, CsvError> {
+    let mut records = Records {
+        rest: input.strip_prefix('\u{feff}').unwrap_or(input),
+        row: 0,
+    };
+    let header = records.next().ok_or(CsvError::MissingHeader)??;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field == name)
+            .ok_or_else(|| CsvError::MissingColumn {
+                name: name.to_string(),
+            })
+    };
+    let (key_at, value_at) = (column(key_col)?, column(value_col)?);
+
+    let mut dict = Dictionary::new();
+    while let Some(record) = records.next() {
+        let mut record = record?;
+        // a blank line is a single empty field
+        if record.len() == 1 && record[0].is_empty() {
+            continue;
+        }
+        if record.len() <= key_at.max(value_at) {
+            return Err(CsvError::ShortRow { row: records.row });
+        }
+        let value = std::mem::take(&mut record[value_at]);
+        let key = std::mem::take(&mut record[key_at]);
+        dict.push_back(key, value);
+    }
+    Ok(dict)
+}
+
+// yields the fields of one record at a time, a record may span lines inside quotes
+struct Records<'a> {
+    rest: &'a str,
+    row: usize,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Result<Vec<String>, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        self.row += 1;
+        let mut fields = Vec::new();
+        loop {
+            let (field, rest) = match self.rest.strip_prefix('"') {
+                Some(quoted) => match parse_quoted(quoted, self.row) {
+                    Ok(split) => split,
+                    Err(e) => return Some(Err(e)),
+                },
+                None => {
+                    let end = self.rest.find([',', '\n', '\r']).unwrap_or(self.rest.len());
+                    (self.rest[..end].to_string(), &self.rest[end..])
+                }
+            };
+            fields.push(field);
+            if let Some(rest) = rest.strip_prefix(',') {
+                self.rest = rest;
+                continue;
+            }
+            self.rest = rest
+                .strip_prefix("\r\n")
+                .or_else(|| rest.strip_prefix('\n'))
+                .or_else(|| rest.strip_prefix('\r'))
+                .unwrap_or(rest);
+            return Some(Ok(fields));
+        }
+    }
+}
+
+// the field after an opening quote and the input after its closing quote
+fn parse_quoted(quoted: &str, row: usize) -> Result<(String, &str), CsvError> {
+    let mut field = String::new();
+    let mut rest = quoted;
+    loop {
+        let end = rest.find('"').ok_or(CsvError::UnterminatedQuote { row })?;
+        field.push_str(&rest[..end]);
+        rest = &rest[end + 1..];
+        match rest.strip_prefix('"') {
+            Some(after) => {
+                field.push('"');
+                rest = after;
+            }
+            None => break,
+        }
+    }
+    if rest.is_empty() || rest.starts_with([',', '\n', '\r']) {
+        Ok((field, rest))
+    } else {
+        Err(CsvError::TrailingCharacters { row })
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, key: &str, value: &str) -> io::Result<()> {
+    write_field(writer, key)?;
+    writer.write_all(b",")?;
+    write_field(writer, value)?;
+    writer.write_all(b"\n")
+}
+
+fn write_field<W: Write>(writer: &mut W, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(writer, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        writer.write_all(field.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_round_trip() {
+        let input = "id,label,note\r\n\
+                     3,\"three, the \"\"magic\"\" one\",x\r\n\
+                     1,\"multi\nline\",y\r\n\
+                     \r\n\
+                     3,trois,z";
+        let dict = Dictionary::from_csv(input.as_bytes(), "id", "label").unwrap();
+        assert_eq!(dict.keys(), &vec![String::from("3"), String::from("1")]);
+        assert_eq!(dict.get("3".into()), Some(String::from("trois")));
+        assert_eq!(dict.get("1".into()), Some(String::from("multi\nline")));
+
+        let mut dict = dict;
+        dict.push_back("q".into(), "say \"hi\"".into());
+        dict.push_back("".into(), "".into());
+        let mut output = Vec::new();
+        dict.to_csv(&mut output, "id", "label").unwrap();
+        let read_back = Dictionary::from_csv(output.as_slice(), "id", "label").unwrap();
+        // an empty key and value write a line with a single comma, not a blank line
+        assert_eq!(read_back, dict);
+    }
+
+    #[test]
+    fn csv_uses_format_options() {
+        let mut dict = Dictionary::from([(1u32, 0.5f64), (2, 2.0)]);
+        dict.set_format_options(crate::format::FormatOptions {
+            float_precision: Some(2),
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        dict.to_csv(&mut output, "id", "score").unwrap();
+        assert_eq!(output, b"id,score\n1,0.50\n2,2.00\n");
+    }
+
+    #[test]
+    fn csv_errors() {
+        let error = |input: &str| -> CsvError {
+            let error = Dictionary::from_csv(input.as_bytes(), "k", "v").unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            *error.into_inner().unwrap().downcast::<CsvError>().unwrap()
+        };
+        assert_eq!(error(""), CsvError::MissingHeader);
+        assert_eq!(
+            error("k,value\n"),
+            CsvError::MissingColumn { name: "v".into() }
+        );
+        assert_eq!(error("k,v\na,b\nc\n"), CsvError::ShortRow { row: 3 });
+        assert_eq!(
+            error("k,v\na,\"b\n"),
+            CsvError::UnterminatedQuote { row: 2 }
+        );
+        assert_eq!(
+            error("k,v\na,\"b\"c\n"),
+            CsvError::TrailingCharacters { row: 2 }
+        );
+    }
+}
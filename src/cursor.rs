@@ -0,0 +1,172 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+
+/// A cursor over the entries of a Dictionary that can edit around its position,
+/// created with `Dictionary::cursor_mut`
+/// the cursor points at an entry, or past the last one once it has walked off the end.
+/// Inserting and removing shift the entries like `Vec::insert` and `Vec::remove` and
+/// update the index map right away, so each is O(n) and the dictionary is consistent
+/// after every edit, even if the cursor is leaked
+pub struct CursorMut<'a, K, V, S: BuildIndex<K>> {
+    dict: &'a mut Dictionary<K, V, S>,
+    position: usize,
+}
+
+impl<'a, K: Eq + Clone, V: Clone, S: BuildIndex<K>> CursorMut<'a, K, V, S> {
+    /// the position of the current entry, None past the end
+    pub fn index(&self) -> Option<usize> {
+        Some(self.position).filter(|&i| i < self.dict.len())
+    }
+
+    /// the current entry, with its value borrowed mutably
+    /// this bumps the version like `Dictionary::get_mut`
+    pub fn current(&mut self) -> Option<(&K, &mut V)> {
+        self.dict.entry_mut(self.position)
+    }
+
+    /// the entry after the current one
+    pub fn peek_next(&self) -> Option<(&K, &V)> {
+        self.dict.get_index_entry(self.position + 1)
+    }
+
+    /// the entry before the current one, the last entry past the end
+    pub fn peek_prev(&self) -> Option<(&K, &V)> {
+        self.dict.get_index_entry(self.position.checked_sub(1)?)
+    }
+
+    /// move to the next entry, or past the end from the last one
+    /// returns false if the cursor was already past the end
+    pub fn move_next(&mut self) -> bool {
+        if self.position >= self.dict.len() {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// move to the previous entry, returns false at the first entry
+    pub fn move_prev(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    /// insert an entry before the current one, the cursor stays on the current entry
+    /// past the end this pushes onto the back. Returns false and inserts nothing if the
+    /// key is already in the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn insert_before(&mut self, key: K, value: V) -> bool {
+        if !self.dict.insert_at(self.position, key, value) {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// insert an entry after the current one, the cursor stays on the current entry
+    /// past the end this pushes onto the back and the cursor points at the new entry.
+    /// Returns false and inserts nothing if the key is already in the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn insert_after(&mut self, key: K, value: V) -> bool {
+        let at = (self.position + 1).min(self.dict.len());
+        self.dict.insert_at(at, key, value)
+    }
+
+    /// remove the current entry and move to the one after it, None past the end
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        if self.position >= self.dict.len() {
+            return None;
+        }
+        Some(self.dict.remove_at(self.position))
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// a cursor at the first entry that can walk the dictionary in order and insert,
+    /// remove or change entries as it goes
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// // merge runs of adjacent entries with the same first letter
+    /// let mut dict = Dictionary::from([("a1", 1), ("a2", 2), ("b1", 3), ("a3", 4), ("a4", 5)]);
+    /// let mut cursor = dict.cursor_mut();
+    /// while let Some((key, _)) = cursor.current() {
+    ///     let letter = key.as_bytes()[0];
+    ///     let run_continues = cursor.peek_prev().is_some_and(|(prev, _)| prev.as_bytes()[0] == letter);
+    ///     if run_continues {
+    ///         let (_, value) = cursor.remove_current().unwrap();
+    ///         cursor.move_prev();
+    ///         *cursor.current().unwrap().1 += value;
+    ///     }
+    ///     cursor.move_next();
+    /// }
+    /// drop(cursor);
+    /// assert_eq!(dict.keys(), &vec!["a1", "b1", "a3"]);
+    /// assert_eq!(dict.values(), &vec![3, 3, 9]);
+    /// assert_eq!(dict.index_of(&"a3"), Some(2));
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, K, V, S> {
+        CursorMut {
+            dict: self,
+            position: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_around_the_cursor() {
+        let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c")]);
+        let mut cursor = dict.cursor_mut();
+        assert!(cursor.insert_before(0, "z"));
+        assert_eq!(cursor.index(), Some(1));
+        assert!(!cursor.insert_after(2, "dup"));
+        assert!(cursor.insert_after(10, "x"));
+        assert_eq!(cursor.peek_next(), Some((&10, &"x")));
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some((2, "b")));
+        *cursor.current().unwrap().1 = "C";
+        assert!(cursor.move_next());
+        assert_eq!(cursor.index(), None);
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.remove_current(), None);
+        assert!(cursor.insert_after(4, "d"));
+        assert_eq!(cursor.index(), Some(4));
+        assert_eq!(dict.keys(), &vec![0, 1, 10, 3, 4]);
+        assert_eq!(dict.values(), &vec!["z", "a", "x", "C", "d"]);
+        for (i, key) in dict.keys().clone().iter().enumerate() {
+            assert_eq!(dict.index_of(key), Some(i));
+        }
+    }
+
+    #[test]
+    fn walk_and_delete() {
+        let mut dict: Dictionary<u32, u32> = (0..10).map(|i| (i, i)).collect();
+        let version = dict.version();
+        let mut cursor = dict.cursor_mut();
+        while let Some((key, _)) = cursor.current() {
+            if key % 3 == 0 {
+                cursor.remove_current();
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert_eq!(cursor.peek_prev(), Some((&8, &8)));
+        assert_eq!(dict.keys(), &vec![1, 2, 4, 5, 7, 8]);
+        assert!(dict.version() > version);
+        assert_eq!(dict.get(7), Some(7));
+        assert_eq!(dict.index_of(&8), Some(5));
+        assert!(dict.health().is_healthy());
+    }
+}
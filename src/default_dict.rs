@@ -0,0 +1,174 @@
+use crate::dict::Dictionary;
+use crate::map_like::MapLike;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+
+/// An ordered dictionary that fills in missing keys with a factory
+/// like `collections.defaultdict` in python, reading a missing key through `entry`
+/// inserts `factory()` at the back first, so accumulating needs no existence check
+/// # Example
+/// ```
+/// use rust_dict::default_dict::DefaultDict;
+///
+/// let mut by_len = DefaultDict::new(Vec::new);
+/// for word in ["fig", "pear", "kiwi", "yam"] {
+///     by_len.entry(word.len()).push(word);
+/// }
+/// assert_eq!(by_len.keys(), &vec![3, 4]);
+/// assert_eq!(by_len.get(&4), Some(&vec!["pear", "kiwi"]));
+/// ```
+pub struct DefaultDict<K: Hash + Eq, V, F = fn() -> V> {
+    dict: Dictionary<K, V>,
+    factory: F,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, F: Fn() -> V> DefaultDict<K, V, F> {
+    pub fn new(factory: F) -> DefaultDict<K, V, F> {
+        DefaultDict {
+            dict: Dictionary::new(),
+            factory,
+        }
+    }
+
+    pub fn with_capacity(size: usize, factory: F) -> DefaultDict<K, V, F> {
+        DefaultDict {
+            dict: Dictionary::with_capacity(size),
+            factory,
+        }
+    }
+
+    /// wrap an existing dictionary, its entries keep their order
+    pub fn from_dictionary(dict: Dictionary<K, V>, factory: F) -> DefaultDict<K, V, F> {
+        DefaultDict { dict, factory }
+    }
+
+    /// the value for the key, inserting `factory()` at the back if it is missing
+    pub fn entry(&mut self, key: K) -> &mut V {
+        if !self.dict.contains_key(&key) {
+            self.dict.push_back(key.clone(), (self.factory)());
+        }
+        self.dict.get_mut(key).unwrap()
+    }
+
+    /// the value for the key, a missing key is not inserted
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.dict.get_full(key).map(|(_, _, v)| v)
+    }
+
+    /// a copy of the value for the key, or `factory()` for a missing key
+    /// a missing key is not inserted
+    pub fn get_or_default(&self, key: &K) -> V {
+        match self.get(key) {
+            Some(value) => value.clone(),
+            None => (self.factory)(),
+        }
+    }
+
+    /// add or replace a value, a replaced key keeps its position
+    pub fn push_back(&mut self, key: K, value: V) -> Option<V> {
+        self.dict.push_back(key, value)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.dict.remove(key.clone())
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.dict.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+
+    pub fn keys(&self) -> &Vec<K> {
+        self.dict.keys()
+    }
+
+    pub fn values(&self) -> &Vec<V> {
+        self.dict.values()
+    }
+
+    /// the dictionary behind the defaults
+    pub fn as_dictionary(&self) -> &Dictionary<K, V> {
+        &self.dict
+    }
+
+    pub fn as_dictionary_mut(&mut self) -> &mut Dictionary<K, V> {
+        &mut self.dict
+    }
+
+    /// drop the factory and keep the entries
+    pub fn into_dictionary(self) -> Dictionary<K, V> {
+        self.dict
+    }
+}
+
+impl<K: Hash + Eq + Debug, V: Debug, F> Debug for DefaultDict<K, V, F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dict, f)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, F: Fn() -> V> MapLike<K, V> for DefaultDict<K, V, F> {
+    /// a missing key is None, MapLike reads never insert
+    fn get(&self, key: &K) -> Option<V> {
+        DefaultDict::get(self, key).cloned()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.push_back(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        DefaultDict::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        DefaultDict::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        MapLike::iter(&self.dict)
+    }
+
+    fn for_each_entry<G: FnMut(&K, &V)>(&self, f: G) {
+        self.dict.for_each_entry(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_without_checks() {
+        let mut counts: DefaultDict<char, u32> = DefaultDict::new(|| 0);
+        for c in "hello".chars() {
+            *counts.entry(c) += 1;
+        }
+        assert_eq!(counts.keys(), &vec!['h', 'e', 'l', 'o']);
+        assert_eq!(counts.get(&'l'), Some(&2));
+        assert_eq!(counts.get_or_default(&'z'), 0);
+        assert!(!counts.contains_key(&'z'));
+        assert_eq!(counts.into_dictionary().values(), &vec![1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn capturing_factory() {
+        let start = 10;
+        let mut dict = DefaultDict::from_dictionary(Dictionary::from([("a", 1)]), move || start);
+        *dict.entry("b") += 1;
+        *dict.entry("a") += 1;
+        assert_eq!(dict.values(), &vec![2, 11]);
+        assert_eq!(MapLike::get(&dict, &"c"), None);
+    }
+}
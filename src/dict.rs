@@ -1,60 +1,234 @@
-use std::cmp::{PartialEq, PartialOrd};
-use std::collections::HashMap;
+use crate::audit::{DuplicateAudit, Resolution};
+use crate::changes::{ChangeEvent, ChangeLog};
+use crate::digest::TrackedDigest;
+use crate::error::{DictError, OccupiedError};
+use crate::format::{EntryWriter, FormatOptions};
+use crate::growth::GrowthStrategy;
+use crate::index::{BuildIndex, IndexBackend};
+use crate::key_limit::{KeyLimit, Truncate};
+use crate::observe::{Mutation, Observer};
+use std::cmp::{Ordering, PartialEq};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, TryReserveError};
+use std::error::Error;
 use std::fmt::{self, Display, Formatter};
-use std::hash::Hash;
-use std::iter::{IntoIterator, Iterator};
-use std::ops::{Add, Sub};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::iter::{Copied, FusedIterator, IntoIterator, Iterator, Rev, Zip};
+use std::ops::{Add, BitOr, BitOrAssign, Bound, RangeBounds, Sub};
 use std::slice::{Iter, IterMut};
 use std::vec::IntoIter;
 
+/// the most keys `Dictionary::mark_hot` keeps pinned
+pub const HOT_CAPACITY: usize = 8;
+
 /// An impelementation of Python style dict
 /// An ordered map that can be indexed
-#[derive(Debug)]
-pub struct Dictionary<K, V> {
+pub struct Dictionary<K, V, S: BuildIndex<K> = RandomState> {
     len: usize,
     capacity: usize,
     keys: Vec<K>,
-    key_map: HashMap<K, usize>,
+    key_map: S::Index,
     values: Vec<V>,
+    // creates the index for dictionaries derived from this one
+    index_builder: S,
+    // bumped on every mutation so views can detect a changed dictionary
+    version: u64,
+    // applied to every key on insert and lookup, set through DictionaryBuilder
+    canonicalize: Option<fn(K) -> K>,
+    // the version right after the last sort_by_keys, the keys are known to be
+    // in order while the version has not moved on
+    sorted_version: Option<u64>,
+    // records of inserts that hit an existing key, set through DictionaryBuilder
+    duplicate_audit: Option<DuplicateAudit<K>>,
+    // a few (key, position) pairs checked before the map on get, see mark_hot
+    hot: Vec<(K, usize)>,
+    // set by seal, structural mutations panic once it is
+    sealed: bool,
+    // kept up to date on push_back, set through track_digest
+    tracked_digest: Option<TrackedDigest<K, V>>,
+    // the last logged mutations, set through track_changes
+    changes: Option<ChangeLog<K, V>>,
+    // how FormatValue output is written, set through DictionaryBuilder
+    format_options: FormatOptions,
+    // shortens over-long keys and keeps their originals, set through DictionaryBuilder
+    key_limit: Option<KeyLimit<K, S::Index>>,
+    // how the containers grow when they run out of room, set through DictionaryBuilder
+    growth: GrowthStrategy,
+    // called after every reported mutation, set through observe
+    observer: Option<Observer<K, V>>,
+}
+
+/// How `Dictionary::update_from` resolves a key that is in both dictionaries
+#[derive(Debug)]
+pub enum MergePolicy<V> {
+    /// keep the value already in the dictionary
+    KeepExisting,
+    /// replace it with the incoming value, like `dict.update` in python
+    Overwrite,
+    /// merge the incoming value into the existing one
+    Combine(fn(&mut V, V)),
+}
+
+/// Error returned by `Dictionary::from_parts` when the columns can not form a Dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartsError {
+    /// the key and value columns have different lengths
+    LengthMismatch { keys: usize, values: usize },
+    /// the key at index `duplicate` is equal to the key at index `first`
+    DuplicateKey { first: usize, duplicate: usize },
+}
+
+impl Display for PartsError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PartsError::LengthMismatch { keys, values } => {
+                write!(f, "{} keys but {} values", keys, values)
+            }
+            PartsError::DuplicateKey { first, duplicate } => {
+                write!(
+                    f,
+                    "key at index {} duplicates key at index {}",
+                    duplicate, first
+                )
+            }
+        }
+    }
+}
+
+impl Error for PartsError {}
+
+/// Error returned by `Dictionary::unsealed` once the dictionary is sealed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sealed;
+
+impl Display for Sealed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "dictionary is sealed, keys can not be added, removed or reordered"
+        )
+    }
+}
+
+impl Error for Sealed {}
+
+// finishes a compact when dropped, the entry being looked at and the ones not yet
+// walked go back after the kept ones, then the index map is fixed up
+struct CompactGuard<'a, K: Eq + Clone, V: Clone, S: BuildIndex<K>> {
+    dict: &'a mut Dictionary<K, V, S>,
+    current: Option<(K, V)>,
+    keys: std::vec::IntoIter<K>,
+    values: std::vec::IntoIter<V>,
+}
+
+impl<'a, K: Eq + Clone, V: Clone, S: BuildIndex<K>> Drop for CompactGuard<'a, K, V, S> {
+    fn drop(&mut self) {
+        let dict = &mut *self.dict;
+        if let Some((key, value)) = self.current.take() {
+            dict.keys.push(key);
+            dict.values.push(value);
+        }
+        dict.keys.extend(&mut self.keys);
+        dict.values.extend(&mut self.values);
+        dict.len = dict.keys.len();
+        dict.recompute_map();
+        dict.bump_version();
+    }
+}
+
+/// Error returned by `Dictionary::rename_keys`, nothing is renamed when it fails
+/// renames are referred to by their position in the renames dictionary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// the rename at `duplicate` has the same new key as the rename at `first`
+    DuplicateTarget { first: usize, duplicate: usize },
+    /// the new key of the rename is a key that is not renamed away
+    TargetExists { rename: usize },
+    /// the dictionary is sealed
+    Sealed,
+}
+
+impl Display for RenameError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            RenameError::DuplicateTarget { first, duplicate } => {
+                write!(
+                    f,
+                    "rename {} has the same new key as rename {}",
+                    duplicate, first
+                )
+            }
+            RenameError::TargetExists { rename } => {
+                write!(f, "rename {} targets a key that is already in use", rename)
+            }
+            RenameError::Sealed => Display::fmt(&Sealed, f),
+        }
+    }
 }
 
-impl<K, V> Display for Dictionary<K, V>
+impl Error for RenameError {}
+
+impl<K, V, S: BuildIndex<K>> Display for Dictionary<K, V, S>
 where
     K: Display,
     V: Display,
 {
+    /// `{k: v, k2: v2}` on one line, or with `{:#}` one indented entry per line
+    /// keys and values whose text is empty, has surrounding whitespace, or holds
+    /// quotes, control characters or the delimiters `,:{}` are double quoted and escaped
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("name", "ada lovelace"), ("note", "a, b")]);
+    /// assert_eq!(dict.to_string(), r#"{name: ada lovelace, note: "a, b"}"#);
+    /// assert_eq!(
+    ///     format!("{:#}", dict),
+    ///     "{\n    name: ada lovelace,\n    note: \"a, b\",\n}"
+    /// );
+    /// ```
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let mut output = String::new();
-        output.push_str("{\n");
+        let mut out = EntryWriter::new(f)?;
         for (key, val) in self.keys.iter().zip(&self.values) {
-            output.push_str(&format!("{}: {}\n", key, val));
+            out.entry(key, val)?;
         }
-        output.push_str("}");
-        write!(f, "{}", output)
+        out.finish()
     }
 }
 
-impl<K, V> Clone for Dictionary<K, V>
+impl<K, V, S> Clone for Dictionary<K, V, S>
 where
-    K: Copy + Clone,
-    V: Copy + Clone,
+    K: Clone,
+    V: Clone,
+    S: BuildIndex<K> + Clone,
+    S::Index: Clone,
 {
     fn clone(&self) -> Self {
         Dictionary {
-            len: self.len.clone(),
-            capacity: self.capacity.clone(),
+            len: self.len,
+            capacity: self.capacity,
             keys: self.keys.clone(),
             key_map: self.key_map.clone(),
             values: self.values.clone(),
+            index_builder: self.index_builder.clone(),
+            version: self.version,
+            canonicalize: self.canonicalize,
+            sorted_version: self.sorted_version,
+            duplicate_audit: self.duplicate_audit.clone(),
+            hot: self.hot.clone(),
+            sealed: self.sealed,
+            tracked_digest: self.tracked_digest.clone(),
+            changes: self.changes.clone(),
+            format_options: self.format_options,
+            key_limit: self.key_limit.clone(),
+            growth: self.growth,
+            observer: None,
         }
     }
 }
 
-impl<
-        K: PartialOrd + PartialEq + Hash + Eq + Clone + Ord + Copy,
-        V: Clone + Ord + PartialEq + PartialOrd + Eq,
-    > PartialEq for Dictionary<K, V>
-{
+impl<K: PartialEq, V: PartialEq, S: BuildIndex<K>> PartialEq for Dictionary<K, V, S> {
+    /// the same pairs in the same order, use eq_ignore_order to compare like a python dict
     fn eq(&self, rhs: &Self) -> bool {
         if self.values != rhs.values {
             return false;
@@ -64,53 +238,95 @@ impl<
             return false;
         }
 
-        if self.key_map != rhs.key_map {
-            return false;
-        }
+        // the index is derived from the keys, equal keys mean an equal index
         true
     }
+}
+
+impl<K: Eq, V: Eq, S: BuildIndex<K>> Eq for Dictionary<K, V, S> {}
+
+impl<K: Hash, V: Hash, S: BuildIndex<K>> Hash for Dictionary<K, V, S> {
+    /// hashes the pairs in order, consistent with Eq
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.keys.len());
+        for (key, value) in self.keys.iter().zip(&self.values) {
+            key.hash(state);
+            value.hash(state);
+        }
+    }
+}
 
-    fn ne(&self, rhs: &Self) -> bool {
-        !self.eq(rhs)
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K> + Default> Default for Dictionary<K, V, S> {
+    /// an empty dictionary with the default index builder, like `new`
+    fn default() -> Self {
+        Dictionary::with_index(S::default())
     }
 }
 
-impl<
-        K: PartialOrd + PartialEq + Hash + Eq + Clone + Ord + Copy,
-        V: Clone + Ord + PartialEq + PartialOrd + Eq,
-    > Add<Dictionary<K, V>> for Dictionary<K, V>
-{
-    type Output = Dictionary<K, V>;
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Add<Dictionary<K, V, S>> for Dictionary<K, V, S> {
+    type Output = Dictionary<K, V, S>;
     fn add(self, rhs: Self) -> Self::Output {
         let len = self.values.len() + rhs.values.len();
         let mut values: Vec<V> = Vec::with_capacity(len);
         let mut keys: Vec<K> = Vec::with_capacity(len);
         values.extend_from_slice(&self.values);
         values.extend_from_slice(&rhs.values);
-        keys.extend(&self.keys);
-        keys.extend(&rhs.keys);
+        keys.extend_from_slice(&self.keys);
+        keys.extend_from_slice(&rhs.keys);
 
-        let mut key_map: HashMap<K, usize> = HashMap::with_capacity(len);
+        let mut key_map = self.index_builder.build_index(len);
         for (ind, key) in keys.iter().enumerate() {
-            key_map.insert(*key, ind);
+            key_map.insert(key.clone(), ind);
+        }
+        let mut key_limit = self.key_limit;
+        if let (Some(limit), Some(rhs_limit)) = (&mut key_limit, &rhs.key_limit) {
+            limit.copy_originals(rhs_limit, rhs.keys.iter());
         }
 
         Dictionary {
             values,
             keys,
             key_map,
+            index_builder: self.index_builder,
             len,
             capacity: len,
+            version: 0,
+            canonicalize: self.canonicalize,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: self.format_options,
+            key_limit,
+            growth: self.growth,
+            observer: None,
         }
     }
 }
 
-impl<
-        K: PartialOrd + PartialEq + Hash + Eq + Clone + Ord + Copy,
-        V: Clone + Ord + PartialEq + PartialOrd + Eq,
-    > Sub<Dictionary<K, V>> for Dictionary<K, V>
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> BitOrAssign<Dictionary<K, V, S>>
+    for Dictionary<K, V, S>
 {
-    type Output = Dictionary<K, V>;
+    /// update self with rhs, values from rhs win
+    fn bitor_assign(&mut self, rhs: Dictionary<K, V, S>) {
+        self.update_from(rhs, MergePolicy::Overwrite);
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> BitOr<Dictionary<K, V, S>> for Dictionary<K, V, S> {
+    type Output = Dictionary<K, V, S>;
+    /// the keys of self followed by the new keys of rhs, values from rhs win
+    /// like `d1 | d2` in python
+    fn bitor(mut self, rhs: Dictionary<K, V, S>) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Sub<Dictionary<K, V, S>> for Dictionary<K, V, S> {
+    type Output = Dictionary<K, V, S>;
     fn sub(self, rhs: Self) -> Self::Output {
         // thoughts here
         // a lookup op on the keys vec would be O(N) for every lookup
@@ -129,16 +345,16 @@ impl<
         let capacity = len;
         let mut keys = Vec::with_capacity(len);
         let mut values = Vec::with_capacity(len);
-        let mut key_map = HashMap::with_capacity(len);
+        let mut key_map = self.index_builder.build_index(len);
         let mut ind = 0;
         for key in &self.keys {
             if rhs.key_map.contains_key(key) {
                 len -= 1;
             } else {
-                let val_ind = self.key_map[&key].clone();
-                keys.push(*key);
+                let val_ind = *self.key_map.get(key).unwrap();
+                keys.push(key.clone());
                 values.push(self.values[val_ind].clone());
-                key_map.insert(*key, ind);
+                key_map.insert(key.clone(), ind);
                 ind += 1;
             }
         }
@@ -148,46 +364,407 @@ impl<
             len,
             capacity,
             key_map,
+            index_builder: self.index_builder,
+            version: 0,
+            canonicalize: self.canonicalize,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: self.format_options,
+            key_limit: self.key_limit,
+            growth: self.growth,
+            observer: None,
         }
     }
 }
 
-impl<
-        K: PartialOrd + PartialEq + Hash + Eq + Clone + Ord + Copy,
-        V: Clone + Ord + PartialEq + PartialOrd + Eq,
-    > Dictionary<K, V>
-{
+// the column accessors need no bounds so formatting can borrow them
+impl<K, V, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// get a reference to the colleciton of values in the dictionary
+    /// prefer iter_values, the Vec is the storage and may stop being one
+    pub fn values(&self) -> &Vec<V> {
+        &self.values
+    }
+
+    /// get a reference to the collection of keys in the dictionary
+    /// prefer iter_keys, the Vec is the storage and may stop being one
+    pub fn keys(&self) -> &Vec<K> {
+        &self.keys
+    }
+
+    /// iterate over the keys in order
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("b", 2), ("a", 1)]);
+    /// assert_eq!(dict.iter_keys().collect::<Vec<_>>(), vec![&"b", &"a"]);
+    /// assert_eq!(dict.iter_values().sum::<i32>(), 3);
+    /// assert_eq!(dict.clone().into_keys().collect::<Vec<_>>(), vec!["b", "a"]);
+    /// assert_eq!(dict.into_values().last(), Some(1));
+    /// ```
+    pub fn iter_keys(&self) -> Keys<'_, K> {
+        Keys {
+            inner: self.keys.iter(),
+        }
+    }
+
+    /// iterate over the values in order
+    pub fn iter_values(&self) -> Values<'_, V> {
+        Values {
+            inner: self.values.iter(),
+        }
+    }
+
+    /// take the keys in order, dropping the values
+    pub fn into_keys(self) -> IntoKeys<K> {
+        IntoKeys {
+            inner: self.keys.into_iter(),
+        }
+    }
+
+    /// take the values in order, dropping the keys
+    pub fn into_values(self) -> IntoValues<V> {
+        IntoValues {
+            inner: self.values.into_iter(),
+        }
+    }
+
+    /// the single line Display form, `{k: v, k2: v2}`, safe to embed in a log line
+    pub fn to_string_compact(&self) -> String
+    where
+        K: Display,
+        V: Display,
+    {
+        format!("{}", self)
+    }
+
+    /// the alternate Display form with one indented entry per line
+    pub fn to_string_pretty(&self) -> String
+    where
+        K: Display,
+        V: Display,
+    {
+        format!("{:#}", self)
+    }
+
+    /// the options `formatted` and CSV export write values with
+    pub fn format_options(&self) -> &FormatOptions {
+        &self.format_options
+    }
+
+    pub fn set_format_options(&mut self, options: FormatOptions) {
+        self.format_options = options;
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
     /// A new instances of a Dictionary with default capacity.
+    /// This makes no heap allocation, the columns and the index allocate on the
+    /// first insert, so structs can embed many usually empty dictionaries cheaply.
+    /// RandomState is seeded at runtime so this can not be a const fn,
+    /// see `with_default_hasher` for a dictionary that can be built in a static
     pub fn new() -> Dictionary<K, V> {
+        Dictionary::with_index(RandomState::new())
+    }
+
+    /// A new instance of a Dictionary with a reserved capacity.
+    /// Allows for the need to not dynamically resize when the size is somewhat known ahead of time
+    pub fn with_capacity(size: usize) -> Dictionary<K, V> {
+        Dictionary::with_capacity_and_index(size, RandomState::new())
+    }
+
+    /// build a dictionary from keys that all map to the same value
+    /// like `dict.fromkeys(keys, value)` in python
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let seen = Dictionary::from_keys(vec!["a", "b", "a"], false);
+    /// assert_eq!(seen.keys(), &vec!["a", "b"]);
+    /// assert_eq!(seen.get("b"), Some(false));
+    /// ```
+    pub fn from_keys<I: IntoIterator<Item = K>>(keys: I, value: V) -> Dictionary<K, V> {
+        Dictionary::from_keys_with(keys, |_| value.clone())
+    }
+
+    /// build a dictionary from keys, computing each value from its key
+    /// the Vecs and map are sized up front from the iterator
+    pub fn from_keys_with<I, F>(keys: I, mut value: F) -> Dictionary<K, V>
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(&K) -> V,
+    {
+        let keys = keys.into_iter();
+        let mut dict = Dictionary::with_capacity(keys.size_hint().0);
+        for key in keys {
+            let value = value(&key);
+            dict.push_back(key, value);
+        }
+        dict
+    }
+
+    /// rebuild a Dictionary from a key column and a value column
+    /// the columns must be the same length and the keys must be unique
+    /// the existing Vec allocations are reused, only the index map is rebuilt
+    pub fn from_parts(keys: Vec<K>, values: Vec<V>) -> Result<Dictionary<K, V>, PartsError> {
+        if keys.len() != values.len() {
+            return Err(PartsError::LengthMismatch {
+                keys: keys.len(),
+                values: values.len(),
+            });
+        }
+        let mut key_map: HashMap<K, usize> = HashMap::with_capacity(keys.capacity());
+        for (i, key) in keys.iter().enumerate() {
+            if let Some(first) = key_map.insert(key.clone(), i) {
+                return Err(PartsError::DuplicateKey {
+                    first,
+                    duplicate: i,
+                });
+            }
+        }
+        Ok(Dictionary {
+            len: keys.len(),
+            capacity: keys.len(),
+            keys,
+            key_map,
+            index_builder: RandomState::new(),
+            values,
+            version: 0,
+            canonicalize: None,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: FormatOptions::default(),
+            key_limit: None,
+            growth: GrowthStrategy::Doubling,
+            observer: None,
+        })
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
+    /// build a dictionary from entries already sorted by key, in one pass
+    /// the order is trusted rather than sorted again, the Vecs and the index are
+    /// allocated once and the dictionary starts out `is_sorted`
+    /// # Panics
+    /// panics if a key is repeated, and in debug builds if the keys are not ascending
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from_sorted_entries(vec![(1, "a"), (5, "b"), (9, "c")]);
+    /// assert!(dict.is_sorted());
+    /// assert_eq!(dict.get(5), Some("b"));
+    ///
+    /// let merged = Dictionary::from_vec(vec![("x", 1), ("y", 2), ("x", 3)]);
+    /// assert_eq!(merged.keys(), &vec!["x", "y"]);
+    /// assert_eq!(merged.get("x"), Some(3));
+    /// ```
+    pub fn from_sorted_entries(entries: Vec<(K, V)>) -> Dictionary<K, V>
+    where
+        K: Ord,
+    {
+        debug_assert!(
+            entries.windows(2).all(|pair| pair[0].0 <= pair[1].0),
+            "entries are not sorted by key"
+        );
+        let mut dict = Dictionary::with_capacity(entries.len());
+        for (i, (key, value)) in entries.into_iter().enumerate() {
+            let previous = dict.key_map.insert(key.clone(), i);
+            assert!(previous.is_none(), "duplicate key in sorted entries");
+            dict.keys.push(key);
+            dict.values.push(value);
+        }
+        dict.len = dict.keys.len();
+        dict.sorted_version = Some(dict.version);
+        dict
+    }
+
+    /// build a dictionary from entries in one pass, allocating once up front
+    /// a repeated key keeps its first position and takes the last value, like push_back
+    pub fn from_vec(entries: Vec<(K, V)>) -> Dictionary<K, V> {
+        let mut dict = Dictionary::with_capacity(entries.len());
+        for (key, value) in entries {
+            match dict.key_map.get(&key) {
+                Some(&i) => dict.values[i] = value,
+                None => {
+                    dict.key_map.insert(key.clone(), dict.keys.len());
+                    dict.keys.push(key);
+                    dict.values.push(value);
+                }
+            }
+        }
+        dict.len = dict.keys.len();
+        dict
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildHasher + Clone> Dictionary<K, V, S> {
+    /// A new Dictionary that hashes keys with the given hasher
+    /// use this to plug in a faster hasher, or a fixed seed one for deterministic tests
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut dict: Dictionary<&str, i32, RandomState> = Dictionary::with_hasher(RandomState::new());
+    /// dict.push_back("a", 1);
+    /// assert_eq!(dict.get("a"), Some(1));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Dictionary<K, V, S> {
+        Dictionary::with_index(hasher)
+    }
+
+    /// A new Dictionary with a reserved capacity that hashes keys with the given hasher
+    pub fn with_capacity_and_hasher(size: usize, hasher: S) -> Dictionary<K, V, S> {
+        Dictionary::with_capacity_and_index(size, hasher)
+    }
+
+    /// the hasher used for the key index
+    pub fn hasher(&self) -> &S {
+        &self.index_builder
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, H: Hasher + Default> Dictionary<K, V, BuildHasherDefault<H>> {
+    /// A new Dictionary hashing keys with H, as a const fn
+    /// no allocation is made and nothing is seeded at runtime, so the dictionary
+    /// can live in a static
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use std::hash::{BuildHasherDefault, DefaultHasher};
+    /// use std::sync::Mutex;
+    ///
+    /// static NAMES: Mutex<Dictionary<u32, &str, BuildHasherDefault<DefaultHasher>>> =
+    ///     Mutex::new(Dictionary::with_default_hasher());
+    ///
+    /// NAMES.lock().unwrap().push_back(7, "seven");
+    /// assert_eq!(NAMES.lock().unwrap().get(7), Some("seven"));
+    /// ```
+    pub const fn with_default_hasher() -> Dictionary<K, V, BuildHasherDefault<H>> {
         Dictionary {
             len: 0,
             capacity: 0,
             keys: Vec::new(),
-            key_map: HashMap::new(),
+            key_map: HashMap::with_hasher(BuildHasherDefault::new()),
             values: Vec::new(),
+            index_builder: BuildHasherDefault::new(),
+            version: 0,
+            canonicalize: None,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: FormatOptions::new(),
+            key_limit: None,
+            growth: GrowthStrategy::Doubling,
+            observer: None,
         }
     }
+}
 
-    /// A new instance of a Dictionary with a reserved capacity.
-    /// Allows for the need to not dynamically resize when the size is somewhat known ahead of time
-    pub fn with_capacity(size: usize) -> Dictionary<K, V> {
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// A new Dictionary with the index built by index_builder
+    /// see `BuildIndex`, any BuildHasher gives a HashMap index and `BTreeIndex` a BTreeMap one
+    pub fn with_index(index_builder: S) -> Dictionary<K, V, S> {
+        Dictionary::with_capacity_and_index(0, index_builder)
+    }
+
+    /// A new Dictionary with a reserved capacity and the index built by index_builder
+    pub fn with_capacity_and_index(size: usize, index_builder: S) -> Dictionary<K, V, S> {
         Dictionary {
             len: 0,
             capacity: size,
             keys: Vec::with_capacity(size),
-            key_map: HashMap::with_capacity(size),
+            key_map: index_builder.build_index(size),
             values: Vec::with_capacity(size),
+            index_builder,
+            version: 0,
+            canonicalize: None,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: FormatOptions::default(),
+            key_limit: None,
+            growth: GrowthStrategy::Doubling,
+            observer: None,
         }
     }
 
+    /// the builder used for the key index
+    pub fn index_builder(&self) -> &S {
+        &self.index_builder
+    }
+
+    // empty the dictionary and drop every setting, seal and observer, leaving it as
+    // with_capacity_and_index would but with the Vec and index allocations kept
+    pub(crate) fn recycle(&mut self)
+    where
+        S: Clone,
+    {
+        let fresh = Dictionary::with_index(self.index_builder.clone());
+        let mut old = std::mem::replace(self, fresh);
+        old.keys.clear();
+        old.values.clear();
+        old.key_map.clear();
+        self.capacity = old.capacity;
+        self.keys = old.keys;
+        self.values = old.values;
+        self.key_map = old.key_map;
+    }
+
     /// Add a key value pair to the dictionary.
     /// This will be pushed to the end of the dictionary.
     /// This will be resized when the dictionary is at full capacity.
+    /// If the key is already in the dictionary the value is updated in place,
+    /// the key keeps its position and the previous value is returned, like `d[key] = value` in python.
+    /// Returns None when a new key was added.
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::<i32, &str>::new();
+    /// assert_eq!(dict.push_back(1, "a"), None);
+    /// assert_eq!(dict.push_back(2, "b"), None);
+    /// assert_eq!(dict.push_back(1, "c"), Some("a"));
+    /// assert_eq!(dict.keys(), &vec![1, 2]);
+    /// assert_eq!(dict.values(), &vec!["c", "b"]);
+    /// ```
     pub fn push_back(&mut self, key: K, value: V) -> Option<V> {
+        let key = self.canonical_new(key);
+        self.push_back_canonical(key, value)
+    }
+
+    // push_back of a key that has already been through canonical_new
+    fn push_back_canonical(&mut self, key: K, value: V) -> Option<V> {
         // check to see if the key is already in the dictionary
-        if self.has_key(&key) {
-            return None;
+        if let Some(&i) = self.key_map.get(&key) {
+            let previous = std::mem::replace(&mut self.values[i], value);
+            self.record_duplicate(key, Resolution::Overwritten);
+            self.bump_version();
+            self.record_change(|dict| ChangeEvent::PushBack {
+                key: dict.keys[i].clone(),
+                value: dict.values[i].clone(),
+            });
+            self.notify_update(i, &previous);
+            return Some(previous);
         }
+        self.assert_unsealed();
         // check to see if dict is at capacity
         if self.len == self.capacity {
             self.update_capacity();
@@ -197,29 +774,123 @@ impl<
         // new len - 1 -> new index
         self.key_map.insert(key, self.len);
         self.len += 1;
-        self.values.push(value.clone());
-        Some(value)
+        self.values.push(value);
+        self.bump_version();
+        if let Some(tracked) = &mut self.tracked_digest {
+            tracked.push(
+                &self.keys[self.len - 1],
+                &self.values[self.len - 1],
+                self.version,
+            );
+        }
+        self.record_change(|dict| ChangeEvent::PushBack {
+            key: dict.keys[dict.len - 1].clone(),
+            value: dict.values[dict.len - 1].clone(),
+        });
+        self.notify_insert(self.len - 1);
+        None
+    }
+
+    /// add a key value pair at the front of the dictionary
+    /// like push_back, a key that is already in the dictionary keeps its position,
+    /// its value is replaced and the previous value returned
+    /// the entries shift up in one move and the index is updated without rehashing,
+    /// but this is still O(n), prefer push_back when order allows
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(2, "b")]);
+    /// assert_eq!(dict.push_front(1, "a"), None);
+    /// assert_eq!(dict.front(), Some((&1, &"a")));
+    /// assert_eq!(dict.back(), Some((&2, &"b")));
+    /// ```
+    pub fn push_front(&mut self, key: K, value: V) -> Option<V> {
+        let key = self.canonical_new(key);
+        if let Some(&i) = self.key_map.get(&key) {
+            let previous = std::mem::replace(&mut self.values[i], value);
+            self.record_duplicate(key, Resolution::Overwritten);
+            self.bump_version();
+            self.record_change(|dict| ChangeEvent::PushFront {
+                key: dict.keys[i].clone(),
+                value: dict.values[i].clone(),
+            });
+            self.notify_update(i, &previous);
+            return Some(previous);
+        }
+        self.assert_unsealed();
+        if self.len == self.capacity {
+            self.update_capacity();
+        }
+        self.key_map.for_each_position(|i| *i += 1);
+        self.keys.insert(0, key.clone());
+        self.values.insert(0, value);
+        self.key_map.insert(key, 0);
+        self.len += 1;
+        self.bump_version();
+        self.record_change(|dict| ChangeEvent::PushFront {
+            key: dict.keys[0].clone(),
+            value: dict.values[0].clone(),
+        });
+        self.notify_insert(0);
+        None
+    }
+
+    /// add a new key at the back, failing if the key is already in the dictionary
+    /// or the dictionary is sealed
+    /// unlike push_back nothing is overwritten, an occupied key hands the key and value back
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::error::DictError;
+    ///
+    /// let mut dict = Dictionary::new();
+    /// assert!(dict.try_push_back("id", 1).is_ok());
+    /// let Err(DictError::Occupied(occupied)) = dict.try_push_back("id", 2) else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(occupied.value, 2);
+    /// assert_eq!(dict.get("id"), Some(1));
+    /// ```
+    pub fn try_push_back(&mut self, key: K, value: V) -> Result<(), DictError<K, V>> {
+        self.check_unsealed()?;
+        let key = self.canonical_new(key);
+        if let Some(&i) = self.key_map.get(&key) {
+            return Err(OccupiedError {
+                key,
+                value,
+                index: i,
+            }
+            .into());
+        }
+        self.push_back_canonical(key, value);
+        Ok(())
     }
 
     fn update_capacity(&mut self) {
-        let mut temp = self.capacity;
-        let mut n = 0;
-        while temp > 1 {
-            temp = temp >> 1;
-            n += 1
-        }
-        let new_capacity = 2 << n;
-        let additional = new_capacity - self.capacity;
-        self.values.reserve(additional);
-        self.keys.reserve(additional);
+        let new_capacity = self.growth.next_capacity(self.capacity, self.len + 1);
+        self.grow_to(new_capacity);
+    }
+
+    // grow the keys, values and index to hold new_capacity entries
+    fn grow_to(&mut self, new_capacity: usize) {
+        let additional = new_capacity - self.len;
+        self.values.reserve_exact(additional);
+        self.keys.reserve_exact(additional);
         self.key_map.reserve(additional);
         self.capacity = new_capacity;
     }
 
     /// remove an element from the dictionary by key name
-    /// This will be worst case an O(3n) operation
+    /// Only the entries after the removed one are shifted and re-indexed,
+    /// so removing from the back is cheap and removing from the front is O(n).
+    /// Use remove_many or retain to remove several entries in a single pass
     /// if the key is in the dictionary, the value with be returned, otherwise None will be
     /// returned
+    /// # Panics
+    /// panics if the dictionary is sealed
     /// # Example
     /// ```
     /// use rust_dict::dict::Dictionary;
@@ -232,19 +903,22 @@ impl<
     /// assert_eq!(dict.get(2).unwrap(), String::from("my_string2"));
     /// ```
     pub fn remove(&mut self, key: K) -> Option<V> {
+        self.assert_unsealed();
+        let key = self.canonical(key);
         // get index from map
         // remove index keys and values
-        // adjust all indexs > than index
+        // adjust the indexes of the keys that shifted down
         match self.key_map.remove(&key) {
             Some(index) => {
                 let value = self.values.remove(index);
                 let _ = self.keys.remove(index);
-                for (_, i) in self.key_map.iter_mut() {
-                    if *i > index {
-                        *i -= 1;
-                    }
+                for key in &self.keys[index..] {
+                    *self.key_map.get_mut(key).unwrap() -= 1;
                 }
                 self.len -= 1;
+                self.bump_version();
+                self.record_change(|_| ChangeEvent::Remove { key: key.clone() });
+                self.notify_remove(index, &key, &value);
 
                 Some(value)
             }
@@ -252,112 +926,2043 @@ impl<
         }
     }
 
-    /// Insert values to a particular index
-    pub fn insert(&mut self, key: K, value: V, index: usize) -> Option<V> {
-        if self.has_key(&key) {
-            return None;
-        }
-        // insert key and value at i
-        // then push_back the index map
-        // increment all > i
-        self.values.insert(index, value.clone());
-        self.keys.insert(index, key);
+    /// remove and return the last key value pair, like `dict.popitem()` in python
+    /// this is O(1)
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        self.assert_unsealed();
+        let key = self.keys.pop()?;
+        let value = self.values.pop().unwrap();
+        self.key_map.remove(&key);
+        self.len -= 1;
+        self.bump_version();
+        self.record_change(|_| ChangeEvent::Remove { key: key.clone() });
+        self.notify_remove(self.len, &key, &value);
+        Some((key, value))
+    }
 
-        for key in &self.keys[index + 1..] {
-            let i = self.key_map.get_mut(&key).unwrap();
-            *i += 1;
+    /// remove and return the first key value pair
+    /// every remaining entry shifts down, so this is O(n)
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c")]);
+    /// assert_eq!(dict.pop_front(), Some((1, "a")));
+    /// assert_eq!(dict.pop_back(), Some((3, "c")));
+    /// assert_eq!(dict.keys(), &vec![2]);
+    /// ```
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        self.assert_unsealed();
+        if self.len == 0 {
+            return None;
         }
-        Some(value)
+        let key = self.keys.remove(0);
+        let value = self.values.remove(0);
+        self.key_map.remove(&key);
+        // every remaining position moves down by one, no need to hash the keys
+        self.key_map.for_each_position(|i| *i -= 1);
+        self.len -= 1;
+        self.bump_version();
+        self.record_change(|_| ChangeEvent::Remove { key: key.clone() });
+        self.notify_remove(0, &key, &value);
+        Some((key, value))
     }
 
-    /// get a reference to the colleciton of values in the dictionary
-    pub fn values(&self) -> &Vec<V> {
-        &self.values
+    /// the first key value pair
+    pub fn front(&self) -> Option<(&K, &V)> {
+        Some((self.keys.first()?, self.values.first()?))
     }
 
-    /// get a reference to the collection of keys in the dictionary
-    pub fn keys(&self) -> &Vec<K> {
-        &self.keys
+    /// the last key value pair
+    pub fn back(&self) -> Option<(&K, &V)> {
+        Some((self.keys.last()?, self.values.last()?))
     }
 
-    /// get value by key
-    /// returns an `Option<V>`
-    pub fn get(&self, key: K) -> Option<V> {
-        // get by key
-        match self.key_map.get(&key) {
-            Some(i) => Some(self.values[*i].clone()),
-            None => None,
+    /// remove every entry, keeping the allocated capacity for reuse
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn clear(&mut self) {
+        self.assert_unsealed();
+        self.keys.clear();
+        self.values.clear();
+        self.key_map.clear();
+        if let Some(limit) = &mut self.key_limit {
+            limit.clear();
         }
+        self.len = 0;
+        self.bump_version();
+        self.record_change(|_| ChangeEvent::Clear);
+        self.notify(|observe, _| observe(Mutation::Clear));
     }
 
-    /// get a value by index
-    /// This method takes advantage of the ordered nature of the data structure
-    pub fn get_index(&self, i: usize) -> Option<V> {
-        if i >= self.len {
-            return None;
+    /// keep the first n entries and drop the rest
+    /// does nothing if the dictionary has n or fewer entries
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c")]);
+    /// dict.truncate(1);
+    /// assert_eq!(dict.keys(), &vec![1]);
+    /// assert_eq!(dict.get(2), None);
+    /// ```
+    pub fn truncate(&mut self, n: usize) {
+        self.assert_unsealed();
+        if n >= self.len {
+            return;
         }
-        Some(self.values[i].clone())
+        self.notify_removed_tail(n);
+        for key in &self.keys[n..] {
+            self.key_map.remove(key);
+        }
+        self.keys.truncate(n);
+        self.values.truncate(n);
+        self.len = n;
+        self.bump_version();
     }
 
-    /// get with a default
-    /// parallel to dict.get(key, default) in python
-    /// if no default is provided, None will be returned
-    pub fn get_or(&self, key: K, default: V) -> V {
-        match self.key_map.get(&key) {
-            Some(i) => self.values[*i].clone(),
-            None => default,
+    /// split the dictionary in two at a position, like `Vec::split_off`
+    /// self keeps the entries before at and its capacity, the returned dictionary
+    /// holds the rest in order, only the moved keys are re-indexed
+    /// # Panics
+    /// panics if at is greater than the length
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut batch = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let rest = batch.split_off(1);
+    /// assert_eq!(batch.keys(), &vec!["a"]);
+    /// assert_eq!(rest.keys(), &vec!["b", "c"]);
+    /// assert_eq!(rest.index_of(&"c"), Some(1));
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+    {
+        assert!(at <= self.len, "split index out of bounds");
+        self.assert_unsealed();
+        self.notify_removed_tail(at);
+        let mut rest = Dictionary::with_capacity_and_index(0, self.index_builder.clone());
+        rest.key_map.reserve(self.len - at);
+        rest.keys = self.keys.split_off(at);
+        rest.values = self.values.split_off(at);
+        for (i, key) in rest.keys.iter().enumerate() {
+            self.key_map.remove(key);
+            rest.key_map.insert(key.clone(), i);
+        }
+        rest.len = rest.keys.len();
+        rest.capacity = rest.keys.capacity();
+        rest.canonicalize = self.canonicalize;
+        rest.growth = self.growth;
+        if let Some(limit) = &self.key_limit {
+            let mut rest_limit = limit.empty_like(
+                self.index_builder.build_index(0),
+                self.index_builder.build_index(0),
+            );
+            rest_limit.copy_originals(limit, rest.keys.iter());
+            rest.key_limit = Some(rest_limit);
         }
+        self.len = at;
+        self.bump_version();
+        rest
     }
 
-    /// the number of key value pairs in the dictionary
-    pub fn len(&self) -> usize {
-        self.len
+    /// move every entry of other onto the back of self in order, leaving other empty
+    /// capacity is reserved once up front, a key already in self keeps its position
+    /// and takes the value from other, like push_back. other keeps its capacity
+    /// # Panics
+    /// panics if either dictionary is sealed, before anything is moved
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut queue = Dictionary::from([("a", 1), ("b", 2)]);
+    /// let mut incoming = Dictionary::from([("c", 3), ("a", 10)]);
+    /// queue.append(&mut incoming);
+    /// assert_eq!(queue.keys(), &vec!["a", "b", "c"]);
+    /// assert_eq!(queue.get("a"), Some(10));
+    /// assert!(incoming.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut Dictionary<K, V, S>) {
+        self.assert_unsealed();
+        other.assert_unsealed();
+        // the drains report an exact size, so extend reserves for all of them at once
+        self.extend(other.keys.drain(..).zip(other.values.drain(..)));
+        other.key_map.clear();
+        other.len = 0;
+        other.bump_version();
+        other.notify(|observe, _| observe(Mutation::Clear));
     }
 
-    /// get the current capacity of the dictionary
-    /// the number of items the dictionary can currently hold
+    /// release unused capacity in the Vecs and the index map
+    pub fn shrink_to_fit(&mut self) {
+        self.keys.shrink_to_fit();
+        self.values.shrink_to_fit();
+        self.key_map.shrink_to_fit();
+        self.capacity = self.len;
+    }
+
+    /// release unused capacity down to at least min_capacity entries, like `Vec::shrink_to`
+    /// the capacity is unchanged if it is already below min_capacity
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.keys.shrink_to(min_capacity);
+        self.values.shrink_to(min_capacity);
+        self.key_map.shrink_to(min_capacity);
+        self.capacity = self.capacity.min(min_capacity.max(self.len));
+    }
+
+    /// remove several keys at once, keeping the order of the remaining entries
+    /// the removed pairs are returned in the order they were in the dictionary,
+    /// keys that are not in the dictionary are ignored
+    /// this is a single O(n) compaction no matter how many keys are removed
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+    /// assert_eq!(dict.remove_many([3, 1, 7]), vec![(1, "a"), (3, "c")]);
+    /// assert_eq!(dict.keys(), &vec![2, 4]);
+    /// ```
+    pub fn remove_many<I: IntoIterator<Item = K>>(&mut self, keys: I) -> Vec<(K, V)> {
+        let mut marked = vec![false; self.len];
+        let mut any = false;
+        for key in keys {
+            if let Some(&i) = self.key_map.get(&self.canonical(key)) {
+                marked[i] = true;
+                any = true;
+            }
+        }
+        let mut removed = Vec::new();
+        if any {
+            self.compact(
+                |i, _, _| !marked[i],
+                |key, value| removed.push((key, value)),
+            );
+        }
+        removed
+    }
+
+    /// keep only the entries the predicate returns true for, in order
+    /// the predicate can also modify the values it keeps
+    /// this is a single O(n) pass with one index map rebuild at the end
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30)]);
+    /// dict.retain(|k, _| k % 2 == 1);
+    /// assert_eq!(dict.keys(), &vec![1, 3]);
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        self.compact(|_, key, value| keep(key, value), |_, _| {});
+    }
+
+    /// a new dictionary of the entries the predicate returns true for, in order
+    /// like `{k: v for k, v in d.items() if ...}` in python, self is left as is
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let ports = Dictionary::from([("http", 80), ("dev", 8080), ("https", 443)]);
+    /// let privileged = ports.filtered(|_, port| *port < 1024);
+    /// assert_eq!(privileged.keys(), &vec!["http", "https"]);
+    ///
+    /// let (low, high) = ports.partition(|_, port| *port < 1024);
+    /// assert_eq!(low, privileged);
+    /// assert_eq!(high.keys(), &vec!["dev"]);
+    /// ```
+    pub fn filtered<F: FnMut(&K, &V) -> bool>(&self, mut keep: F) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+    {
+        let mut kept = Dictionary::with_index(self.index_builder.clone());
+        kept.canonicalize = self.canonicalize;
+        for (key, value) in self.keys.iter().zip(&self.values) {
+            if keep(key, value) {
+                kept.push_back(key.clone(), value.clone());
+            }
+        }
+        kept
+    }
+
+    /// split the entries into those the predicate returns true for and the rest,
+    /// both in order, self is left as is
+    pub fn partition<F: FnMut(&K, &V) -> bool>(
+        &self,
+        mut pred: F,
+    ) -> (Dictionary<K, V, S>, Dictionary<K, V, S>)
+    where
+        S: Clone,
+    {
+        let mut matched = Dictionary::with_index(self.index_builder.clone());
+        let mut rest = Dictionary::with_index(self.index_builder.clone());
+        matched.canonicalize = self.canonicalize;
+        rest.canonicalize = self.canonicalize;
+        for (key, value) in self.keys.iter().zip(&self.values) {
+            let side = if pred(key, value) {
+                &mut matched
+            } else {
+                &mut rest
+            };
+            side.push_back(key.clone(), value.clone());
+        }
+        (matched, rest)
+    }
+
+    /// remove the entries in a range of positions, returning them as owned pairs in order
+    /// the entries after the range shift down, the index map is fixed up once
+    /// the range is removed right away, even if the returned iterator is not consumed
+    /// # Panics
+    /// panics if the range is out of bounds, like `Vec::drain`
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+    /// let drained: Vec<(i32, &str)> = dict.drain(1..3).collect();
+    /// assert_eq!(drained, vec![(2, "b"), (3, "c")]);
+    /// assert_eq!(dict.keys(), &vec![1, 4]);
+    /// assert_eq!(dict.drain(..).count(), 2);
+    /// assert!(dict.is_empty());
+    /// ```
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> DictIntoIter<K, V> {
+        self.assert_unsealed();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let keys: Vec<K> = self
+            .keys
+            .drain((range.start_bound().cloned(), range.end_bound().cloned()))
+            .collect();
+        let values: Vec<V> = self.values.drain(range).collect();
+        for key in &keys {
+            self.key_map.remove(key);
+        }
+        for key in &self.keys[start..] {
+            *self.key_map.get_mut(key).unwrap() -= keys.len();
+        }
+        self.len = self.keys.len();
+        self.bump_version();
+        self.notify(|observe, _| {
+            for (key, value) in keys.iter().zip(&values) {
+                observe(Mutation::Remove {
+                    index: start,
+                    key,
+                    value,
+                });
+            }
+        });
+        DictIntoIter::new(keys, values)
+    }
+
+    // walk the entries once, moving the kept ones down and handing the rest to removed
+    // the index map is fixed up in one pass at the end, by the guard so that it also
+    // happens if keep or removed panics
+    fn compact<F, R>(&mut self, mut keep: F, mut removed: R)
+    where
+        F: FnMut(usize, &K, &mut V) -> bool,
+        R: FnMut(K, V),
+    {
+        self.assert_unsealed();
+        let keys = std::mem::replace(&mut self.keys, Vec::with_capacity(self.capacity));
+        let values = std::mem::replace(&mut self.values, Vec::with_capacity(self.capacity));
+        let mut guard = CompactGuard {
+            dict: self,
+            current: None,
+            keys: keys.into_iter(),
+            values: values.into_iter(),
+        };
+        let mut i = 0;
+        while let (Some(key), Some(value)) = (guard.keys.next(), guard.values.next()) {
+            let (key, value) = guard.current.insert((key, value));
+            let kept = keep(i, key, value);
+            let (key, value) = guard.current.take().unwrap();
+            let dict = &mut *guard.dict;
+            if kept {
+                dict.keys.push(key);
+                dict.values.push(value);
+            } else {
+                dict.key_map.remove(&key);
+                if let Some(observe) = &mut dict.observer {
+                    observe(Mutation::Remove {
+                        index: dict.keys.len(),
+                        key: &key,
+                        value: &value,
+                    });
+                }
+                removed(key, value);
+            }
+            i += 1;
+        }
+    }
+
+    /// remove an element from the dictionary by key name in O(1)
+    /// the last entry is moved into the position of the removed entry,
+    /// so this does not preserve the order of the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(1, "a"), (2, "b"), (3, "c")]);
+    /// assert_eq!(dict.swap_remove(1), Some("a"));
+    /// assert_eq!(dict.keys(), &vec![3, 2]);
+    /// ```
+    pub fn swap_remove(&mut self, key: K) -> Option<V> {
+        self.assert_unsealed();
+        let index = self.key_map.remove(&self.canonical(key))?;
+        let value = self.values.swap_remove(index);
+        let key = self.keys.swap_remove(index);
+        // the previous last entry now lives at index
+        if index < self.keys.len() {
+            let i = self.key_map.get_mut(&self.keys[index]).unwrap();
+            *i = index;
+        }
+        self.len -= 1;
+        self.bump_version();
+        // reported as a removal, then the last entry moving into the gap as a reorder
+        self.notify_remove(index, &key, &value);
+        if index < self.len {
+            self.notify_reorder();
+        }
+        Some(value)
+    }
+
+    /// swap the entries at positions a and b in O(1)
+    /// both key and value move, and only the two affected index map entries are updated
+    /// this is the building block for shuffles and heaps on top of the dictionary
+    /// # Panics
+    /// panics if a or b is out of bounds, like `Vec::swap`
+    /// panics if the dictionary is sealed
+    pub fn swap_entries(&mut self, a: usize, b: usize) {
+        self.assert_unsealed();
+        self.keys.swap(a, b);
+        self.values.swap(a, b);
+        *self.key_map.get_mut(&self.keys[a]).unwrap() = a;
+        *self.key_map.get_mut(&self.keys[b]).unwrap() = b;
+        self.bump_version();
+        self.notify_reorder();
+    }
+
+    /// swap the entries at positions a and b, the same as swap_entries
+    /// # Panics
+    /// panics if a or b is out of bounds
+    /// panics if the dictionary is sealed
+    pub fn swap_indices(&mut self, a: usize, b: usize) {
+        self.swap_entries(a, b);
+    }
+
+    /// move the entry at position from to position to, shifting the entries between
+    /// only the positions between from and to are re-indexed
+    /// # Panics
+    /// panics if from or to is out of bounds
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([('a', 1), ('b', 2), ('c', 3), ('d', 4)]);
+    /// dict.move_index(0, 2);
+    /// assert_eq!(dict.keys(), &vec!['b', 'c', 'a', 'd']);
+    /// dict.move_to_front('d');
+    /// assert_eq!(dict.keys(), &vec!['d', 'b', 'c', 'a']);
+    /// ```
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        self.assert_unsealed();
+        let len = self.keys.len();
+        assert!(
+            from < len && to < len,
+            "move_index from {} to {} out of bounds for length {}",
+            from,
+            to,
+            len
+        );
+        let (start, end) = if from < to {
+            self.keys[from..=to].rotate_left(1);
+            self.values[from..=to].rotate_left(1);
+            (from, to)
+        } else {
+            self.keys[to..=from].rotate_right(1);
+            self.values[to..=from].rotate_right(1);
+            (to, from)
+        };
+        for i in start..=end {
+            *self.key_map.get_mut(&self.keys[i]).unwrap() = i;
+        }
+        self.bump_version();
+        self.notify_reorder();
+    }
+
+    /// move a key to the last position, returning false if it is not in the dictionary
+    /// useful for LRU style structures, touch a key to mark it most recently used
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is present
+    pub fn move_to_back(&mut self, key: K) -> bool {
+        match self.index_of(&key) {
+            Some(i) => {
+                self.move_index(i, self.keys.len() - 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// move a key to the first position, returning false if it is not in the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is present
+    pub fn move_to_front(&mut self, key: K) -> bool {
+        match self.index_of(&key) {
+            Some(i) => {
+                self.move_index(i, 0);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// rename many keys at once, each key of renames is renamed to its value
+    /// keys that are not in the dictionary are skipped. Each entry keeps its
+    /// position and value, and renames apply together so chains like a to b and
+    /// b to c and swaps like a to b and b to a work. Fails without renaming anything
+    /// if two renames have the same new key, a new key is already used by a key that
+    /// stays or the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::{Dictionary, RenameError};
+    ///
+    /// let mut config = Dictionary::from([("host", "db"), ("user", "app"), ("pass", "x")]);
+    /// let renames = Dictionary::from([("user", "username"), ("pass", "user"), ("tls", "ssl")]);
+    /// config.rename_keys(&renames).unwrap();
+    /// assert_eq!(config.keys(), &vec!["host", "username", "user"]);
+    /// assert_eq!(config.get("user"), Some("x"));
+    ///
+    /// let clash = Dictionary::from([("host", "user")]);
+    /// assert_eq!(config.rename_keys(&clash), Err(RenameError::TargetExists { rename: 0 }));
+    /// ```
+    pub fn rename_keys<T: BuildIndex<K>>(
+        &mut self,
+        renames: &Dictionary<K, K, T>,
+    ) -> Result<(), RenameError> {
+        if self.sealed {
+            return Err(RenameError::Sealed);
+        }
+        // (position of the old key, new key, position of the rename)
+        let mut moves = Vec::new();
+        // the new keys before any key limit, so two long keys that truncate alike
+        // are not taken for the same key
+        let mut targets = self.index_builder.build_index(renames.len());
+        for (rename, (old, new)) in renames.iter().enumerate() {
+            let old = self.canonical(old.clone());
+            let short = self.canonical(new.clone());
+            let position = match self.key_map.get(&old) {
+                Some(&i) if old != short => i,
+                _ => continue,
+            };
+            if let Some(first) = targets.insert(self.canonicalized(new.clone()), rename) {
+                return Err(RenameError::DuplicateTarget {
+                    first,
+                    duplicate: rename,
+                });
+            }
+            moves.push((position, short, rename));
+        }
+        // a new key that is in use must belong to a key that is renamed away
+        let moving: HashSet<usize> = moves.iter().map(|(position, _, _)| *position).collect();
+        for (_, short, rename) in &moves {
+            if let Some(i) = self.key_map.get(short) {
+                if !moving.contains(i) {
+                    return Err(RenameError::TargetExists { rename: *rename });
+                }
+            }
+        }
+
+        if moves.is_empty() {
+            return Ok(());
+        }
+        for (position, _, _) in &moves {
+            self.key_map.remove(&self.keys[*position]);
+        }
+        // only now that nothing can fail do the new keys go into the key limit
+        let mut renamed = Vec::with_capacity(moves.len());
+        for (position, _, rename) in moves {
+            let new = self.canonical_new(renames.values()[rename].clone());
+            self.key_map.insert(new.clone(), position);
+            renamed.push((position, std::mem::replace(&mut self.keys[position], new)));
+        }
+        self.bump_version();
+        for (position, old) in renamed {
+            self.notify_rename(position, &old);
+        }
+        Ok(())
+    }
+
+    /// insert a pair right before an existing anchor key
+    /// a key that is already in the dictionary gets the new value and is moved next to the anchor
+    /// returns the position the pair ended up at, or None without inserting if the
+    /// anchor is not in the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut config = Dictionary::from([("server", 1), ("logging", 2)]);
+    /// assert_eq!(config.insert_before(&"logging", "tls", 3), Some(1));
+    /// assert_eq!(config.insert_after(&"logging", "metrics", 4), Some(3));
+    /// assert_eq!(config.keys(), &vec!["server", "tls", "logging", "metrics"]);
+    /// assert_eq!(config.insert_after(&"missing", "x", 5), None);
+    /// ```
+    pub fn insert_before(&mut self, anchor: &K, key: K, value: V) -> Option<usize> {
+        self.insert_next_to(anchor, key, value, false)
+    }
+
+    /// insert a pair right after an existing anchor key, see insert_before
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    pub fn insert_after(&mut self, anchor: &K, key: K, value: V) -> Option<usize> {
+        self.insert_next_to(anchor, key, value, true)
+    }
+
+    fn insert_next_to(&mut self, anchor: &K, key: K, value: V, after: bool) -> Option<usize> {
+        self.index_of(anchor)?;
+        let key = self.canonical_new(key);
+        self.push_back(key.clone(), value);
+        let from = *self.key_map.get(&key)?;
+        // pushing a new key does not move the anchor, but look it up again in case
+        // the key and the anchor are the same
+        let at = self.index_of(anchor)?;
+        if from == at {
+            return Some(from);
+        }
+        let to = match (after, from > at) {
+            (true, true) => at + 1,
+            (true, false) => at,
+            (false, true) => at,
+            (false, false) => at - 1,
+        };
+        self.move_index(from, to);
+        Some(to)
+    }
+
+    /// insert a new key at a position, shifting the entries from there on up by one
+    /// a key that is already in the dictionary keeps its value and position, the new
+    /// value is dropped and Ok(false) returned
+    /// index may be the length to insert at the back
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::error::DictError;
+    ///
+    /// let mut dict = Dictionary::from([("a", 1), ("c", 3)]);
+    /// assert_eq!(dict.insert("b", 2, 1), Ok(true));
+    /// assert_eq!(dict.insert("a", 10, 0), Ok(false));
+    /// assert_eq!(dict.insert("z", 26, 9), Err(DictError::IndexOutOfBounds { index: 9, len: 3 }));
+    /// assert_eq!(dict.keys(), &vec!["a", "b", "c"]);
+    /// assert_eq!(dict.get("b"), Some(2));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V, index: usize) -> Result<bool, DictError<K, V>> {
+        match self.try_insert(key, value, index) {
+            Ok(()) => Ok(true),
+            Err(DictError::Occupied(occupied)) => {
+                self.record_duplicate(occupied.key, Resolution::KeptExisting);
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// insert a new key at a position like insert, failing if the key is already in
+    /// the dictionary or the dictionary is sealed. Nothing is changed on failure, an
+    /// occupied key hands the key and value back
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::error::DictError;
+    ///
+    /// let mut dict = Dictionary::from([("a", 1)]);
+    /// assert_eq!(dict.try_insert("b", 2, 0), Ok(()));
+    /// let Err(DictError::Occupied(occupied)) = dict.try_insert("a", 5, 0) else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!((occupied.key, occupied.value, occupied.index), ("a", 5, 1));
+    /// ```
+    pub fn try_insert(&mut self, key: K, value: V, index: usize) -> Result<(), DictError<K, V>> {
+        self.check_unsealed()?;
+        if index > self.len {
+            return Err(DictError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+        let key = self.canonical_new(key);
+        if let Some(&i) = self.key_map.get(&key) {
+            return Err(OccupiedError {
+                key,
+                value,
+                index: i,
+            }
+            .into());
+        }
+        self.insert_new_canonical(key, value, index);
+        Ok(())
+    }
+
+    /// insert a pair at a position, like `IndexMap::shift_insert`
+    /// a new key is inserted at index, shifting the entries from there on up by one,
+    /// index may be the length to insert at the back. A key that is already in the
+    /// dictionary gets the new value and is moved to index, the previous value is
+    /// returned, index must then be a position of the dictionary.
+    /// Nothing is changed if the index is out of bounds or the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut steps = Dictionary::from([("fetch", 1), ("build", 2), ("test", 3)]);
+    /// assert_eq!(steps.shift_insert("lint", 0, 1), Ok(None));
+    /// assert_eq!(steps.shift_insert("test", 30, 1), Ok(Some(3)));
+    /// assert_eq!(steps.keys(), &vec!["fetch", "test", "lint", "build"]);
+    /// assert!(steps.shift_insert("build", 0, 4).is_err());
+    /// ```
+    pub fn shift_insert(
+        &mut self,
+        key: K,
+        value: V,
+        index: usize,
+    ) -> Result<Option<V>, DictError<K, V>> {
+        self.check_unsealed()?;
+        let from = self.key_map.get(&self.canonical(key.clone())).copied();
+        // an existing key moves within the current positions, a new one may go at the back
+        let last = if from.is_some() {
+            self.len
+        } else {
+            self.len + 1
+        };
+        if index >= last {
+            return Err(DictError::IndexOutOfBounds {
+                index,
+                len: self.len,
+            });
+        }
+        // a key limit only remembers the original once the key is going in
+        let key = self.canonical_new(key);
+        match from {
+            Some(from) => {
+                let previous = self.push_back_canonical(key, value);
+                if from != index {
+                    self.move_index(from, index);
+                }
+                Ok(previous)
+            }
+            None => {
+                self.insert_new_canonical(key, value, index);
+                Ok(None)
+            }
+        }
+    }
+
+    // insert a key that has been through canonical_new and is not in the dictionary
+    fn insert_new_canonical(&mut self, key: K, value: V, index: usize) {
+        self.assert_unsealed();
+        if self.len == self.capacity {
+            self.update_capacity();
+        }
+        // every position from index on moves up by one, no need to hash the keys
+        self.key_map.for_each_position(|i| {
+            if *i >= index {
+                *i += 1
+            }
+        });
+        self.keys.insert(index, key.clone());
+        self.values.insert(index, value);
+        self.key_map.insert(key, index);
+        self.len += 1;
+        self.bump_version();
+        self.notify_insert(index);
+    }
+
+    /// the position of every key, in order
+    /// useful for building columnar encodings where each key becomes a column id
+    pub fn key_indices(&self) -> impl Iterator<Item = (&K, usize)> + '_ {
+        self.keys.iter().enumerate().map(|(i, key)| (key, i))
+    }
+
+    /// a copy of the internal key to position map, borrowing the keys
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("b", 0), ("a", 0)]);
+    /// let columns = dict.as_index_map();
+    /// assert_eq!(columns[&"a"], 1);
+    /// ```
+    pub fn as_index_map(&self) -> HashMap<&K, usize>
+    where
+        K: Hash,
+    {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key, i))
+            .collect()
+    }
+
+    /// take ownership of the key and value columns
+    /// the index map is dropped, use from_parts to rebuild the Dictionary
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(1, 10), (2, 20)]);
+    /// let (keys, mut values) = dict.into_parts();
+    /// values.iter_mut().for_each(|v| *v *= 2);
+    /// let dict = Dictionary::from_parts(keys, values).unwrap();
+    /// assert_eq!(dict.get(2), Some(40));
+    /// ```
+    pub fn into_parts(self) -> (Vec<K>, Vec<V>) {
+        (self.keys, self.values)
+    }
+
+    /// split the dictionary into n dictionaries of consecutive entries, in order
+    /// chunk sizes differ by at most one and each chunk is allocated at its exact size,
+    /// there are fewer than n chunks when the dictionary has fewer than n entries.
+    /// The chunks own their entries and can be moved to the threads of any pool,
+    /// `from_chunked_parts` puts the results back together in order.
+    /// # Panics
+    /// panics if n is 0
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use std::thread;
+    ///
+    /// let dict: Dictionary<i32, i32> = (0..10).map(|i| (i, i)).collect();
+    /// let handles: Vec<_> = dict
+    ///     .into_chunked_parts(3)
+    ///     .into_iter()
+    ///     .map(|mut chunk| {
+    ///         thread::spawn(move || {
+    ///             chunk.iter_mut().for_each(|(_, v)| *v *= 10);
+    ///             chunk
+    ///         })
+    ///     })
+    ///     .collect();
+    /// let parts = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    /// let dict = Dictionary::from_chunked_parts(parts);
+    /// assert_eq!(dict.keys(), &(0..10).collect::<Vec<i32>>());
+    /// assert_eq!(dict.get(9), Some(90));
+    /// ```
+    pub fn into_chunked_parts(self, n: usize) -> Vec<Dictionary<K, V, S>>
+    where
+        S: Clone,
+    {
+        assert!(n != 0, "chunk count must be non-zero");
+        let len = self.keys.len();
+        let (base, extra) = (len / n, len % n);
+        let mut keys = self.keys.into_iter();
+        let mut values = self.values.into_iter();
+        let mut parts = Vec::with_capacity(n.min(len));
+        for i in 0..n.min(len) {
+            let size = base + usize::from(i < extra);
+            let mut part = Dictionary::with_capacity_and_index(size, self.index_builder.clone());
+            for (key, value) in keys.by_ref().zip(values.by_ref()).take(size) {
+                part.push_back(key, value);
+            }
+            parts.push(part);
+        }
+        parts
+    }
+
+    /// join dictionaries back into one, keeping the order of the parts and their entries
+    /// the inverse of into_chunked_parts, the first part is grown in place to the total size.
+    /// A key in more than one part keeps its first position and takes the later value.
+    pub fn from_chunked_parts(parts: Vec<Dictionary<K, V, S>>) -> Dictionary<K, V, S>
+    where
+        S: Default,
+    {
+        let total: usize = parts.iter().map(|part| part.len()).sum();
+        let mut parts = parts.into_iter();
+        let mut dict = match parts.next() {
+            Some(first) => first,
+            None => return Dictionary::with_index(S::default()),
+        };
+        dict.reserve(total - dict.len());
+        for part in parts {
+            dict.extend(part);
+        }
+        dict
+    }
+
+    /// a new dictionary with the same keys in the same order and each value mapped by f
+    /// the keys and their index are cloned rather than rehashed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let prices = Dictionary::from([("tea", 250), ("cake", 400)]);
+    /// let labels = prices.map_values(|cents| format!("${}.{:02}", cents / 100, cents % 100));
+    /// assert_eq!(labels.get("cake"), Some(String::from("$4.00")));
+    ///
+    /// let upper = prices.map(|k, v| (k.to_uppercase(), v * 2));
+    /// assert_eq!(upper.keys(), &vec![String::from("TEA"), String::from("CAKE")]);
+    /// assert_eq!(upper.values(), &vec![500, 800]);
+    /// ```
+    pub fn map_values<W, F>(&self, f: F) -> Dictionary<K, W, S>
+    where
+        W: Clone,
+        F: FnMut(&V) -> W,
+        S: Clone,
+        S::Index: Clone,
+    {
+        let mut mapped = Dictionary::with_capacity_and_index(0, self.index_builder.clone());
+        mapped.keys = self.keys.clone();
+        mapped.key_map = self.key_map.clone();
+        mapped.values = self.values.iter().map(f).collect();
+        mapped.len = self.len;
+        mapped.capacity = self.len;
+        mapped.canonicalize = self.canonicalize;
+        mapped.key_limit = self.key_limit.clone();
+        mapped.format_options = self.format_options;
+        mapped.growth = self.growth;
+        mapped
+    }
+
+    /// a new dictionary of the pairs f makes from each entry, in order
+    /// with capacity for every entry reserved up front. When f makes the same key
+    /// twice the later value wins and the key keeps its first position, like push_back
+    pub fn map<K2, V2, F>(&self, mut f: F) -> Dictionary<K2, V2, S>
+    where
+        K2: Eq + Clone,
+        V2: Clone,
+        F: FnMut(&K, &V) -> (K2, V2),
+        S: BuildIndex<K2> + Clone,
+    {
+        let mut mapped = Dictionary::with_capacity_and_index(self.len, self.index_builder.clone());
+        mapped.format_options = self.format_options;
+        for (key, value) in self.keys.iter().zip(&self.values) {
+            let (key, value) = f(key, value);
+            mapped.push_back(key, value);
+        }
+        mapped
+    }
+
+    /// get value by key
+    /// returns an `Option<V>`, the value is cloned
+    /// use get_full to borrow it instead, or get_copied for Copy values
+    pub fn get(&self, key: K) -> Option<V> {
+        let key = self.canonical(key);
+        if let Some(i) = self.hot_index(&key) {
+            return Some(self.values[i].clone());
+        }
+        // get by key
+        self.key_map.get(&key).map(|i| self.values[*i].clone())
+    }
+
+    /// pin a key in a small side table that get checks before hashing
+    /// for workloads where a handful of keys take most of the lookups
+    /// up to HOT_CAPACITY keys are pinned, marking another drops the oldest
+    /// mutations need no bookkeeping, a pinned position is checked against the key
+    /// on every read and a key that has moved is looked up in the map as usual,
+    /// call mark_hot again to pin it at its new position
+    /// returns false if the key is not in the dictionary
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([("user", 1), ("session", 2)]);
+    /// assert!(dict.mark_hot("session"));
+    /// assert_eq!(dict.get("session"), Some(2));
+    /// assert_eq!(dict.hot_keys(), vec![&"session"]);
+    /// ```
+    pub fn mark_hot(&mut self, key: K) -> bool {
+        let key = self.canonical(key);
+        let i = match self.key_map.get(&key) {
+            Some(&i) => i,
+            None => return false,
+        };
+        self.hot.retain(|(hot, _)| *hot != key);
+        if self.hot.len() == HOT_CAPACITY {
+            self.hot.remove(0);
+        }
+        self.hot.push((key, i));
+        true
+    }
+
+    /// unpin every key
+    pub fn clear_hot(&mut self) {
+        self.hot.clear();
+    }
+
+    /// the pinned keys that are still at their pinned position
+    pub fn hot_keys(&self) -> Vec<&K> {
+        self.hot
+            .iter()
+            .filter(|(key, i)| self.keys.get(*i) == Some(key))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    #[inline]
+    fn hot_index(&self, key: &K) -> Option<usize> {
+        self.hot
+            .iter()
+            .find(|(hot, _)| hot == key)
+            .map(|&(_, i)| i)
+            .filter(|&i| self.keys.get(i) == Some(key))
+    }
+
+    /// true if both hold the same pairs, in any order, like `==` on python dicts
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let a = Dictionary::from([("x", 1), ("y", 2)]);
+    /// let b = Dictionary::from([("y", 2), ("x", 1)]);
+    /// assert!(a != b);
+    /// assert!(a.eq_ignore_order(&b));
+    /// ```
+    pub fn eq_ignore_order(&self, other: &Dictionary<K, V, S>) -> bool
+    where
+        V: PartialEq,
+    {
+        self.len == other.len
+            && self.iter().all(|(key, value)| {
+                other
+                    .stored_index_of(key)
+                    .is_some_and(|i| &other.values[i] == value)
+            })
+    }
+
+    /// true if the key is in the dictionary, without touching the value
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// the position of a key in the dictionary
+    /// the dual of get_index
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(dict.index_of(&"b"), Some(1));
+    /// assert_eq!(dict.get_index(dict.index_of(&"b").unwrap()), Some(2));
+    /// assert_eq!(dict.index_of(&"c"), None);
+    /// ```
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.key_map.get(&self.canonical(key.clone())).copied()
+    }
+
+    // the position of a key as it is stored, such as a key taken from keys(), the key
+    // limit is skipped since it would take a stored short key for a new key
+    pub(crate) fn stored_index_of(&self, key: &K) -> Option<usize> {
+        match self.canonicalize {
+            Some(canonicalize) => self.key_map.get(&canonicalize(key.clone())).copied(),
+            None => self.key_map.get(key).copied(),
+        }
+    }
+
+    /// the position, key and value of an entry in one lookup, without cloning
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("a", 1), ("b", 2)]);
+    /// assert_eq!(dict.get_full(&"b"), Some((1, &"b", &2)));
+    /// assert_eq!(dict.get_index_entry(0), Some((&"a", &1)));
+    /// ```
+    pub fn get_full(&self, key: &K) -> Option<(usize, &K, &V)> {
+        let i = self.index_of(key)?;
+        Some((i, &self.keys[i], &self.values[i]))
+    }
+
+    /// the key and value at a position, without cloning
+    pub fn get_index_entry(&self, i: usize) -> Option<(&K, &V)> {
+        Some((self.keys.get(i)?, self.values.get(i)?))
+    }
+
+    /// get a mutable reference to a value by key
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        let i = *self.key_map.get(&self.canonical(key))?;
+        // the value may be changed through the reference
+        self.bump_version();
+        Some(&mut self.values[i])
+    }
+
+    /// mutable references to the values of several distinct keys at once
+    /// each slot is None if its key is not in the dictionary
+    /// # Panics
+    /// panics if two of the keys are the same, like `HashMap::get_disjoint_mut`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut accounts = Dictionary::from([("alice", 100), ("bob", 20)]);
+    /// let [Some(from), Some(to), missing] = accounts.get_disjoint_mut([&"alice", &"bob", &"carol"]) else {
+    ///     unreachable!()
+    /// };
+    /// *from -= 30;
+    /// *to += 30;
+    /// assert!(missing.is_none());
+    /// assert_eq!(accounts.values(), &vec![70, 50]);
+    /// ```
+    pub fn get_disjoint_mut<const N: usize>(&mut self, keys: [&K; N]) -> [Option<&mut V>; N] {
+        let positions = keys.map(|key| self.index_of(key));
+        self.disjoint_values_mut(positions, "duplicate key")
+    }
+
+    /// mutable references to the values at several distinct positions at once
+    /// each slot is None if its position is out of bounds
+    /// # Panics
+    /// panics if two of the positions are the same
+    pub fn get_disjoint_indices_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> [Option<&mut V>; N] {
+        let len = self.len;
+        let positions = indices.map(|i| Some(i).filter(|&i| i < len));
+        self.disjoint_values_mut(positions, "duplicate index")
+    }
+
+    // split the values around each requested position in ascending order,
+    // so every reference comes from its own part of the slice
+    fn disjoint_values_mut<const N: usize>(
+        &mut self,
+        positions: [Option<usize>; N],
+        duplicate: &str,
+    ) -> [Option<&mut V>; N] {
+        let mut order: Vec<usize> = (0..N).filter(|&slot| positions[slot].is_some()).collect();
+        order.sort_unstable_by_key(|&slot| positions[slot]);
+        for pair in order.windows(2) {
+            assert!(positions[pair[0]] != positions[pair[1]], "{}", duplicate);
+        }
+        // the values may be changed through the references
+        self.bump_version();
+        let mut found: [Option<&mut V>; N] = std::array::from_fn(|_| None);
+        let mut rest = self.values.as_mut_slice();
+        let mut offset = 0;
+        for slot in order {
+            let position = positions[slot].unwrap();
+            let (_, tail) = std::mem::take(&mut rest).split_at_mut(position - offset);
+            let (value, tail) = tail.split_first_mut().unwrap();
+            found[slot] = Some(value);
+            rest = tail;
+            offset = position + 1;
+        }
+        found
+    }
+
+    /// get a value by index
+    /// This method takes advantage of the ordered nature of the data structure
+    pub fn get_index(&self, i: usize) -> Option<V> {
+        if i >= self.len {
+            return None;
+        }
+        Some(self.values[i].clone())
+    }
+
+    /// a mutable reference to the value at a position, without hashing the key
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut scores = Dictionary::from([("b", 2), ("a", 9), ("c", 5)]);
+    /// scores.sort_by_values();
+    /// *scores.get_index_mut(0).unwrap() *= 10;
+    /// assert_eq!(scores.set_index(2, 0), Some(9));
+    /// assert_eq!(scores.set_index(3, 0), None);
+    /// assert_eq!(scores.keys(), &vec!["b", "c", "a"]);
+    /// assert_eq!(scores.values(), &vec![20, 5, 0]);
+    /// ```
+    pub fn get_index_mut(&mut self, i: usize) -> Option<&mut V> {
+        if i >= self.len {
+            return None;
+        }
+        // the value may be changed through the reference
+        self.bump_version();
+        Some(&mut self.values[i])
+    }
+
+    /// replace the value at a position, returning the previous value
+    /// the key keeps its position, like push_back of an existing key.
+    /// Returns None and drops value if the position is out of bounds
+    pub fn set_index(&mut self, i: usize, value: V) -> Option<V> {
+        if i >= self.len {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.values[i], value);
+        self.bump_version();
+        self.record_change(|dict| ChangeEvent::PushBack {
+            key: dict.keys[i].clone(),
+            value: dict.values[i].clone(),
+        });
+        self.notify_update(i, &previous);
+        Some(previous)
+    }
+
+    /// get with a default
+    /// parallel to dict.get(key, default) in python
+    /// if no default is provided, None will be returned
+    pub fn get_or(&self, key: K, default: V) -> V {
+        match self.key_map.get(&self.canonical(key)) {
+            Some(i) => self.values[*i].clone(),
+            None => default,
+        }
+    }
+
+    /// borrow the value from this dictionary, or else from the first fallback that has the key
+    /// a lighter chain than a ChainMap for layered defaults, nothing is merged or cloned
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let cli = Dictionary::from([("port", 9000)]);
+    /// let env = Dictionary::from([("port", 8080), ("workers", 4)]);
+    /// let defaults = Dictionary::from([("workers", 1), ("timeout", 30)]);
+    /// assert_eq!(cli.get_with_fallbacks(&"port", &[&env, &defaults]), Some(&9000));
+    /// assert_eq!(cli.get_with_fallbacks(&"workers", &[&env, &defaults]), Some(&4));
+    /// assert_eq!(cli.get_with_fallbacks(&"timeout", &[&env, &defaults]), Some(&30));
+    /// assert_eq!(cli.get_with_fallbacks(&"retries", &[&env, &defaults]), None);
+    /// ```
+    pub fn get_with_fallbacks<'a>(
+        &'a self,
+        key: &K,
+        fallbacks: &[&'a Dictionary<K, V, S>],
+    ) -> Option<&'a V> {
+        std::iter::once(self)
+            .chain(fallbacks.iter().copied())
+            .find_map(|dict| dict.get_full(key).map(|(_, _, value)| value))
+    }
+
+    /// the number of key value pairs in the dictionary
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// true when the dictionary holds no key value pairs
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// get the current capacity of the dictionary
+    /// the number of items the dictionary can currently hold
     pub fn capacity(&self) -> usize {
         self.capacity
     }
 
-    /// reserve additional capacity in the dictionary
-    /// useful when you know you will need more than what you currently have
-    /// same approach as when more space is revered in a Vec
-    pub fn reserve(&mut self, size: usize) {
-        self.capacity += size;
-        self.values.reserve(size);
-        self.key_map.reserve(size);
-        self.keys.reserve(size);
+    /// reserve additional capacity in the dictionary
+    /// useful when you know you will need more than what you currently have
+    /// the capacity grows by at least size, following the GrowthStrategy of the dictionary
+    pub fn reserve(&mut self, size: usize) {
+        let new_capacity = self
+            .growth
+            .next_capacity(self.capacity, self.capacity + size);
+        self.grow_to(new_capacity);
+    }
+
+    /// reserve additional capacity like `reserve`, but return an error instead of
+    /// aborting when the keys, the values or the index can not allocate
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict: Dictionary<u64, u64> = Dictionary::new();
+    /// assert!(dict.try_reserve(64).is_ok());
+    /// assert!(dict.capacity() >= 64);
+    /// assert!(dict.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let new_capacity = self
+            .growth
+            .next_capacity(self.capacity, self.capacity.saturating_add(additional));
+        let additional = new_capacity - self.len;
+        self.keys.try_reserve_exact(additional)?;
+        self.values.try_reserve_exact(additional)?;
+        self.key_map.try_reserve(additional)?;
+        self.capacity = new_capacity;
+        Ok(())
+    }
+
+    /// sort the entries with a comparator over (key, value) pairs
+    /// the sort is stable, entries that compare equal keep their relative order
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut scores = Dictionary::from([("a", 2), ("b", 9), ("c", 5)]);
+    /// // descending by value
+    /// scores.sort_by(|(_, v1), (_, v2)| v2.cmp(v1));
+    /// assert_eq!(scores.keys(), &vec!["b", "c", "a"]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut((&K, &V), (&K, &V)) -> Ordering,
+    {
+        self.assert_unsealed();
+        // sort the pairs together so every value follows its key
+        // then split them back into the two columns
+        // recompute map with new indexs
+        let keys = std::mem::replace(&mut self.keys, Vec::with_capacity(self.capacity));
+        let values = std::mem::replace(&mut self.values, Vec::with_capacity(self.capacity));
+        let mut entries: Vec<(K, V)> = keys.into_iter().zip(values).collect();
+        entries.sort_by(|(k1, v1), (k2, v2)| compare((k1, v1), (k2, v2)));
+        for (key, value) in entries {
+            self.keys.push(key);
+            self.values.push(value);
+        }
+        // recompute the key value index map
+        self.recompute_map();
+        self.bump_version();
+        self.notify_reorder();
+    }
+
+    /// sort the entries by a key derived from each pair
+    /// the sort is stable, and the derived key is computed on every comparison,
+    /// use sort_by_cached_key when deriving it is expensive
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut words = Dictionary::from([("pear", 1), ("fig", 2), ("banana", 3)]);
+    /// words.sort_by_key(|k, _| k.len());
+    /// assert_eq!(words.keys(), &vec!["fig", "pear", "banana"]);
+    /// ```
+    pub fn sort_by_key<T, F>(&mut self, mut f: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        self.sort_by(|(k1, v1), (k2, v2)| f(k1, v1).cmp(&f(k2, v2)));
+    }
+
+    /// sort the entries by a key derived from each pair, computing it once per entry
+    /// like `Vec::sort_by_cached_key` the sort is stable and holds all n derived keys
+    /// at once, so prefer sort_by_key when the derived key is cheap
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut files = Dictionary::from([("b.txt", "3,1"), ("a.txt", "10"), ("c.txt", "2")]);
+    /// files.sort_by_cached_key(|_, v| v.split(',').map(|n| n.parse::<u32>().unwrap()).sum::<u32>());
+    /// assert_eq!(files.keys(), &vec!["c.txt", "b.txt", "a.txt"]);
+    /// ```
+    pub fn sort_by_cached_key<T, F>(&mut self, mut f: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        self.assert_unsealed();
+        let mut order: Vec<usize> = (0..self.len).collect();
+        order.sort_by_cached_key(|&i| f(&self.keys[i], &self.values[i]));
+        self.permute(order);
+    }
+
+    /// the positions of the entries in the order sort_by would put them, without
+    /// moving anything. The sort is stable, equal entries keep their relative order
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let scores = Dictionary::from([("a", 2), ("b", 9), ("c", 5), ("d", 7)]);
+    /// let order = scores.sorted_indices_by(|(_, v1), (_, v2)| v2.cmp(v1));
+    /// assert_eq!(order, vec![1, 3, 2, 0]);
+    /// let top = scores.take_indices(&order[..2]);
+    /// assert_eq!(top.keys(), &vec!["b", "d"]);
+    /// assert_eq!(scores.keys(), &vec!["a", "b", "c", "d"]);
+    /// ```
+    pub fn sorted_indices_by<F>(&self, mut compare: F) -> Vec<usize>
+    where
+        F: FnMut((&K, &V), (&K, &V)) -> Ordering,
+    {
+        let mut order: Vec<usize> = (0..self.len).collect();
+        order.sort_by(|&a, &b| {
+            compare(
+                (&self.keys[a], &self.values[a]),
+                (&self.keys[b], &self.values[b]),
+            )
+        });
+        order
+    }
+
+    /// a new dictionary of the entries at the positions, in the order given
+    /// a position given twice is taken once, at its first place
+    /// # Panics
+    /// panics if a position is out of bounds
+    pub fn take_indices(&self, indices: &[usize]) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+    {
+        let mut taken =
+            Dictionary::with_capacity_and_index(indices.len(), self.index_builder.clone());
+        for &i in indices {
+            assert!(
+                i < self.len,
+                "index {} out of bounds for length {}",
+                i,
+                self.len
+            );
+            taken.push_back(self.keys[i].clone(), self.values[i].clone());
+        }
+        taken.canonicalize = self.canonicalize;
+        taken
+    }
+
+    /// reverse the order of the entries in place
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn reverse(&mut self) {
+        self.assert_unsealed();
+        self.keys.reverse();
+        self.values.reverse();
+        self.recompute_map();
+        self.bump_version();
+        self.notify_reorder();
+    }
+
+    // move the entries into a sorted order of positions and bring the index up to date
+    fn permute(&mut self, order: Vec<usize>) {
+        self.apply_permutation(order);
+        self.recompute_map();
+        self.bump_version();
+        self.notify_reorder();
+    }
+
+    // reorder the entries so the entry at order[i] ends up at position i
+    // each cycle of the permutation is walked once with swaps, so this is O(n)
+    fn apply_permutation(&mut self, mut order: Vec<usize>) {
+        for start in 0..order.len() {
+            let mut current = start;
+            // positions already in place are marked by pointing at themselves
+            while order[current] != start {
+                let next = order[current];
+                self.keys.swap(current, next);
+                self.values.swap(current, next);
+                order[current] = current;
+                current = next;
+            }
+            order[current] = current;
+        }
+    }
+
+    #[inline]
+    fn recompute_map(&mut self) {
+        for (i, key) in self.keys.iter().enumerate() {
+            let index = self.key_map.get_mut(key).unwrap();
+            *index = i;
+        }
+    }
+
+    /// true if every key in self is also a key in other, values are not compared
+    /// like `d1.keys() <= d2.keys()` in python
+    pub fn is_subset_of(&self, other: &Dictionary<K, V, S>) -> bool {
+        self.len <= other.len && self.keys.iter().all(|key| other.has_key(key))
+    }
+
+    /// true if every key in other is also a key in self, values are not compared
+    /// like `d1.keys() >= d2.keys()` in python
+    pub fn is_superset_of(&self, other: &Dictionary<K, V, S>) -> bool {
+        other.is_subset_of(self)
+    }
+
+    /// true if every key value pair in self is also in other, order is ignored
+    /// like `d1.items() <= d2.items()` in python
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let granted = Dictionary::from([("read", true), ("write", false), ("admin", false)]);
+    /// let requested = Dictionary::from([("write", false), ("read", true)]);
+    /// assert!(requested.is_submap_of(&granted));
+    /// assert!(!Dictionary::from([("admin", true)]).is_submap_of(&granted));
+    /// ```
+    pub fn is_submap_of(&self, other: &Dictionary<K, V, S>) -> bool
+    where
+        V: PartialEq,
+    {
+        self.len <= other.len
+            && self
+                .iter()
+                .all(|(key, value)| match other.key_map.get(key) {
+                    Some(&i) => other.values[i] == *value,
+                    None => false,
+                })
+    }
+
+    /// the entries of self followed by those of other whose keys are not in self,
+    /// merge picks the value of a key in both from (key, self value, other value)
+    /// like `d1.keys() | d2.keys()` in python, with the values kept
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let a = Dictionary::from([("x", 1), ("y", 2), ("z", 3)]);
+    /// let b = Dictionary::from([("w", 10), ("y", 20)]);
+    /// let sum = a.union(&b, |_, l, r| l + r);
+    /// assert_eq!(sum.keys(), &vec!["x", "y", "z", "w"]);
+    /// assert_eq!(sum.values(), &vec![1, 22, 3, 10]);
+    ///
+    /// let both = a.intersection(&b, |_, _, r| *r);
+    /// assert_eq!(both, Dictionary::from([("y", 20)]));
+    /// assert_eq!(a.difference(&b).keys(), &vec!["x", "z"]);
+    /// assert_eq!(a.symmetric_difference(&b).keys(), &vec!["x", "z", "w"]);
+    /// ```
+    pub fn union<F>(&self, other: &Dictionary<K, V, S>, mut merge: F) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+        F: FnMut(&K, &V, &V) -> V,
+    {
+        let mut union = Dictionary::with_capacity_and_index(self.len, self.index_builder.clone());
+        union.canonicalize = self.canonicalize;
+        for (key, value) in self.iter() {
+            let value = match other.key_map.get(key) {
+                Some(&i) => merge(key, value, &other.values[i]),
+                None => value.clone(),
+            };
+            union.push_back(key.clone(), value);
+        }
+        for (key, value) in other.iter() {
+            if !self.has_key(key) {
+                union.push_back(key.clone(), value.clone());
+            }
+        }
+        union
+    }
+
+    /// the keys of self that are also in other, in the order of self,
+    /// merge picks each value from (key, self value, other value)
+    /// like `d1.keys() & d2.keys()` in python, with the values kept
+    pub fn intersection<F>(&self, other: &Dictionary<K, V, S>, mut merge: F) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+        F: FnMut(&K, &V, &V) -> V,
+    {
+        let mut both = Dictionary::with_index(self.index_builder.clone());
+        both.canonicalize = self.canonicalize;
+        for (key, value) in self.iter() {
+            if let Some(&i) = other.key_map.get(key) {
+                both.push_back(key.clone(), merge(key, value, &other.values[i]));
+            }
+        }
+        both
+    }
+
+    /// the entries of self whose keys are not in other, in order
+    /// like `d1.keys() - d2.keys()` in python, with the values kept
+    pub fn difference(&self, other: &Dictionary<K, V, S>) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+    {
+        self.filtered(|key, _| !other.has_key(key))
+    }
+
+    /// the entries of self whose keys are not in other, followed by the entries of
+    /// other whose keys are not in self
+    /// like `d1.keys() ^ d2.keys()` in python, with the values kept
+    pub fn symmetric_difference(&self, other: &Dictionary<K, V, S>) -> Dictionary<K, V, S>
+    where
+        S: Clone,
+    {
+        let mut only = self.difference(other);
+        for (key, value) in other.iter() {
+            if !self.has_key(key) {
+                only.push_back(key.clone(), value.clone());
+            }
+        }
+        only
+    }
+
+    /// merge key value pairs into the dictionary, like `dict.update` in python
+    /// new keys are pushed to the back in the order they come in,
+    /// keys already in the dictionary keep their position and are resolved by the policy
+    /// `d1 |= d2` is the same as `d1.update_from(d2, MergePolicy::Overwrite)`
+    /// # Panics
+    /// panics if the dictionary is sealed and a key is new
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::{Dictionary, MergePolicy};
+    ///
+    /// let mut totals = Dictionary::from([("a", 1), ("b", 2)]);
+    /// let more = Dictionary::from([("b", 10), ("c", 3)]);
+    /// totals.update_from(more, MergePolicy::Combine(|v, new| *v += new));
+    /// assert_eq!(totals.keys(), &vec!["a", "b", "c"]);
+    /// assert_eq!(totals.values(), &vec![1, 12, 3]);
+    /// ```
+    pub fn update_from<I: IntoIterator<Item = (K, V)>>(
+        &mut self,
+        other: I,
+        policy: MergePolicy<V>,
+    ) {
+        for (key, value) in other {
+            let key = self.canonical_new(key);
+            match (self.key_map.get(&key), &policy) {
+                (Some(_), MergePolicy::KeepExisting) => {
+                    self.record_duplicate(key, Resolution::KeptExisting);
+                }
+                (Some(&i), MergePolicy::Combine(combine)) => {
+                    // the old value is only kept for the observer
+                    let old = self.observer.as_ref().map(|_| self.values[i].clone());
+                    combine(&mut self.values[i], value);
+                    self.record_duplicate(key, Resolution::Combined);
+                    self.bump_version();
+                    if let Some(old) = old {
+                        self.notify_update(i, &old);
+                    }
+                }
+                _ => {
+                    self.push_back(key, value);
+                }
+            }
+        }
+    }
+
+    /// rebuild the key to index map from the key order
+    /// the map is kept in step by every method, this is only needed as a repair
+    /// when `health()` reports an inconsistent index
+    pub fn rebuild_index(&mut self) {
+        self.key_map.clear();
+        for (i, key) in self.keys.iter().enumerate() {
+            self.key_map.insert(key.clone(), i);
+        }
+        self.len = self.keys.len();
+        self.bump_version();
+    }
+
+    // (entries, buckets) of the index map
+    pub(crate) fn index_load(&self) -> (usize, usize) {
+        (self.key_map.len(), self.key_map.capacity())
+    }
+
+    // true if the map has exactly one entry per key pointing at that key's position
+    pub(crate) fn index_is_consistent(&self) -> bool {
+        self.len == self.keys.len()
+            && self.key_map.len() == self.keys.len()
+            && self
+                .keys
+                .iter()
+                .enumerate()
+                .all(|(i, key)| self.key_map.get(key) == Some(&i))
+    }
+
+    fn has_key(&self, key: &K) -> bool {
+        self.key_map.contains_key(key)
+    }
+
+    /// the current version of the dictionary
+    /// this is incremented on every mutation, so two equal versions of the same
+    /// dictionary are guaranteed to have the same contents and order
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// true if the keys are known to be in ascending order
+    /// this is set by sort_by_keys and cleared by any mutation after it,
+    /// including ones that happen to keep the keys in order
+    pub fn is_sorted(&self) -> bool {
+        self.sorted_version == Some(self.version)
+    }
+
+    /// stop any further structural mutation of the dictionary
+    /// after sealing, adding, removing or reordering keys fails, values stay writable
+    /// through get_mut, iter_mut and push_back of a key that is already present.
+    /// try_push_back, try_insert, insert, shift_insert and rename_keys return the
+    /// error, every other structural mutator panics, as its Panics section says.
+    /// Guard those with `dict.unsealed()?` to get `Err(Sealed)` instead.
+    /// There is no way to unseal
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::{Dictionary, Sealed};
+    /// use rust_dict::error::DictError;
+    ///
+    /// let mut config = Dictionary::from([("port", 80), ("workers", 4)]);
+    /// config.seal();
+    /// config.push_back("port", 8080);
+    /// assert_eq!(config.get("port"), Some(8080));
+    /// assert_eq!(config.try_push_back("debug", 1), Err(DictError::Sealed));
+    /// assert_eq!(config.unsealed().map(|d| d.remove("workers")), Err(Sealed));
+    /// assert_eq!(config.len(), 2);
+    /// ```
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    /// borrow the dictionary for a structural mutation, `Err(Sealed)` once it is sealed
+    /// `dict.unsealed()?.push_back(key, value)` fails instead of panicking
+    pub fn unsealed(&mut self) -> Result<&mut Dictionary<K, V, S>, Sealed> {
+        if self.sealed {
+            Err(Sealed)
+        } else {
+            Ok(self)
+        }
+    }
+
+    #[inline]
+    fn check_unsealed(&self) -> Result<(), Sealed> {
+        if self.sealed {
+            Err(Sealed)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn assert_unsealed(&self) {
+        if let Err(sealed) = self.check_unsealed() {
+            panic!("{}", sealed);
+        }
+    }
+
+    // run the key through the canonicalizer and the key limit, if they are set
+    #[inline]
+    fn canonical(&self, key: K) -> K {
+        let key = self.canonicalized(key);
+        match &self.key_limit {
+            Some(limit) => limit
+                .shorten(&key, |short| self.key_map.contains_key(short))
+                .unwrap_or(key),
+            None => key,
+        }
+    }
+
+    // canonical for a key that may be inserted, the key limit remembers its original
+    #[inline]
+    fn canonical_new(&mut self, key: K) -> K {
+        let key = self.canonicalized(key);
+        let key_map = &self.key_map;
+        match &mut self.key_limit {
+            Some(limit) => limit.shorten_new(key, |short| key_map.contains_key(short)),
+            None => key,
+        }
+    }
+
+    // the key after the canonicalizer but before any key limit
+    #[inline]
+    fn canonicalized(&self, key: K) -> K {
+        match self.canonicalize {
+            Some(canonicalize) => canonicalize(key),
+            None => key,
+        }
+    }
+
+    pub(crate) fn set_canonicalizer(&mut self, canonicalize: fn(K) -> K) {
+        self.canonicalize = Some(canonicalize);
+    }
+
+    /// the duplicate insert records, None unless enabled on the builder
+    pub fn duplicate_audit(&self) -> Option<&DuplicateAudit<K>> {
+        self.duplicate_audit.as_ref()
+    }
+
+    /// empty the duplicate insert records, auditing stays on
+    pub fn clear_duplicate_audit(&mut self) {
+        if let Some(audit) = &mut self.duplicate_audit {
+            audit.clear();
+        }
+    }
+
+    pub(crate) fn tracked_digest(&self) -> Option<&TrackedDigest<K, V>> {
+        self.tracked_digest.as_ref()
+    }
+
+    pub(crate) fn tracked_digest_mut(&mut self) -> Option<&mut TrackedDigest<K, V>> {
+        self.tracked_digest.as_mut()
+    }
+
+    pub(crate) fn set_tracked_digest(&mut self, tracked: Option<TrackedDigest<K, V>>) {
+        self.tracked_digest = tracked;
+    }
+
+    pub(crate) fn key_limit(&self) -> Option<&KeyLimit<K, S::Index>> {
+        self.key_limit.as_ref()
+    }
+
+    /// how the dictionary grows when it runs out of room
+    pub fn growth_strategy(&self) -> GrowthStrategy {
+        self.growth
+    }
+
+    pub(crate) fn set_growth_strategy(&mut self, growth: GrowthStrategy) {
+        self.growth = growth;
+    }
+
+    pub(crate) fn set_key_limit(&mut self, max_len: usize, truncate: Truncate<K>) {
+        let by_short = self.index_builder.build_index(0);
+        let by_original = self.index_builder.build_index(0);
+        self.key_limit = Some(KeyLimit::new(max_len, truncate, by_short, by_original));
+    }
+
+    pub(crate) fn change_log(&self) -> Option<&ChangeLog<K, V>> {
+        self.changes.as_ref()
+    }
+
+    pub(crate) fn set_change_log(&mut self, log: Option<ChangeLog<K, V>>) {
+        self.changes = log;
+    }
+
+    // log the mutation that just bumped the version, the event is only built
+    // when changes are tracked
+    fn record_change<F: FnOnce(&Self) -> ChangeEvent<K, V>>(&mut self, event: F) {
+        if self.changes.is_none() {
+            return;
+        }
+        let event = event(self);
+        let version = self.version;
+        if let Some(log) = &mut self.changes {
+            log.record(event, version);
+        }
+    }
+
+    pub(crate) fn set_duplicate_audit(&mut self, capacity: usize) {
+        self.duplicate_audit = Some(DuplicateAudit::with_capacity(capacity));
+    }
+
+    #[inline]
+    fn record_duplicate(&mut self, key: K, resolution: Resolution) {
+        if let Some(audit) = &mut self.duplicate_audit {
+            audit.record(key, resolution);
+        }
+    }
+
+    #[inline]
+    fn bump_version(&mut self) {
+        self.version = self.version.wrapping_add(1);
+    }
+
+    // the entry at a position with its value borrowed mutably, for cursors
+    pub(crate) fn entry_mut(&mut self, i: usize) -> Option<(&K, &mut V)> {
+        if i >= self.len {
+            return None;
+        }
+        // the value may be changed through the reference
+        self.bump_version();
+        Some((&self.keys[i], &mut self.values[i]))
+    }
+
+    // insert a new key at a position, false if the key is already in the dictionary
+    pub(crate) fn insert_at(&mut self, at: usize, key: K, value: V) -> bool {
+        self.assert_unsealed();
+        let key = self.canonical_new(key);
+        if self.has_key(&key) {
+            return false;
+        }
+        self.insert_new_canonical(key, value, at);
+        true
     }
 
-    pub fn sort_by_keys(&mut self) {
-        // use built in sort to sort keys
-        // iter through the map and swap each value in value vec
-        // recompute map with new indexs
-        self.keys.sort();
-        // swap indexes in values
-        // old index -> new index
-        // once we reach mid point, all are correct
-        for (new_i, key) in self.keys[..self.len / 2].iter().enumerate() {
-            let old_i = *self.key_map.get(&key).unwrap();
-            let temp = self.values[new_i].to_owned();
-            self.values[new_i] = self.values[old_i].to_owned();
-            self.values[old_i] = temp;
+    // remove the entry at a position, shifting the positions after it down
+    pub(crate) fn remove_at(&mut self, at: usize) -> (K, V) {
+        self.assert_unsealed();
+        let key = self.keys.remove(at);
+        let value = self.values.remove(at);
+        self.key_map.remove(&key);
+        for key in &self.keys[at..] {
+            *self.key_map.get_mut(key).unwrap() -= 1;
         }
-        // recompute the key value index map
-        self.recompute_map();
+        self.len -= 1;
+        self.bump_version();
+        self.notify_remove(at, &key, &value);
+        (key, value)
+    }
+
+    // lets tests break the index to check that it is detected and repaired
+    #[cfg(test)]
+    pub(crate) fn key_map_mut(&mut self) -> &mut S::Index {
+        &mut self.key_map
     }
 
+    pub(crate) fn observer(&self) -> Option<&Observer<K, V>> {
+        self.observer.as_ref()
+    }
+
+    pub(crate) fn set_observer(&mut self, observer: Option<Observer<K, V>>) {
+        self.observer = observer;
+    }
+
+    // report a mutation that just happened, the observer is taken out for the call
+    // so report can read the dictionary
     #[inline]
-    fn recompute_map(&mut self) {
-        for (i, key) in self.keys.iter().enumerate() {
-            let index = self.key_map.get_mut(&key).unwrap();
-            *index = i;
+    fn notify<F: FnOnce(&mut Observer<K, V>, &Self)>(&mut self, report: F) {
+        if let Some(mut observer) = self.observer.take() {
+            report(&mut observer, self);
+            self.observer = Some(observer);
+        }
+    }
+
+    fn notify_insert(&mut self, index: usize) {
+        self.notify(|observe, dict| {
+            observe(Mutation::Insert {
+                index,
+                key: &dict.keys[index],
+                value: &dict.values[index],
+            })
+        });
+    }
+
+    fn notify_update(&mut self, index: usize, old: &V) {
+        self.notify(|observe, dict| {
+            observe(Mutation::Update {
+                index,
+                key: &dict.keys[index],
+                old,
+                value: &dict.values[index],
+            })
+        });
+    }
+
+    fn notify_remove(&mut self, index: usize, key: &K, value: &V) {
+        self.notify(|observe, _| observe(Mutation::Remove { index, key, value }));
+    }
+
+    // report the removal of the entries from at on, before they are removed
+    fn notify_removed_tail(&mut self, at: usize) {
+        self.notify(|observe, dict| {
+            for (key, value) in dict.keys[at..].iter().zip(&dict.values[at..]) {
+                observe(Mutation::Remove {
+                    index: at,
+                    key,
+                    value,
+                });
+            }
+        });
+    }
+
+    fn notify_rename(&mut self, index: usize, old: &K) {
+        self.notify(|observe, dict| {
+            observe(Mutation::Rename {
+                index,
+                old,
+                key: &dict.keys[index],
+            })
+        });
+    }
+
+    fn notify_reorder(&mut self) {
+        self.notify(|observe, dict| observe(Mutation::Reorder { keys: &dict.keys }));
+    }
+
+    pub fn iter<'a>(&'a self) -> DictIter<'a, K, V> {
+        DictIter {
+            inner: self.keys.iter().zip(self.values.iter()),
+        }
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> DictIterMut<'a, K, V> {
+        // values may be changed through the iterator
+        self.bump_version();
+        DictIterMut {
+            inner: self.keys.iter_mut().zip(self.values.iter_mut()),
+        }
+    }
+
+    /// iterate from the last entry to the first, like `reversed(d.items())` in python
+    /// the same as `iter().rev()`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    /// let keys: Vec<&i32> = dict.iter_rev().map(|(k, _)| k).collect();
+    /// assert_eq!(keys, vec![&3, &2, &1]);
+    /// ```
+    pub fn iter_rev(&self) -> Rev<DictIter<'_, K, V>> {
+        self.iter().rev()
+    }
+
+    /// an owned copy of the keys in order
+    /// the copy does not borrow the dictionary, so it can be iterated while mutating
+    pub fn keys_snapshot(&self) -> Vec<K> {
+        self.keys.clone()
+    }
+
+    /// visit each key of a snapshot with mutable access to the whole dictionary
+    /// this is the `for k in list(d)` idiom from python, the closure may insert, remove
+    /// or change entries freely. Keys from the snapshot that are no longer in the
+    /// dictionary when their turn comes are skipped, keys added along the way are not visited.
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut stock = Dictionary::from([("apple", 3), ("pear", 0), ("fig", 1)]);
+    /// let keys = stock.keys_snapshot();
+    /// stock.modify_each(keys, |dict, key| {
+    ///     if dict.get(key) == Some(0) {
+    ///         dict.remove(key);
+    ///     } else if let Some(count) = dict.get_mut(key) {
+    ///         *count *= 10;
+    ///     }
+    /// });
+    /// assert_eq!(stock.keys(), &vec!["apple", "fig"]);
+    /// assert_eq!(stock.values(), &vec![30, 10]);
+    /// ```
+    pub fn modify_each<I, F>(&mut self, keys: I, mut f: F)
+    where
+        I: IntoIterator<Item = K>,
+        F: FnMut(&mut Dictionary<K, V, S>, K),
+    {
+        for key in keys {
+            let key = self.canonical(key);
+            if self.has_key(&key) {
+                f(self, key);
+            }
         }
     }
+}
+
+impl<K: Eq + Clone + Ord, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// sort the dictionary by keys in ascending order
+    /// range queries use binary search until the next mutation.
+    /// Keys are unique, so the order is fully determined and stability does not arise
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn sort_by_keys(&mut self) {
+        self.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+        self.sorted_version = Some(self.version);
+    }
 
+    /// sort by keys like sort_by_keys, with an unstable sort of the positions
+    /// the result is the same since keys are unique, this only skips the merge sort
+    /// buffer and usually runs faster
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn sort_unstable_by_keys(&mut self) {
+        self.assert_unsealed();
+        let mut order: Vec<usize> = (0..self.len).collect();
+        order.sort_unstable_by(|&a, &b| self.keys[a].cmp(&self.keys[b]));
+        self.permute(order);
+        self.sorted_version = Some(self.version);
+    }
+}
+
+impl<K: Eq + Clone, V: Clone + Ord, S: BuildIndex<K>> Dictionary<K, V, S> {
     /// Sort the dictionary by values.
-    /// keys
+    /// keys move with their values, the sort is stable and equal values are
+    /// guaranteed to keep their relative order
+    /// this is O(n log n) and does not clone any keys or values
+    /// # Panics
+    /// panics if the dictionary is sealed
     /// # Example
     /// ```
     /// use rust_dict::dict::Dictionary;
@@ -372,101 +2977,113 @@ impl<
     /// assert_eq!(dict.keys(), &vec![2, 3, 1, 5]);
     /// ```
     pub fn sort_by_values(&mut self) {
-        // start with bubble sort
-        // when we swap, swap both
-        // starting with bubble sort so we can swap both the keys and the values when sorting
-        // there is probably a better way to do this
-        for i in 0..self.len {
-            let mut swapped = false;
-            for j in 0..self.len - i - 1 {
-                if self.values[j] > self.values[j + 1] {
-                    swapped = true;
-                    // swap both keys and values
-                    self.keys.swap(j, j + 1);
-                    self.values.swap(j, j + 1);
-                }
-            }
-            if !swapped {
-                break;
-            }
-        }
-        // recompute the key value index map
-        self.recompute_map();
+        self.assert_unsealed();
+        // sort the positions by value, then move the entries into that order
+        // the sort is stable so equal values keep their relative order
+        let mut order: Vec<usize> = (0..self.keys.len()).collect();
+        order.sort_by(|&a, &b| self.values[a].cmp(&self.values[b]));
+        self.permute(order);
     }
 
-    fn has_key(&self, key: &K) -> bool {
-        return self.key_map.contains_key(key);
+    /// sort by values without keeping equal values in their relative order
+    /// usually faster than sort_by_values, use it when ties do not matter
+    /// # Panics
+    /// panics if the dictionary is sealed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([("a", 3), ("b", 1), ("c", 2)]);
+    /// dict.sort_unstable_by_values();
+    /// assert_eq!(dict.keys(), &vec!["b", "c", "a"]);
+    /// ```
+    pub fn sort_unstable_by_values(&mut self) {
+        self.assert_unsealed();
+        let mut order: Vec<usize> = (0..self.keys.len()).collect();
+        order.sort_unstable_by(|&a, &b| self.values[a].cmp(&self.values[b]));
+        self.permute(order);
     }
+}
 
-    pub fn iter<'a>(&'a self) -> DictIter<'a, K, V> {
-        DictIter {
-            key_iter: self.keys.iter(),
-            val_iter: self.values.iter(),
-        }
+impl<K: Eq + Clone, V: Copy, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// get a value by key for Copy values
+    /// a plain copy out of the values vec, no clone call and no borrow of the dictionary
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let prices = Dictionary::from([("apple", 1.25), ("pear", 0.5)]);
+    /// assert_eq!(prices.get_copied(&"pear"), Some(0.5));
+    /// assert_eq!(prices.values_copied().sum::<f64>(), 1.75);
+    /// ```
+    #[inline]
+    pub fn get_copied(&self, key: &K) -> Option<V> {
+        self.index_of(key).map(|i| self.values[i])
     }
 
-    pub fn iter_mut<'a>(&'a mut self) -> DictIterMut<'a, K, V> {
-        DictIterMut {
-            key_iter: self.keys.iter_mut(),
-            val_iter: self.values.iter_mut(),
-        }
+    /// the values by copy, in order
+    pub fn values_copied(&self) -> Copied<Iter<'_, V>> {
+        self.values.iter().copied()
     }
 }
 
-impl<K, V> Into<DictIntoIter<K, V>> for Dictionary<K, V> {
-    fn into(self) -> DictIntoIter<K, V> {
-        DictIntoIter {
-            key_iter: self.keys.into_iter(),
-            val_iter: self.values.into_iter(),
-        }
+impl<K, V, S: BuildIndex<K>> From<Dictionary<K, V, S>> for DictIntoIter<K, V> {
+    fn from(dict: Dictionary<K, V, S>) -> DictIntoIter<K, V> {
+        DictIntoIter::new(dict.keys, dict.values)
     }
 }
 
 pub struct DictIntoIter<K, V> {
-    key_iter: IntoIter<K>,
-    val_iter: IntoIter<V>,
+    inner: Zip<IntoIter<K>, IntoIter<V>>,
+}
+
+impl<K, V> DictIntoIter<K, V> {
+    fn new(keys: Vec<K>, values: Vec<V>) -> DictIntoIter<K, V> {
+        debug_assert_eq!(keys.len(), values.len());
+        DictIntoIter {
+            inner: keys.into_iter().zip(values),
+        }
+    }
 }
 
 // Gets collect for free here
 // collect will return a Vec<(K,V)>
-impl<'a, K, V> Iterator for DictIntoIter<K, V> {
+impl<K, V> Iterator for DictIntoIter<K, V> {
     type Item = (K, V);
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let next_key = self.key_iter.next();
-        let next_val = self.val_iter.next();
-        // make sure always Some, Some or None, None
-        #[cfg(debug_assertions)]
-        {
-            if next_key.is_some() {
-                debug_assert!(next_key.is_some() && next_val.is_some());
-            } else {
-                debug_assert!(next_key.is_none() && next_val.is_none());
-            }
-        }
-        match (next_key, next_val) {
-            (Some(key), Some(val)) => return Some((key, val)),
-            _ => return None,
-        }
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
-impl<
-        K: PartialOrd + PartialEq + Hash + Eq + Clone + Ord + Copy,
-        V: Clone + Ord + PartialEq + PartialOrd + Eq,
-    > Into<Dictionary<K, V>> for DictIntoIter<K, V>
-{
-    fn into(self) -> Dictionary<K, V> {
+impl<K, V> DoubleEndedIterator for DictIntoIter<K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for DictIntoIter<K, V> {}
+
+impl<K, V> FusedIterator for DictIntoIter<K, V> {}
+
+impl<K: Hash + Eq + Clone, V: Clone> From<DictIntoIter<K, V>> for Dictionary<K, V> {
+    fn from(iter: DictIntoIter<K, V>) -> Dictionary<K, V> {
         // utility to go back to the Dictionary
-        debug_assert_eq!(self.key_iter.len(), self.val_iter.len());
-        let len = self.key_iter.len();
+        let len = iter.len();
         let capacity = (len as f32 * 1.1_f32) as usize;
         let mut keys: Vec<K> = Vec::with_capacity(capacity);
         let mut values: Vec<V> = Vec::with_capacity(capacity);
         let mut key_map: HashMap<K, usize> = HashMap::with_capacity(capacity);
 
         // iter through self and collect the the items to reconstruct the Dictionary
-        for (i, (key, value)) in self.enumerate() {
-            keys.push(key);
+        for (i, (key, value)) in iter.enumerate() {
+            keys.push(key.clone());
             values.push(value);
             key_map.insert(key, i);
         }
@@ -475,106 +3092,519 @@ impl<
             capacity,
             keys,
             key_map,
+            index_builder: RandomState::new(),
             values,
+            version: 0,
+            canonicalize: None,
+            sorted_version: None,
+            duplicate_audit: None,
+            hot: Vec::new(),
+            sealed: false,
+            tracked_digest: None,
+            changes: None,
+            format_options: FormatOptions::default(),
+            key_limit: None,
+            growth: GrowthStrategy::Doubling,
+            observer: None,
+        }
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K> + Default> FromIterator<(K, V)>
+    for Dictionary<K, V, S>
+{
+    /// Build a Dictionary from an iterator of key value pairs
+    /// the iteration order becomes the insertion order
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Dictionary<K, V, S> {
+        let mut dict = Dictionary::with_index(S::default());
+        dict.extend(iter);
+        dict
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Extend<(K, V)> for Dictionary<K, V, S> {
+    /// push_back each pair from the iterator onto the end of the dictionary
+    /// # Panics
+    /// panics if the dictionary is sealed and a key is new
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        // reserve on the lower bound so we do not resize on every push
+        let (lower, _) = iter.size_hint();
+        let free = self.capacity - self.len;
+        if lower > free {
+            self.reserve(lower - free);
         }
+        for (key, value) in iter {
+            self.push_back(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, const N: usize> From<[(K, V); N]> for Dictionary<K, V> {
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(1, "a"), (2, "b")]);
+    /// assert_eq!(dict.keys(), &vec![1, 2]);
+    /// assert_eq!(dict.get(2), Some("b"));
+    /// ```
+    fn from(arr: [(K, V); N]) -> Dictionary<K, V> {
+        let mut dict = Dictionary::with_capacity(N);
+        dict.extend(arr);
+        dict
     }
 }
 
-impl<K, V> IntoIterator for Dictionary<K, V> {
+impl<K, V, S: BuildIndex<K>> IntoIterator for Dictionary<K, V, S> {
     type Item = (K, V);
     type IntoIter = DictIntoIter<K, V>;
     fn into_iter(self) -> DictIntoIter<K, V> {
-        DictIntoIter {
-            key_iter: self.keys.into_iter(),
-            val_iter: self.values.into_iter(),
-        }
+        DictIntoIter::new(self.keys, self.values)
     }
 }
 
 pub struct DictIter<'a, K, V> {
-    key_iter: Iter<'a, K>,
-    val_iter: Iter<'a, V>,
+    inner: Zip<Iter<'a, K>, Iter<'a, V>>,
 }
 
 impl<'a, K, V> Iterator for DictIter<'a, K, V> {
     type Item = (&'a K, &'a V);
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let next_key = self.key_iter.next();
-        let next_val = self.val_iter.next();
-
-        // make sure always Some, Some or None, None
-        #[cfg(debug_assertions)]
-        {
-            if next_key.is_some() {
-                debug_assert!(next_key.is_some() && next_val.is_some());
-            } else {
-                debug_assert!(next_key.is_none() && next_val.is_none());
-            }
-        }
+        self.inner.next()
+    }
 
-        match (next_key, next_val) {
-            (Some(key), Some(val)) => return Some((key, val)),
-            _ => return None,
-        }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+impl<'a, K, V> DoubleEndedIterator for DictIter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for DictIter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for DictIter<'a, K, V> {}
+
 pub struct DictIterMut<'a, K, V> {
-    key_iter: IterMut<'a, K>,
-    val_iter: IterMut<'a, V>,
+    inner: Zip<IterMut<'a, K>, IterMut<'a, V>>,
 }
 
 impl<'a, K, V> Iterator for DictIterMut<'a, K, V> {
     type Item = (&'a mut K, &'a mut V);
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let next_key = self.key_iter.next();
-        let next_val = self.val_iter.next();
-
-        // make sure always Some, Some or None, None
-        #[cfg(debug_assertions)]
-        {
-            if next_key.is_some() {
-                debug_assert!(next_key.is_some() && next_val.is_some());
-            } else {
-                debug_assert!(next_key.is_none() && next_val.is_none());
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for DictIterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for DictIterMut<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for DictIterMut<'a, K, V> {}
+
+// an iterator over one column, forwarding to the Vec iterator
+macro_rules! column_iter {
+    ($(#[$doc:meta])* $name:ident<$($lt:lifetime,)? $t:ident>, $inner:ty, $item:ty) => {
+        $(#[$doc])*
+        pub struct $name<$($lt,)? $t> {
+            inner: $inner,
+        }
+
+        impl<$($lt,)? $t> Iterator for $name<$($lt,)? $t> {
+            type Item = $item;
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.inner.next()
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.inner.size_hint()
             }
         }
-        match (next_key, next_val) {
-            (Some(key), Some(val)) => return Some((key, val)),
-            _ => return None,
+
+        impl<$($lt,)? $t> DoubleEndedIterator for $name<$($lt,)? $t> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.inner.next_back()
+            }
         }
-    }
+
+        impl<$($lt,)? $t> ExactSizeIterator for $name<$($lt,)? $t> {}
+
+        impl<$($lt,)? $t> FusedIterator for $name<$($lt,)? $t> {}
+    };
 }
 
+column_iter!(
+    /// The keys in order, created with `Dictionary::iter_keys`
+    Keys<'a, K>, Iter<'a, K>, &'a K
+);
+column_iter!(
+    /// The values in order, created with `Dictionary::iter_values`
+    Values<'a, V>, Iter<'a, V>, &'a V
+);
+column_iter!(
+    /// The owned keys in order, created with `Dictionary::into_keys`
+    IntoKeys<K>, IntoIter<K>, K
+);
+column_iter!(
+    /// The owned values in order, created with `Dictionary::into_values`
+    IntoValues<V>, IntoIter<V>, V
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn dictiter_to_dictionary() {
-        let mut dict = Dictionary::<i32, String>::new();
-        dict.push_back(1, "my_string".into());
-        dict.push_back(2, "my_string2".into());
+    fn dictiter_to_dictionary() {
+        let mut dict = Dictionary::<i32, String>::new();
+        dict.push_back(1, "my_string".into());
+        dict.push_back(2, "my_string2".into());
+
+        let mut dict2 = Dictionary::<i32, String>::new();
+        dict2.push_back(1, "my_string".into());
+        dict2.push_back(2, "my_string2".into());
+
+        let dict2iter = dict2.into_iter();
+
+        let dict2: Dictionary<i32, String> = dict2iter.into();
+        assert_eq!(dict, dict2);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut dict = Dictionary::<i32, String>::new();
+        dict.push_back(1, "my_string".into());
+        dict.push_back(2, "my_string2".into());
+
+        let mut dict_iter = dict.into_iter();
+        assert_eq!(dict_iter.next(), Some((1, "my_string".to_string())));
+        assert_eq!(dict_iter.next(), Some((2, "my_string2".to_string())));
+    }
+
+    #[test]
+    fn rename_keys_applies_together() {
+        let mut dict: Dictionary<&str, u32> = ["a", "b", "c", "d"].into_iter().zip(1..).collect();
+        let version = dict.version();
+        dict.rename_keys(&Dictionary::from([("c", "e"), ("b", "c"), ("a", "b")]))
+            .unwrap();
+        assert_eq!(dict.keys(), &vec!["b", "c", "e", "d"]);
+        assert_eq!(dict.values(), &vec![1, 2, 3, 4]);
+        assert_eq!(dict.get("e"), Some(3));
+        assert_eq!(dict.index_of(&"b"), Some(0));
+        assert!(dict.index_is_consistent());
+        assert_eq!(dict.version(), version + 1);
+
+        // renames of missing keys and to the same key change nothing
+        dict.rename_keys(&Dictionary::from([("x", "y"), ("d", "d")]))
+            .unwrap();
+        assert_eq!(dict.version(), version + 1);
+    }
+
+    #[test]
+    fn rename_keys_errors_leave_dictionary_unchanged() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+        let before = dict.clone();
+        assert_eq!(
+            dict.rename_keys(&Dictionary::from([("a", "x"), ("b", "x")])),
+            Err(RenameError::DuplicateTarget {
+                first: 0,
+                duplicate: 1
+            })
+        );
+        assert_eq!(
+            dict.rename_keys(&Dictionary::from([("a", "b"), ("c", "z")])),
+            Err(RenameError::TargetExists { rename: 0 })
+        );
+        assert_eq!(dict, before);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn rename_keys_swaps_and_cycles() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+        dict.rename_keys(&Dictionary::from([("a", "b"), ("b", "a")]))
+            .unwrap();
+        assert_eq!(dict.keys(), &vec!["b", "a", "c"]);
+        assert_eq!(dict.get("a"), Some(2));
+        dict.rename_keys(&Dictionary::from([
+            ("x", "y"),
+            ("a", "c"),
+            ("b", "a"),
+            ("c", "b"),
+        ]))
+        .unwrap();
+        assert_eq!(dict.keys(), &vec!["a", "c", "b"]);
+        assert_eq!(dict.values(), &vec![1, 2, 3]);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn rename_keys_error_keeps_key_limit() {
+        use crate::key_limit::TruncateKey;
+
+        let mut dict = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        dict.push_back("alpha".into(), 1);
+        dict.push_back("beta".into(), 2);
+        let long = "gamma.with.a.long.name".to_string();
+        let renames = Dictionary::from([
+            ("alpha".to_string(), long.clone()),
+            ("beta".to_string(), long.clone()),
+        ]);
+        assert!(dict.rename_keys(&renames).is_err());
+        let short = long.truncate_key(12).unwrap();
+        assert_eq!(dict.key_limit().unwrap().original(&short), None);
+        dict.rename_keys(&Dictionary::from([("alpha".to_string(), long.clone())]))
+            .unwrap();
+        assert_eq!(dict.original_key(&short), Some(&long));
+    }
+
+    #[test]
+    fn clone_owned_keys_and_values() {
+        let mut dict: Dictionary<String, Vec<String>> = Dictionary::new();
+        dict.push_back("hosts".into(), vec!["a".into(), "b".into()]);
+        dict.push_back("users".into(), Vec::new());
+        let copy = dict.clone();
+        dict.get_mut("hosts".into()).unwrap().push("c".into());
+        dict.remove("users".into());
+        assert_eq!(copy.len(), 2);
+        assert_eq!(copy.get("hosts".into()).unwrap().len(), 2);
+        assert!(copy.index_is_consistent());
+        assert_eq!(
+            format!("{:?}", copy),
+            r#"{"hosts": ["a", "b"], "users": []}"#
+        );
+    }
+
+    #[test]
+    fn column_iterators() {
+        let mut dict: Dictionary<String, u32> = Dictionary::new();
+        dict.push_back("x".into(), 1);
+        dict.push_back("y".into(), 2);
+        dict.push_back("z".into(), 3);
+        let mut keys = dict.iter_keys();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys.next_back().map(String::as_str), Some("z"));
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            dict.iter_values().copied().collect::<Vec<u32>>(),
+            vec![1, 2, 3]
+        );
+        let mut owned = dict.clone().into_keys();
+        assert_eq!(owned.next_back(), Some(String::from("z")));
+        assert_eq!(owned.len(), 2);
+        let values: Vec<u32> = dict.into_values().collect();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn map_values_keeps_index() {
+        let mut dict = Dictionary::from([(3, 'c'), (1, 'a'), (2, 'b')]);
+        dict.remove(1);
+        let codes = dict.map_values(|c| *c as u32);
+        assert_eq!(codes.keys(), &vec![3, 2]);
+        assert_eq!(codes.values(), &vec![99, 98]);
+        assert_eq!(codes.get(2), Some(98));
+        assert!(codes.index_is_consistent());
+        assert_eq!(codes.capacity(), 2);
+    }
+
+    #[test]
+    fn map_merges_equal_keys() {
+        let dict: Dictionary<u32, u32> = (1..=6).map(|i| (i, i * 10)).collect();
+        let by_parity = dict.map(|k, v| (k % 2 == 0, *v));
+        assert_eq!(by_parity.keys(), &vec![false, true]);
+        assert_eq!(by_parity.values(), &vec![50, 60]);
+        assert!(by_parity.index_is_consistent());
+        let empty: Dictionary<u8, u8> = Dictionary::new();
+        assert!(empty.map(|k, v| (*v, *k)).is_empty());
+    }
+
+    #[test]
+    fn sorted_indices_leave_dictionary() {
+        let dict = Dictionary::from([("x", 3), ("y", 1), ("z", 3), ("w", 2)]);
+        let version = dict.version();
+        let order = dict.sorted_indices_by(|(_, a), (_, b)| a.cmp(b));
+        assert_eq!(order, vec![1, 3, 0, 2]);
+        assert_eq!(dict.version(), version);
+        let mut sorted = dict.clone();
+        sorted.sort_by(|(_, a), (_, b)| a.cmp(b));
+        assert_eq!(dict.take_indices(&order), sorted);
+        assert!(Dictionary::<u8, u8>::new()
+            .sorted_indices_by(|a, b| a.cmp(&b))
+            .is_empty());
+    }
+
+    #[test]
+    fn take_indices_projects() {
+        let dict: Dictionary<u32, char> = (0..5).zip('a'..).collect();
+        let sample = dict.take_indices(&[4, 0, 4, 2]);
+        assert_eq!(sample.keys(), &vec![4, 0, 2]);
+        assert_eq!(sample.get(2), Some('c'));
+        assert!(sample.index_is_consistent());
+        assert!(dict.take_indices(&[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of bounds")]
+    fn take_indices_out_of_bounds() {
+        let dict = Dictionary::from([(1, 1)]);
+        dict.take_indices(&[0, 5]);
+    }
+
+    #[test]
+    fn filtered_keeps_relative_order() {
+        let dict: Dictionary<u32, u32> = (0..10).rev().map(|i| (i, i * i)).collect();
+        let even = dict.filtered(|k, _| k % 2 == 0);
+        assert_eq!(even.keys(), &vec![8, 6, 4, 2, 0]);
+        assert_eq!(even.get(6), Some(36));
+        assert!(even.index_is_consistent());
+        assert_eq!(dict.len(), 10);
+        assert!(dict.filtered(|_, _| false).is_empty());
+    }
+
+    #[test]
+    fn partition_covers_every_entry() {
+        let dict = Dictionary::from([("a", 1), ("b", -2), ("c", 3), ("d", -4)]);
+        let (positive, negative) = dict.partition(|_, v| *v > 0);
+        assert_eq!(positive.keys(), &vec!["a", "c"]);
+        assert_eq!(negative.keys(), &vec!["b", "d"]);
+        assert_eq!(negative.index_of(&"d"), Some(1));
+        assert_eq!(positive.len() + negative.len(), dict.len());
+        let (all, none) = dict.partition(|_, _| true);
+        assert_eq!(all, dict);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn try_reserve_failure_keeps_dictionary() {
+        let mut dict = Dictionary::from([(1u64, 1u64), (2, 2)]);
+        let capacity = dict.capacity();
+        assert!(dict.try_reserve(usize::MAX / 2).is_err());
+        assert_eq!(dict.capacity(), capacity);
+        assert_eq!(dict.get(2), Some(2));
+        dict.try_reserve(10).unwrap();
+        assert_eq!(dict.capacity(), capacity + 10);
+        assert!(dict.keys().capacity() >= 12);
+    }
+
+    #[test]
+    fn shrink_to_keeps_minimum() {
+        let mut dict: Dictionary<u32, u32> = Dictionary::with_capacity(100);
+        dict.extend((0..10).map(|i| (i, i)));
+        dict.shrink_to(20);
+        assert_eq!(dict.capacity(), 20);
+        assert!(dict.keys().capacity() >= 20 && dict.keys().capacity() < 100);
+        dict.shrink_to(0);
+        assert_eq!(dict.capacity(), 10);
+        dict.shrink_to(50);
+        assert_eq!(dict.capacity(), 10);
+        assert_eq!(dict.get(9), Some(9));
+    }
+
+    #[test]
+    fn bulk_construction() {
+        let entries: Vec<(u32, u32)> = (0..1000).map(|i| (i * 2, i)).collect();
+        let sorted = Dictionary::from_sorted_entries(entries.clone());
+        assert!(sorted.is_sorted());
+        assert_eq!(sorted.len(), 1000);
+        assert_eq!(sorted.capacity(), 1000);
+        assert!(sorted.index_is_consistent());
+        assert_eq!(sorted, Dictionary::from_vec(entries));
 
-        let mut dict2 = Dictionary::<i32, String>::new();
-        dict2.push_back(1, "my_string".into());
-        dict2.push_back(2, "my_string2".into());
+        let mut repeated =
+            Dictionary::from_vec(vec![(3, 'a'), (1, 'b'), (3, 'c'), (1, 'd'), (2, 'e')]);
+        assert_eq!(repeated.keys(), &vec![3, 1, 2]);
+        assert_eq!(repeated.values(), &vec!['c', 'd', 'e']);
+        assert!(repeated.index_is_consistent());
+        repeated.push_back(4, 'f');
+        assert_eq!(repeated.index_of(&4), Some(3));
+    }
 
-        let dict2iter = dict2.into_iter();
+    #[test]
+    #[should_panic(expected = "duplicate key in sorted entries")]
+    fn sorted_entries_reject_duplicates() {
+        Dictionary::from_sorted_entries(vec![(1, 'a'), (1, 'b')]);
+    }
 
-        let dict2: Dictionary<i32, String> = dict2iter.into();
-        assert_eq!(dict, dict2);
+    #[test]
+    fn display_compact_and_pretty() {
+        let mut dict: Dictionary<String, String> = Dictionary::new();
+        assert_eq!(dict.to_string_compact(), "{}");
+        assert_eq!(dict.to_string_pretty(), "{}");
+        dict.push_back("path".into(), "/tmp/x".into());
+        dict.push_back("multi line".into(), "a\nb".into());
+        dict.push_back(" padded".into(), String::new());
+        dict.push_back("quote".into(), "say \"hi\"".into());
+        let compact = dict.to_string_compact();
+        assert_eq!(
+            compact,
+            r#"{path: /tmp/x, multi line: "a\nb", " padded": "", quote: "say \"hi\""}"#
+        );
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            dict.to_string_pretty(),
+            "{\n    path: /tmp/x,\n    multi line: \"a\\nb\",\n    \" padded\": \"\",\n    quote: \"say \\\"hi\\\"\",\n}"
+        );
     }
 
     #[test]
-    fn test_iter() {
-        let mut dict = Dictionary::<i32, String>::new();
-        dict.push_back(1, "my_string".into());
-        dict.push_back(2, "my_string2".into());
+    fn display_numbers_unquoted() {
+        let dict = Dictionary::from([(1, -2.5), (10, 3.0)]);
+        assert_eq!(dict.to_string(), "{1: -2.5, 10: 3}");
+        assert_eq!(format!("{:#}", dict), "{\n    1: -2.5,\n    10: 3,\n}");
+        assert_eq!(format!("{}", dict), dict.to_string_compact());
+    }
 
-        let mut dict_iter = dict.into_iter();
-        assert_eq!(dict_iter.next(), Some((1, "my_string".to_string())));
-        assert_eq!(dict_iter.next(), Some((2, "my_string2".to_string())));
+    #[test]
+    fn default_is_empty() {
+        #[derive(Default)]
+        struct Config {
+            settings: Dictionary<String, String>,
+            sorted: Dictionary<String, u32, crate::index::BTreeIndex>,
+        }
+        let mut config = Config::default();
+        assert!(config.settings.is_empty() && config.sorted.is_empty());
+        config.sorted.push_back("b".into(), 2);
+        config.sorted.push_back("a".into(), 1);
+        assert_eq!(config.sorted.get("a".into()), Some(1));
+        assert_eq!(Dictionary::<u8, u8>::default(), Dictionary::new());
+    }
+
+    #[test]
+    fn const_construction() {
+        type Fixed = BuildHasherDefault<std::hash::DefaultHasher>;
+        const EMPTY: Dictionary<&str, u32, Fixed> = Dictionary::with_default_hasher();
+        let mut dict = EMPTY;
+        assert_eq!(dict.len(), 0);
+        assert_eq!(dict.capacity(), 0);
+        dict.push_back("a", 1);
+        dict.push_back("b", 2);
+        assert_eq!(dict.get("b"), Some(2));
+        assert_eq!(
+            dict.key_indices().collect::<Vec<_>>(),
+            vec![(&"a", 0), (&"b", 1)]
+        );
     }
 
     #[test]
@@ -703,8 +3733,83 @@ mod tests {
         dict.push_back(1, 7);
         dict.push_back(2, 1);
         dict.push_back(5, 9);
-        dict.insert(6, 7, 2);
+        assert_eq!(dict.insert(6, 7, 2), Ok(true));
         assert_eq!(dict.keys(), &vec![3, 1, 6, 2, 5]);
+        assert_eq!(dict.get(6), Some(7));
+        assert_eq!(dict.index_of(&2), Some(3));
+        assert_eq!(dict.len(), 5);
+        assert_eq!(dict.insert(7, 0, 5), Ok(true));
+        assert_eq!(dict.back(), Some((&7, &0)));
+        assert_eq!(
+            dict.insert(8, 0, 7),
+            Err(DictError::IndexOutOfBounds { index: 7, len: 6 })
+        );
+    }
+
+    #[test]
+    fn shift_insert_moves_or_inserts() {
+        let mut dict: Dictionary<u32, u32> = (0..5).map(|i| (i, i)).collect();
+        assert_eq!(dict.shift_insert(10, 100, 2), Ok(None));
+        assert_eq!(dict.shift_insert(4, 40, 0), Ok(Some(4)));
+        assert_eq!(dict.keys(), &vec![4, 0, 1, 10, 2, 3]);
+        assert_eq!(dict.len(), 6);
+        assert_eq!(dict.shift_insert(11, 0, 6), Ok(None));
+        assert_eq!(
+            dict.shift_insert(11, 0, 7),
+            Err(DictError::IndexOutOfBounds { index: 7, len: 7 })
+        );
+        // an existing key can not move past the last position
+        assert!(dict.shift_insert(4, 0, 7).is_err());
+        assert_eq!(dict.get(4), Some(40));
+
+        assert_eq!(dict.remove(10), Some(100));
+        assert_eq!(dict.index_of(&2), Some(3));
+        assert_eq!(dict.shift_insert(10, 1, 6), Ok(None));
+        dict.sort_by_keys();
+        assert_eq!(dict.keys(), &vec![0, 1, 2, 3, 4, 10, 11]);
+        assert_eq!(dict.shift_insert(3, 33, 0), Ok(Some(3)));
+        assert!(!dict.is_sorted());
+        dict.sort_by_values();
+        assert_eq!(dict.keys(), &vec![0, 11, 1, 10, 2, 3, 4]);
+        for (i, key) in dict.keys().clone().iter().enumerate() {
+            assert_eq!(dict.index_of(key), Some(i));
+        }
+        assert!(dict.health().is_healthy());
+    }
+
+    #[test]
+    fn shift_insert_out_of_bounds_keeps_key_limit() {
+        use crate::key_limit::TruncateKey;
+
+        let mut dict = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        let long = "a.key.longer.than.twelve".to_string();
+        let short = long.truncate_key(12).unwrap();
+        assert!(dict.shift_insert(long.clone(), 1, 1).is_err());
+        assert_eq!(dict.key_limit().unwrap().original(&short), None);
+        assert_eq!(dict.shift_insert(long.clone(), 1, 0), Ok(None));
+        assert_eq!(dict.original_key(&short), Some(&long));
+        assert_eq!(dict.shift_insert(long, 2, 0), Ok(Some(1)));
+    }
+
+    #[test]
+    fn try_variants_leave_state_alone() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2)]);
+        let version = dict.version();
+        assert_eq!(
+            dict.try_push_back("b", 20),
+            Err(DictError::Occupied(OccupiedError {
+                key: "b",
+                value: 20,
+                index: 1
+            }))
+        );
+        let err = dict.try_insert("a", 10, 1).unwrap_err();
+        assert_eq!(err.to_string(), "key \"a\" is already at index 0");
+        assert_eq!(dict.version(), version);
+        assert_eq!(dict.try_push_back("c", 3), Ok(()));
+        assert_eq!(dict.try_insert("z", 0, 0), Ok(()));
+        assert_eq!(dict.keys(), &vec!["z", "a", "b", "c"]);
+        assert_eq!(dict.index_of(&"c"), Some(3));
     }
 
     #[test]
@@ -720,4 +3825,718 @@ mod tests {
         dict.push_back(6, 10);
         assert_eq!(dict.capacity(), 8);
     }
+
+    #[test]
+    fn from_iter() {
+        let dict: Dictionary<i32, i32> = vec![(3, 4), (1, 7), (2, 1)].into_iter().collect();
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.keys(), &vec![3, 1, 2]);
+        assert_eq!(dict.get(1), Some(7));
+    }
+
+    #[test]
+    fn extend() {
+        let mut dict = Dictionary::from([(1, 2), (2, 3)]);
+        dict.extend(vec![(3, 4), (4, 5), (5, 6)]);
+        assert_eq!(dict.len(), 5);
+        assert_eq!(dict.keys(), &vec![1, 2, 3, 4, 5]);
+        assert_eq!(dict.values(), &vec![2, 3, 4, 5, 6]);
+        assert!(dict.capacity() >= 5);
+    }
+
+    #[test]
+    fn parts_round_trip() {
+        let dict = Dictionary::from([(3, 4), (1, 7), (2, 1)]);
+        let (keys, values) = dict.into_parts();
+        assert_eq!(keys, vec![3, 1, 2]);
+        assert_eq!(values, vec![4, 7, 1]);
+        let dict = Dictionary::from_parts(keys, values).unwrap();
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.get(1), Some(7));
+        assert_eq!(dict.get(2), Some(1));
+    }
+
+    #[test]
+    fn from_parts_errors() {
+        assert_eq!(
+            Dictionary::from_parts(vec![1, 2], vec![1]),
+            Err(PartsError::LengthMismatch { keys: 2, values: 1 })
+        );
+        assert_eq!(
+            Dictionary::from_parts(vec![1, 2, 1], vec![1, 2, 3]),
+            Err(PartsError::DuplicateKey {
+                first: 0,
+                duplicate: 2
+            })
+        );
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30), (4, 40)]);
+        assert_eq!(dict.swap_remove(2), Some(20));
+        assert_eq!(dict.keys(), &vec![1, 4, 3]);
+        assert_eq!(dict.get(4), Some(40));
+        assert_eq!(dict.get_index(1), Some(40));
+        // removing the last entry does not move anything
+        assert_eq!(dict.swap_remove(3), Some(30));
+        assert_eq!(dict.keys(), &vec![1, 4]);
+        assert_eq!(dict.swap_remove(5), None);
+        assert_eq!(dict.len(), 2);
+    }
+
+    #[test]
+    fn swap_entries() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30)]);
+        dict.swap_entries(0, 2);
+        assert_eq!(dict.keys(), &vec![3, 2, 1]);
+        assert_eq!(dict.values(), &vec![30, 20, 10]);
+        assert_eq!(dict.get(1), Some(10));
+        assert_eq!(dict.get(3), Some(30));
+        dict.swap_entries(1, 1);
+        assert_eq!(dict.get(2), Some(20));
+    }
+
+    #[test]
+    fn remove_reindexes() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30), (4, 40)]);
+        dict.remove(2);
+        assert_eq!(dict.get_index(1), Some(30));
+        assert_eq!(dict.get(3), Some(30));
+        assert_eq!(dict.get(4), Some(40));
+        dict.remove(4);
+        assert_eq!(dict.keys(), &vec![1, 3]);
+        assert_eq!(dict.get(3), Some(30));
+    }
+
+    #[test]
+    fn remove_many() {
+        let mut dict: Dictionary<i32, i32> = (0..10).map(|i| (i, i * 10)).collect();
+        let removed = dict.remove_many(vec![8, 0, 3, 3, 42]);
+        assert_eq!(removed, vec![(0, 0), (3, 30), (8, 80)]);
+        assert_eq!(dict.keys(), &vec![1, 2, 4, 5, 6, 7, 9]);
+        assert_eq!(dict.len(), 7);
+        for (i, key) in dict.keys().iter().enumerate() {
+            assert_eq!(dict.get(*key), dict.get_index(i));
+        }
+        assert!(dict.remove_many(vec![100]).is_empty());
+    }
+
+    #[test]
+    fn retain() {
+        let mut dict: Dictionary<i32, i32> = (0..6).map(|i| (i, i)).collect();
+        dict.retain(|_, v| {
+            *v *= 2;
+            *v % 4 == 0
+        });
+        assert_eq!(dict.keys(), &vec![0, 2, 4]);
+        assert_eq!(dict.values(), &vec![0, 4, 8]);
+        assert_eq!(dict.get(4), Some(8));
+        assert_eq!(dict.get(1), None);
+    }
+
+    #[test]
+    fn retain_panic_keeps_entries() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut dict: Dictionary<i32, i32> = (0..6).map(|i| (i, i)).collect();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            dict.retain(|k, _| {
+                assert!(*k != 3, "keep panicked");
+                k % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+        // the entries walked before the panic are filtered, the rest stay in order
+        assert_eq!(dict.keys(), &vec![0, 2, 3, 4, 5]);
+        assert_eq!(dict.len(), 5);
+        assert_eq!(dict.get(5), Some(5));
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn push_back_upsert() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30)]);
+        assert_eq!(dict.push_back(2, 21), Some(20));
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.keys(), &vec![1, 2, 3]);
+        assert_eq!(dict.get(2), Some(21));
+        assert_eq!(dict.get_index(1), Some(21));
+        assert_eq!(dict.push_back(4, 40), None);
+        assert_eq!(dict.keys(), &vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn from_iter_last_wins() {
+        let dict: Dictionary<i32, i32> = vec![(1, 1), (2, 2), (1, 3)].into_iter().collect();
+        assert_eq!(dict.keys(), &vec![1, 2]);
+        assert_eq!(dict.get(1), Some(3));
+    }
+
+    #[test]
+    fn sort_keys_keeps_pairs() {
+        let mut dict = Dictionary::from([(3, 30), (1, 10), (2, 20)]);
+        dict.sort_by_keys();
+        assert_eq!(dict.keys(), &vec![1, 2, 3]);
+        assert_eq!(dict.values(), &vec![10, 20, 30]);
+        assert_eq!(dict.get(3), Some(30));
+
+        let mut dict: Dictionary<i32, i32> = (0..9).rev().map(|i| (i, i * 10)).collect();
+        dict.sort_by_keys();
+        for (key, value) in dict.iter() {
+            assert_eq!(*value, key * 10);
+        }
+    }
+
+    #[test]
+    fn pop() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20), (3, 30), (4, 40)]);
+        assert_eq!(dict.pop_back(), Some((4, 40)));
+        assert_eq!(dict.pop_front(), Some((1, 10)));
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(2), Some(20));
+        assert_eq!(dict.get_index(0), Some(20));
+        assert_eq!(dict.get(4), None);
+        dict.push_back(1, 11);
+        assert_eq!(dict.keys(), &vec![2, 3, 1]);
+        assert_eq!(dict.pop_front(), Some((2, 20)));
+        assert_eq!(dict.pop_front(), Some((3, 30)));
+        assert_eq!(dict.pop_back(), Some((1, 11)));
+        assert_eq!(dict.pop_back(), None);
+        assert_eq!(dict.pop_front(), None);
+    }
+
+    #[test]
+    fn drain() {
+        let mut dict: Dictionary<i32, i32> = (0..6).map(|i| (i, i * 10)).collect();
+        let drained: Vec<(i32, i32)> = dict.drain(1..=2).collect();
+        assert_eq!(drained, vec![(1, 10), (2, 20)]);
+        assert_eq!(dict.keys(), &vec![0, 3, 4, 5]);
+        assert_eq!(dict.get(4), Some(40));
+        assert_eq!(dict.get_index(1), Some(30));
+        assert_eq!(dict.get(1), None);
+
+        let drained: Vec<(i32, i32)> = dict.drain(3..).collect();
+        assert_eq!(drained, vec![(5, 50)]);
+        assert_eq!(dict.len(), 3);
+        dict.push_back(5, 51);
+        assert_eq!(dict.get_index(3), Some(51));
+    }
+
+    #[test]
+    fn positional_updates() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2)]);
+        dict.track_changes(4);
+        let mut replica = dict.clone();
+        let version = dict.version();
+        assert_eq!(dict.set_index(1, 20), Some(2));
+        assert_eq!(dict.get("b"), Some(20));
+        for change in dict.changes_since(version).unwrap() {
+            replica.apply_change(change);
+        }
+        assert_eq!(replica, dict);
+        if let Some(v) = dict.get_index_mut(0) {
+            *v += 10;
+        }
+        assert_eq!(dict.values(), &vec![11, 20]);
+        assert!(dict.version() > version + 1);
+        assert_eq!(dict.get_index_mut(2), None);
+    }
+
+    #[test]
+    fn subset_superset() {
+        let small = Dictionary::from([(2, 0), (1, 0)]);
+        let big = Dictionary::from([(1, 1), (2, 2), (3, 3)]);
+        assert!(small.is_subset_of(&big));
+        assert!(big.is_superset_of(&small));
+        assert!(!big.is_subset_of(&small));
+        assert!(small.is_subset_of(&small));
+        assert!(Dictionary::<i32, i32>::new().is_subset_of(&small));
+        // same keys but different values
+        assert!(!small.is_submap_of(&big));
+        assert!(Dictionary::from([(2, 2), (3, 3)]).is_submap_of(&big));
+    }
+
+    #[test]
+    fn set_operations_keep_left_order() {
+        let left = Dictionary::from([(3, "c"), (1, "a"), (2, "b")]);
+        let right = Dictionary::from([(4, "D"), (2, "B"), (3, "C")]);
+        let union = left.union(&right, |_, l, r| if l < r { l } else { r });
+        assert_eq!(union.keys(), &vec![3, 1, 2, 4]);
+        assert_eq!(union.values(), &vec!["C", "a", "B", "D"]);
+        let both = left.intersection(&right, |k, l, _| if *k == 3 { "both" } else { l });
+        assert_eq!(both.keys(), &vec![3, 2]);
+        assert_eq!(both.values(), &vec!["both", "b"]);
+        assert_eq!(left.difference(&right), Dictionary::from([(1, "a")]));
+        assert_eq!(
+            left.symmetric_difference(&right),
+            Dictionary::from([(1, "a"), (4, "D")])
+        );
+        let empty = Dictionary::new();
+        assert_eq!(left.union(&empty, |_, l, _| l), left);
+        assert!(left.intersection(&empty, |_, l, _| l).is_empty());
+        assert_eq!(empty.symmetric_difference(&left), left);
+    }
+
+    #[test]
+    fn update_from_policies() {
+        let base = Dictionary::from([(1, 10), (2, 20)]);
+
+        let mut keep = base.clone();
+        keep.update_from(vec![(2, 99), (3, 30)], MergePolicy::KeepExisting);
+        assert_eq!(keep.values(), &vec![10, 20, 30]);
+
+        let mut overwrite = base.clone();
+        overwrite.update_from(vec![(2, 99), (3, 30)], MergePolicy::Overwrite);
+        assert_eq!(overwrite.keys(), &vec![1, 2, 3]);
+        assert_eq!(overwrite.values(), &vec![10, 99, 30]);
+
+        let mut combine = base.clone();
+        combine.update_from(
+            vec![(1, 1), (1, 1)],
+            MergePolicy::Combine(|v, new| *v += new),
+        );
+        assert_eq!(combine.get(1), Some(12));
+    }
+
+    #[test]
+    fn bitor_merge() {
+        let mut left = Dictionary::from([(1, 10), (2, 20)]);
+        let right = Dictionary::from([(3, 30), (1, 11)]);
+        let merged = left.clone() | right.clone();
+        assert_eq!(merged.keys(), &vec![1, 2, 3]);
+        assert_eq!(merged.values(), &vec![11, 20, 30]);
+        left |= right;
+        assert_eq!(left, merged);
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20)]);
+        *dict.get_mut(2).unwrap() += 1;
+        assert_eq!(dict.get(2), Some(21));
+        assert_eq!(dict.get_mut(3), None);
+    }
+
+    #[test]
+    fn from_keys() {
+        let dict = Dictionary::from_keys(1..=3, 0);
+        assert_eq!(dict.keys(), &vec![1, 2, 3]);
+        assert_eq!(dict.values(), &vec![0, 0, 0]);
+        assert_eq!(dict.capacity(), 3);
+
+        let squares = Dictionary::from_keys_with(vec![3, 1, 2], |k| k * k);
+        assert_eq!(squares.values(), &vec![9, 1, 4]);
+        assert_eq!(squares.get(3), Some(9));
+    }
+
+    #[test]
+    fn key_indices() {
+        let mut dict = Dictionary::from([(5, 'a'), (3, 'b'), (9, 'c')]);
+        dict.remove(3);
+        let indices: Vec<(&i32, usize)> = dict.key_indices().collect();
+        assert_eq!(indices, vec![(&5, 0), (&9, 1)]);
+        let map = dict.as_index_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&9], 1);
+    }
+
+    #[test]
+    fn clear_truncate_shrink() {
+        let mut dict: Dictionary<i32, i32> = (0..10).map(|i| (i, i)).collect();
+        let capacity = dict.capacity();
+        dict.truncate(4);
+        assert_eq!(dict.len(), 4);
+        assert_eq!(dict.get(5), None);
+        assert_eq!(dict.get(3), Some(3));
+        dict.truncate(10);
+        assert_eq!(dict.len(), 4);
+        assert_eq!(dict.capacity(), capacity);
+
+        dict.shrink_to_fit();
+        assert_eq!(dict.capacity(), 4);
+        dict.push_back(10, 10);
+        assert_eq!(dict.get_index(4), Some(10));
+
+        dict.clear();
+        assert!(dict.is_empty());
+        assert_eq!(dict.get(0), None);
+        assert_eq!(dict.capacity(), 8);
+    }
+
+    #[test]
+    fn contains_key_index_of() {
+        let mut dict = Dictionary::from([(7, 'a'), (8, 'b'), (9, 'c')]);
+        assert!(dict.contains_key(&8));
+        assert!(!dict.contains_key(&1));
+        assert_eq!(dict.index_of(&9), Some(2));
+        dict.remove(7);
+        assert_eq!(dict.index_of(&9), Some(1));
+        assert_eq!(dict.index_of(&7), None);
+    }
+
+    #[test]
+    fn sort_by_comparator() {
+        let mut dict = Dictionary::from([(1, 3), (2, 1), (3, 3), (4, 2)]);
+        dict.sort_by(|(_, v1), (_, v2)| v2.cmp(v1));
+        // stable, 1 stays before 3
+        assert_eq!(dict.keys(), &vec![1, 3, 4, 2]);
+        assert_eq!(dict.get(4), Some(2));
+        assert_eq!(dict.index_of(&2), Some(3));
+
+        dict.sort_by_key(|k, v| (*v, -k));
+        assert_eq!(dict.keys(), &vec![2, 4, 3, 1]);
+    }
+
+    #[test]
+    fn cached_and_unstable_sorts() {
+        let mut calls = 0;
+        let mut dict: Dictionary<u32, u32> = (0..50).map(|i| (i, (i * 37) % 11)).collect();
+        dict.sort_by_cached_key(|_, v| {
+            calls += 1;
+            *v
+        });
+        assert_eq!(calls, 50);
+        // stable: ties stay in key order
+        for pair in dict.iter().collect::<Vec<_>>().windows(2) {
+            assert!(pair[0].1 < pair[1].1 || (pair[0].1 == pair[1].1 && pair[0].0 < pair[1].0));
+        }
+        let mut unstable = dict.clone();
+        unstable.sort_unstable_by_values();
+        assert!(unstable.values().windows(2).all(|w| w[0] <= w[1]));
+        assert!(unstable.eq_ignore_order(&dict));
+        unstable.sort_unstable_by_keys();
+        assert_eq!(unstable.keys(), &(0..50).collect::<Vec<_>>());
+        assert!(unstable.is_sorted());
+        for (i, key) in unstable.keys().iter().enumerate() {
+            assert_eq!(unstable.index_of(key), Some(i));
+        }
+    }
+
+    #[test]
+    fn sort_by_values_is_stable() {
+        let mut dict =
+            Dictionary::from([('a', 3), ('b', 1), ('c', 2), ('d', 1), ('e', 3), ('f', 0)]);
+        dict.sort_by_values();
+        assert_eq!(dict.keys(), &vec!['f', 'b', 'd', 'c', 'a', 'e']);
+        assert_eq!(dict.values(), &vec![0, 1, 1, 2, 3, 3]);
+        assert_eq!(dict.index_of(&'a'), Some(4));
+        assert_eq!(dict.get('d'), Some(1));
+    }
+
+    #[test]
+    fn modify_each_skips_removed_keys() {
+        let mut dict = Dictionary::from([(1, 1), (2, 2), (3, 3), (4, 4)]);
+        let mut visited = Vec::new();
+        let keys = dict.keys_snapshot();
+        dict.modify_each(keys, |dict, key| {
+            visited.push(key);
+            // removing a later key means it is never visited
+            if key == 1 {
+                dict.remove(3);
+            }
+            dict.push_back(key + 10, key);
+        });
+        assert_eq!(visited, vec![1, 2, 4]);
+        assert_eq!(dict.keys(), &vec![1, 2, 4, 11, 12, 14]);
+    }
+
+    #[test]
+    fn reverse_in_place() {
+        let mut dict = Dictionary::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+        let version = dict.version();
+        dict.reverse();
+        assert_eq!(dict.keys(), &vec![3, 2, 1]);
+        assert_eq!(dict.values(), &vec!['c', 'b', 'a']);
+        assert_eq!(dict.index_of(&3), Some(0));
+        assert_ne!(dict.version(), version);
+        let back: Vec<(&i32, &char)> = dict.iter_rev().collect();
+        assert_eq!(back, vec![(&1, &'a'), (&2, &'b'), (&3, &'c')]);
+    }
+
+    #[test]
+    fn get_full_and_index_entry() {
+        let dict = Dictionary::from([(10, 'a'), (20, 'b'), (30, 'c')]);
+        assert_eq!(dict.get_full(&30), Some((2, &30, &'c')));
+        assert_eq!(dict.get_full(&40), None);
+        assert_eq!(dict.get_index_entry(1), Some((&20, &'b')));
+        assert_eq!(dict.get_index_entry(3), None);
+    }
+
+    #[test]
+    fn move_entries() {
+        let mut dict: Dictionary<i32, i32> = (0..5).map(|i| (i, i * 10)).collect();
+        dict.move_index(4, 1);
+        assert_eq!(dict.keys(), &vec![0, 4, 1, 2, 3]);
+        assert_eq!(dict.values(), &vec![0, 40, 10, 20, 30]);
+        dict.move_index(2, 2);
+        assert!(dict.move_to_back(0));
+        assert!(!dict.move_to_back(9));
+        assert_eq!(dict.keys(), &vec![4, 1, 2, 3, 0]);
+        dict.swap_indices(0, 4);
+        assert_eq!(dict.keys(), &vec![0, 1, 2, 3, 4]);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_index_out_of_bounds() {
+        let mut dict = Dictionary::from([(1, 1)]);
+        dict.move_index(0, 1);
+    }
+
+    #[test]
+    fn hot_keys_survive_mutation() {
+        let mut dict: Dictionary<i32, i32> = (0..4).map(|i| (i, i)).collect();
+        assert!(dict.mark_hot(3));
+        assert!(!dict.mark_hot(9));
+        *dict.get_mut(3).unwrap() = 30;
+        assert_eq!(dict.get(3), Some(30));
+        // moving the key leaves a stale pin, get falls back to the map
+        dict.remove(0);
+        assert!(dict.hot_keys().is_empty());
+        assert_eq!(dict.get(3), Some(30));
+        dict.remove(3);
+        assert_eq!(dict.get(3), None);
+        dict.push_back(3, 3);
+        assert_eq!(dict.get(3), Some(3));
+
+        for k in 0..(HOT_CAPACITY as i32 + 2) {
+            dict.push_back(k, k);
+            dict.mark_hot(k);
+        }
+        assert_eq!(dict.hot_keys().len(), HOT_CAPACITY);
+        assert_eq!(dict.hot_keys()[0], &2);
+        dict.clear_hot();
+        assert!(dict.hot_keys().is_empty());
+    }
+
+    #[test]
+    fn insert_relative_to_anchor() {
+        let mut dict = Dictionary::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+        assert_eq!(dict.insert_before(&1, 0, 'z'), Some(0));
+        assert_eq!(dict.keys(), &vec![0, 1, 2, 3]);
+        // existing keys move next to the anchor
+        assert_eq!(dict.insert_after(&0, 3, 'C'), Some(1));
+        assert_eq!(dict.keys(), &vec![0, 3, 1, 2]);
+        assert_eq!(dict.insert_before(&3, 2, 'B'), Some(1));
+        assert_eq!(dict.keys(), &vec![0, 2, 3, 1]);
+        assert_eq!(dict.values(), &vec!['z', 'B', 'C', 'a']);
+        assert_eq!(dict.insert_after(&1, 1, 'A'), Some(3));
+        assert_eq!(dict.get(1), Some('A'));
+        assert_eq!(dict.insert_before(&9, 9, 'x'), None);
+        assert_eq!(dict.len(), 4);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn iterators_are_double_ended_and_exact() {
+        let mut dict = Dictionary::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+        let mut iter = dict.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some((&3, &'c')));
+        assert_eq!(iter.next(), Some((&1, &'a')));
+        assert_eq!(iter.size_hint(), (1, Some(1)));
+        assert_eq!(iter.next(), Some((&2, &'b')));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        for (_, v) in dict.iter_mut().rev().take(1) {
+            *v = 'z';
+        }
+        assert_eq!(dict.get(3), Some('z'));
+        let owned: Vec<(i32, char)> = dict.clone().into_iter().rev().collect();
+        assert_eq!(owned, vec![(3, 'z'), (2, 'b'), (1, 'a')]);
+        assert_eq!(dict.into_iter().len(), 3);
+    }
+
+    // fnv-1a, a fixed seed hasher for deterministic tests
+    #[derive(Default, Clone)]
+    struct Fnv(u64);
+
+    impl std::hash::Hasher for Fnv {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+        fn write(&mut self, bytes: &[u8]) {
+            for b in bytes {
+                self.0 = (self.0 ^ *b as u64).wrapping_mul(0x100000001b3);
+            }
+        }
+    }
+
+    #[test]
+    fn get_copied() {
+        let mut dict = Dictionary::from([(1, 10u8), (2, 20)]);
+        let value = dict.get_copied(&2);
+        // no borrow is held, the dictionary can be changed right away
+        dict.push_back(3, 30);
+        assert_eq!(value, Some(20));
+        assert_eq!(dict.get_copied(&4), None);
+        assert_eq!(dict.values_copied().collect::<Vec<u8>>(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn sealed_values_stay_writable() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2)]);
+        dict.seal();
+        assert!(dict.is_sealed());
+        *dict.get_mut("a").unwrap() += 10;
+        dict.iter_mut().for_each(|(_, v)| *v *= 2);
+        assert_eq!(dict.push_back("b", 0), Some(4));
+        assert_eq!(dict.values(), &vec![22, 0]);
+        assert_eq!(dict.unsealed().map(|d| d.len()), Err(Sealed));
+        assert!(dict.clone().is_sealed());
+    }
+
+    #[test]
+    fn sealed_mutators_return_errors() {
+        let mut dict: Dictionary<i32, i32> = (0..4).map(|i| (i, i)).collect();
+        dict.seal();
+        assert_eq!(dict.insert(9, 9, 0), Err(DictError::Sealed));
+        assert_eq!(dict.shift_insert(1, 10, 0), Err(DictError::Sealed));
+        assert_eq!(
+            dict.rename_keys(&Dictionary::from([(0, 5)])),
+            Err(RenameError::Sealed)
+        );
+        assert_eq!(dict.unsealed().map(|d| d.pop_back()), Err(Sealed));
+        assert_eq!(dict.unsealed().map(|d| d.sort_by_values()), Err(Sealed));
+        assert_eq!(dict.len(), 4);
+
+        let mut open: Dictionary<i32, i32> = dict.into_iter().collect();
+        assert_eq!(open.unsealed().map(|d| d.move_index(0, 3)), Ok(()));
+        assert_eq!(open.unsealed().map(|d| d.remove(0)), Ok(Some(0)));
+        assert_eq!(open.keys(), &vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "dictionary is sealed")]
+    fn sealed_rejects_new_keys() {
+        let mut dict = Dictionary::from([("a", 1)]);
+        dict.seal();
+        dict.push_back("c", 3);
+    }
+
+    #[test]
+    fn append_to_sealed_leaves_other_intact() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut sealed = Dictionary::from([(1, 1)]);
+        sealed.seal();
+        let mut other = Dictionary::from([(1, 10), (2, 20)]);
+        let result = catch_unwind(AssertUnwindSafe(|| sealed.append(&mut other)));
+        assert!(result.is_err());
+        assert_eq!(sealed, Dictionary::from([(1, 1)]));
+        assert_eq!(other, Dictionary::from([(1, 10), (2, 20)]));
+        assert!(other.index_is_consistent());
+    }
+
+    #[test]
+    fn chunked_parts() {
+        let dict: Dictionary<i32, char> = (0..7).map(|i| (i, 'x')).collect();
+        let parts = dict.clone().into_chunked_parts(3);
+        let sizes: Vec<usize> = parts.iter().map(|p| p.len()).collect();
+        assert_eq!(sizes, vec![3, 2, 2]);
+        assert!(parts.iter().all(|p| p.capacity() == p.len()));
+        assert_eq!(parts[1].keys(), &vec![3, 4]);
+        assert_eq!(Dictionary::from_chunked_parts(parts), dict);
+
+        let few = Dictionary::from([(1, 'a')]).into_chunked_parts(4);
+        assert_eq!(few.len(), 1);
+        let none: Vec<Dictionary<i32, char>> = Vec::new();
+        assert!(Dictionary::from_chunked_parts(none).is_empty());
+    }
+
+    #[test]
+    fn disjoint_mut() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+        let [c, a, z] = dict.get_disjoint_mut([&"c", &"a", &"z"]);
+        std::mem::swap(c.unwrap(), a.unwrap());
+        assert!(z.is_none());
+        assert_eq!(dict.values(), &vec![3, 2, 1]);
+        let [last, first, past] = dict.get_disjoint_indices_mut([2, 0, 3]);
+        *last.unwrap() += 10;
+        *first.unwrap() += 20;
+        assert!(past.is_none());
+        assert_eq!(dict.values(), &vec![23, 2, 11]);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key")]
+    fn disjoint_mut_rejects_duplicates() {
+        let mut dict = Dictionary::from([("a", 1), ("b", 2)]);
+        dict.get_disjoint_mut([&"b", &"a", &"b"]);
+    }
+
+    #[test]
+    fn split_off_reindexes_both() {
+        let mut dict: Dictionary<i32, i32> = (0..10).map(|i| (i, i * i)).collect();
+        let rest = dict.split_off(4);
+        assert_eq!(dict.len(), 4);
+        assert_eq!(rest.len(), 6);
+        assert_eq!(dict.get(9), None);
+        assert_eq!(rest.get(9), Some(81));
+        assert_eq!(rest.index_of(&4), Some(0));
+        assert!(rest.key_indices().all(|(k, i)| rest.keys()[i] == *k));
+        dict.push_back(4, 0);
+        assert_eq!(dict.index_of(&4), Some(4));
+
+        let all = dict.split_off(0);
+        assert!(dict.is_empty());
+        assert_eq!(all.keys(), &vec![0, 1, 2, 3, 4]);
+        assert!(Dictionary::from([(1, 1)]).split_off(1).is_empty());
+    }
+
+    #[test]
+    fn append_reserves_once() {
+        let mut dict: Dictionary<u32, u32> = Dictionary::with_capacity(2);
+        dict.push_back(0, 0);
+        let mut other: Dictionary<u32, u32> = (0..100).map(|i| (i, i + 1)).collect();
+        let other_capacity = other.capacity();
+        dict.append(&mut other);
+        assert_eq!(dict.len(), 100);
+        assert_eq!(dict.capacity(), 101);
+        assert_eq!(dict.get(0), Some(1));
+        assert_eq!(dict.index_of(&99), Some(99));
+        assert!(other.is_empty());
+        assert_eq!(other.capacity(), other_capacity);
+        other.push_back(7, 7);
+        assert_eq!(other.index_of(&7), Some(0));
+    }
+
+    #[test]
+    fn equality_and_hash() {
+        use std::collections::HashSet;
+        let a = Dictionary::from([(1, 'a'), (2, 'b')]);
+        let mut b = Dictionary::with_capacity(64);
+        b.push_back(1, 'a');
+        b.push_back(3, 'c');
+        b.remove(3);
+        b.push_back(2, 'b');
+        // capacity and the mutation history do not matter
+        assert_eq!(a, b);
+        let set: HashSet<Dictionary<i32, char>> = [a.clone(), b].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        let reordered = Dictionary::from([(2, 'b'), (1, 'a')]);
+        assert!(!set.contains(&reordered));
+        assert!(a.eq_ignore_order(&reordered));
+        assert!(!a.eq_ignore_order(&Dictionary::from([(1, 'a'), (2, 'x')])));
+        assert!(!a.eq_ignore_order(&Dictionary::from([(1, 'a')])));
+    }
+
+    #[test]
+    fn custom_hasher() {
+        type FnvState = std::hash::BuildHasherDefault<Fnv>;
+        let mut dict: Dictionary<&str, i32, FnvState> =
+            Dictionary::with_capacity_and_hasher(4, FnvState::default());
+        dict.push_back("a", 1);
+        dict.push_back("b", 2);
+        dict.sort_by_values();
+        assert_eq!(dict.get("b"), Some(2));
+        assert_eq!(dict.remove("a"), Some(1));
+        let other: Dictionary<&str, i32, FnvState> = [("b", 2), ("c", 3)].into_iter().collect();
+        let merged = dict.clone() | other;
+        assert_eq!(merged.keys(), &vec!["b", "c"]);
+        assert!(dict.is_subset_of(&merged));
+    }
 }
@@ -0,0 +1,177 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+
+/// The changes that turn one Dictionary into another, created with `Dictionary::diff`
+/// or `Dictionary::diff_ordered` and applied with `Dictionary::apply`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictDiff<K, V> {
+    /// keys that are only in the old dictionary, in its order
+    pub removed: Vec<K>,
+    /// keys in both whose value changed, with the new value, in the new order
+    pub changed: Vec<(K, V)>,
+    /// keys that are only in the new dictionary, in its order
+    pub added: Vec<(K, V)>,
+    /// (key, position) moves that give the new order once the other changes are
+    /// applied, always empty from diff
+    pub moves: Vec<(K, usize)>,
+}
+
+impl<K, V> DictDiff<K, V> {
+    /// true if applying the diff changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.added.is_empty()
+            && self.moves.is_empty()
+    }
+}
+
+impl<K: Eq + Clone, V: Clone + PartialEq, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// the keys removed, changed and added going from self to other, ignoring order
+    /// applying it to a copy of self gives the entries of other, with the added keys
+    /// at the back, equal to other by `eq_ignore_order`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let old = Dictionary::from([("host", "a"), ("port", "80"), ("tls", "off")]);
+    /// let new = Dictionary::from([("port", "443"), ("host", "a"), ("debug", "on")]);
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.removed, vec!["tls"]);
+    /// assert_eq!(diff.changed, vec![("port", "443")]);
+    /// assert_eq!(diff.added, vec![("debug", "on")]);
+    ///
+    /// let mut synced = old.clone();
+    /// synced.apply(diff);
+    /// assert!(synced.eq_ignore_order(&new));
+    /// assert_ne!(synced, new);
+    /// ```
+    pub fn diff<T: BuildIndex<K>>(&self, other: &Dictionary<K, V, T>) -> DictDiff<K, V> {
+        let removed = self
+            .iter()
+            .filter(|(key, _)| other.stored_index_of(key).is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut changed = Vec::new();
+        let mut added = Vec::new();
+        for (key, value) in other.iter() {
+            match self.stored_index_of(key).map(|i| &self.values()[i]) {
+                Some(old) if old == value => {}
+                Some(_) => changed.push((key.clone(), value.clone())),
+                None => added.push((key.clone(), value.clone())),
+            }
+        }
+        DictDiff {
+            removed,
+            changed,
+            added,
+            moves: Vec::new(),
+        }
+    }
+
+    /// diff with the moves that also give the order of other, applying it to a copy
+    /// of self gives a dictionary equal to other.
+    /// Each move takes the first key out of place to its position, which is O(n^2)
+    /// in the worst case, and a key moved towards the back shows up as the keys it
+    /// passed each moving forward
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let old = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let new = Dictionary::from([("c", 3), ("a", 1), ("d", 4), ("b", 2)]);
+    /// let diff = old.diff_ordered(&new);
+    /// assert_eq!(diff.moves, vec![("c", 0), ("d", 2)]);
+    ///
+    /// let mut synced = old.clone();
+    /// synced.apply(diff);
+    /// assert_eq!(synced, new);
+    /// ```
+    pub fn diff_ordered<T: BuildIndex<K>>(&self, other: &Dictionary<K, V, T>) -> DictDiff<K, V> {
+        let mut diff = self.diff(other);
+        // the order apply leaves before the moves, the kept keys then the added ones
+        let mut current: Vec<&K> = self
+            .keys()
+            .iter()
+            .filter(|key| other.stored_index_of(key).is_some())
+            .chain(diff.added.iter().map(|(key, _)| key))
+            .collect();
+        for (i, key) in other.keys().iter().enumerate() {
+            if current[i] == key {
+                continue;
+            }
+            let from = i + current[i..].iter().position(|k| *k == key).unwrap();
+            current[i..=from].rotate_right(1);
+            diff.moves.push((key.clone(), i));
+        }
+        diff
+    }
+
+    /// apply a diff, removing, updating and pushing back its keys and then making its moves
+    /// removed keys that are not in the dictionary are skipped, as are moves of
+    /// missing keys, and a move past the end goes to the last position
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn apply(&mut self, diff: DictDiff<K, V>) {
+        if !diff.removed.is_empty() {
+            self.remove_many(diff.removed);
+        }
+        for (key, value) in diff.changed.into_iter().chain(diff.added) {
+            self.push_back(key, value);
+        }
+        for (key, to) in diff.moves {
+            if let Some(from) = self.index_of(&key) {
+                self.move_index(from, to.min(self.len() - 1));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unordered_diff() {
+        let old = Dictionary::from([(1, "a"), (2, "b"), (3, "c")]);
+        assert!(old.diff(&old).is_empty());
+        assert!(old.diff_ordered(&old.clone()).is_empty());
+        let new = Dictionary::from([(3, "c"), (4, "d"), (2, "x")]);
+        let diff = old.diff(&new);
+        assert_eq!(diff.removed, vec![1]);
+        assert_eq!(diff.changed, vec![(2, "x")]);
+        assert_eq!(diff.added, vec![(4, "d")]);
+        assert!(diff.moves.is_empty());
+        let mut synced = old.clone();
+        synced.apply(diff.clone());
+        assert_eq!(synced.keys(), &vec![2, 3, 4]);
+        assert!(synced.eq_ignore_order(&new));
+        // applying twice changes nothing more
+        synced.apply(diff);
+        assert!(synced.eq_ignore_order(&new));
+    }
+
+    #[test]
+    fn ordered_diff_round_trips() {
+        let old: Dictionary<u32, u32> = (0..8).map(|i| (i, i)).collect();
+        let targets: Vec<Dictionary<u32, u32>> = vec![
+            (0..8).rev().map(|i| (i, i)).collect(),
+            [7, 0, 1, 2, 3, 4, 5, 6]
+                .into_iter()
+                .map(|i| (i, i))
+                .collect(),
+            [9, 2, 4, 6, 8].into_iter().map(|i| (i, i * 2)).collect(),
+            Dictionary::new(),
+        ];
+        for new in targets {
+            let mut synced = old.clone();
+            synced.apply(old.diff_ordered(&new));
+            assert_eq!(synced, new);
+        }
+        let rotated: Dictionary<u32, u32> = [7, 0, 1, 2, 3, 4, 5, 6]
+            .into_iter()
+            .map(|i| (i, i))
+            .collect();
+        assert_eq!(old.diff_ordered(&rotated).moves, vec![(7, 0)]);
+    }
+}
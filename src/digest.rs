@@ -0,0 +1,228 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::hash::{Hash, Hasher};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64 bit FNV-1a Hasher with no random seed
+/// the same bytes hash to the same value in every process, unlike RandomState,
+/// which is what lets two processes compare digests
+#[derive(Debug, Clone, Copy)]
+pub struct DigestHasher {
+    state: u64,
+}
+
+impl Default for DigestHasher {
+    fn default() -> Self {
+        DigestHasher { state: FNV_OFFSET }
+    }
+}
+
+// Hasher methods that write an integer as its little endian bytes
+macro_rules! write_le {
+    ($($method:ident($int:ty)),* $(,)?) => {
+        $(
+            fn $method(&mut self, n: $int) {
+                self.write(&n.to_le_bytes());
+            }
+        )*
+    };
+}
+
+impl Hasher for DigestHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    // integers are hashed as little endian so the digest does not depend on the platform
+    write_le! {
+        write_u8(u8),
+        write_u16(u16),
+        write_u32(u32),
+        write_u64(u64),
+        write_u128(u128),
+        write_i8(i8),
+        write_i16(i16),
+        write_i32(i32),
+        write_i64(i64),
+        write_i128(i128),
+    }
+
+    // and usize and isize as 64 bits so they match between 32 and 64 bit targets
+    fn write_usize(&mut self, n: usize) {
+        self.write(&(n as u64).to_le_bytes());
+    }
+
+    fn write_isize(&mut self, n: isize) {
+        self.write(&(n as i64).to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// A digest kept up to date as entries are pushed to the back
+/// enabled with `Dictionary::track_digest`
+#[derive(Debug, Clone)]
+pub(crate) struct TrackedDigest<K, V> {
+    entry: fn(&K, &V) -> u64,
+    state: u64,
+    // the dictionary version the state matches
+    version: u64,
+}
+
+impl<K, V> TrackedDigest<K, V> {
+    /// fold the entry pushed by the mutation that moved the dictionary from
+    /// version - 1 to version, a stale digest stays stale
+    pub(crate) fn push(&mut self, key: &K, value: &V, version: u64) {
+        if self.version.wrapping_add(1) == version {
+            self.state = fold(self.state, (self.entry)(key, value));
+            self.version = version;
+        }
+    }
+}
+
+fn entry_digest<K: Hash, V: Hash>(key: &K, value: &V) -> u64 {
+    let mut hasher = DigestHasher::default();
+    key.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// order sensitive, folding a then b differs from b then a
+#[inline]
+fn fold(state: u64, entry: u64) -> u64 {
+    (state.rotate_left(5) ^ entry).wrapping_mul(FNV_PRIME)
+}
+
+impl<K: Hash + Eq + Clone, V: Hash + Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// an order sensitive hash of every entry
+    /// equal dictionaries have equal digests in any process and on any platform,
+    /// so two sides can compare digests before exchanging the entries.
+    /// This walks the whole dictionary, unless `track_digest` is on and only
+    /// push_back has been used since the digest was last computed.
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let a = Dictionary::from([("x", 1), ("y", 2)]);
+    /// let b = Dictionary::from([("y", 2), ("x", 1)]);
+    /// assert_eq!(a.digest(), a.clone().digest());
+    /// assert_ne!(a.digest(), b.digest());
+    /// ```
+    pub fn digest(&self) -> u64 {
+        if let Some(tracked) = self.tracked_digest() {
+            if tracked.version == self.version() {
+                return tracked.state;
+            }
+        }
+        self.compute_digest()
+    }
+
+    /// keep the digest up to date on push_back so reading it is O(1)
+    /// any other mutation leaves it stale until the next `refresh_digest`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut log = Dictionary::new();
+    /// log.track_digest();
+    /// for i in 0..100 {
+    ///     log.push_back(i, i * i);
+    /// }
+    /// let mut copy = Dictionary::new();
+    /// copy.extend(log.iter().map(|(k, v)| (*k, *v)));
+    /// assert_eq!(log.digest(), copy.digest());
+    /// ```
+    pub fn track_digest(&mut self) {
+        let tracked = TrackedDigest {
+            entry: entry_digest::<K, V>,
+            state: self.compute_digest(),
+            version: self.version(),
+        };
+        self.set_tracked_digest(Some(tracked));
+    }
+
+    /// recompute a tracked digest after mutations other than push_back
+    pub fn refresh_digest(&mut self) -> u64 {
+        let digest = self.compute_digest();
+        let version = self.version();
+        if let Some(tracked) = self.tracked_digest_mut() {
+            tracked.state = digest;
+            tracked.version = version;
+        }
+        digest
+    }
+
+    /// stop keeping the digest up to date
+    pub fn untrack_digest(&mut self) {
+        self.set_tracked_digest(None);
+    }
+
+    fn compute_digest(&self) -> u64 {
+        self.iter().fold(FNV_OFFSET, |state, (key, value)| {
+            fold(state, entry_digest(key, value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracked_matches_computed() {
+        let mut dict: Dictionary<String, u32> = Dictionary::new();
+        dict.push_back("a".into(), 1);
+        dict.track_digest();
+        dict.push_back("b".into(), 2);
+        dict.push_back("c".into(), 3);
+        // read from the tracked state without walking the entries
+        assert_eq!(dict.tracked_digest().unwrap().version, dict.version());
+        assert_eq!(dict.digest(), dict.compute_digest());
+
+        // a replaced value makes the tracked digest stale, digest still computes it
+        dict.push_back("a".into(), 9);
+        assert_eq!(dict.digest(), dict.compute_digest());
+        dict.push_back("d".into(), 4);
+        assert_eq!(dict.digest(), dict.compute_digest());
+        assert_eq!(dict.refresh_digest(), dict.compute_digest());
+        dict.push_back("e".into(), 5);
+        assert_eq!(dict.digest(), dict.compute_digest());
+    }
+
+    #[test]
+    fn digest_is_stable() {
+        // fixed inputs give a fixed digest across runs and platforms
+        let dict = Dictionary::from([(1u32, 2u64)]);
+        let empty: Dictionary<u32, u64> = Dictionary::new();
+        assert_eq!(empty.digest(), FNV_OFFSET);
+        assert_eq!(dict.digest(), 11215749495063296557);
+        assert_eq!(dict.digest(), Dictionary::from([(1u32, 2u64)]).digest());
+        assert_ne!(dict.digest(), Dictionary::from([(2u32, 1u64)]).digest());
+    }
+
+    #[test]
+    fn integers_hash_as_little_endian() {
+        let hash = |write: &dyn Fn(&mut DigestHasher)| {
+            let mut hasher = DigestHasher::default();
+            write(&mut hasher);
+            hasher.finish()
+        };
+        let bytes = hash(&|h| h.write(&[1, 0, 0, 0]));
+        assert_eq!(hash(&|h| h.write_u32(1)), bytes);
+        assert_eq!(hash(&|h| h.write_i32(1)), bytes);
+        assert_eq!(hash(&|h| h.write_u16(0x0201)), hash(&|h| h.write(&[1, 2])));
+        assert_eq!(
+            hash(&|h| h.write_u128(1)),
+            hash(&|h| h.write(&1u128.to_le_bytes()))
+        );
+        assert_eq!(hash(&|h| h.write_usize(1)), hash(&|h| h.write_u64(1)));
+        assert_eq!(hash(&|h| h.write_isize(-1)), hash(&|h| h.write_i64(-1)));
+    }
+}
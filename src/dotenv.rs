@@ -0,0 +1,341 @@
+use crate::dict::Dictionary;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::process::Command;
+
+/// Errors raised while parsing or writing a .env file
+/// every variant carries the 1 based line number of the offending line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DotenvError {
+    /// the line is not a comment and has no `=`
+    MissingEquals { line: usize },
+    /// the line has nothing before the `=`
+    EmptyKey { line: usize },
+    /// a quoted value was never closed
+    UnterminatedQuote { line: usize },
+    /// there was something other than a comment after the closing quote
+    TrailingCharacters { line: usize },
+    /// the key can not be written, it is empty or has a `=`, `#` or whitespace in it
+    InvalidKey { line: usize },
+}
+
+impl Display for DotenvError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DotenvError::MissingEquals { line } => write!(f, "line {}: expected KEY=VALUE", line),
+            DotenvError::EmptyKey { line } => write!(f, "line {}: empty key", line),
+            DotenvError::UnterminatedQuote { line } => {
+                write!(f, "line {}: unterminated quoted value", line)
+            }
+            DotenvError::TrailingCharacters { line } => {
+                write!(f, "line {}: unexpected characters after quoted value", line)
+            }
+            DotenvError::InvalidKey { line } => {
+                write!(f, "line {}: key can not be written to a .env file", line)
+            }
+        }
+    }
+}
+
+impl Error for DotenvError {}
+
+impl Dictionary<String, String> {
+    /// Parse the contents of a .env file into a Dictionary
+    /// keys keep the order they appear in the file
+    /// blank lines and `#` comments are skipped and an `export ` prefix is ignored
+    /// values can be bare, 'single quoted' (taken literally) or "double quoted" (with escapes)
+    /// when a key is repeated the last value wins but the key keeps its first position
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let env = Dictionary::from_dotenv_str("# db\nexport HOST=localhost\nPORT=5432 # default\n").unwrap();
+    /// assert_eq!(env.keys(), &vec![String::from("HOST"), String::from("PORT")]);
+    /// assert_eq!(env.get(String::from("PORT")), Some(String::from("5432")));
+    /// ```
+    pub fn from_dotenv_str(input: &str) -> Result<Dictionary<String, String>, DotenvError> {
+        let mut env = Dictionary::new();
+
+        for (i, raw_line) in input.lines().enumerate() {
+            let line_no = i + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = match line.strip_prefix("export ") {
+                Some(rest) => rest.trim_start(),
+                None => line,
+            };
+            let (key, rest) = match line.split_once('=') {
+                Some(split) => split,
+                None => return Err(DotenvError::MissingEquals { line: line_no }),
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(DotenvError::EmptyKey { line: line_no });
+            }
+            let value = parse_value(rest.trim_start(), line_no)?;
+            env.push_back(key.to_string(), value);
+        }
+        Ok(env)
+    }
+
+    /// Write the dictionary out in .env format, one `KEY=VALUE` line per entry in order
+    /// values that would not survive being written bare are double quoted and escaped
+    /// so the output parses back to the same dictionary with from_dotenv_str.
+    /// Keys are always bare, so one that would not parse back fails with InvalidKey
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::dotenv::DotenvError;
+    ///
+    /// let env = Dictionary::from([(String::from("GREETING"), String::from("hi there "))]);
+    /// assert_eq!(env.to_dotenv_string().unwrap(), "GREETING=\"hi there \"\n");
+    /// let bad = Dictionary::from([(String::from("A=B"), String::new())]);
+    /// assert_eq!(bad.to_dotenv_string(), Err(DotenvError::InvalidKey { line: 1 }));
+    /// ```
+    pub fn to_dotenv_string(&self) -> Result<String, DotenvError> {
+        let mut output = String::new();
+        for (i, (key, value)) in self.iter().enumerate() {
+            if key.is_empty() || key.contains(['=', '#']) || key.contains(char::is_whitespace) {
+                return Err(DotenvError::InvalidKey { line: i + 1 });
+            }
+            output.push_str(key);
+            output.push('=');
+            if needs_quotes(value) {
+                output.push('"');
+                for c in value.chars() {
+                    match c {
+                        '"' => output.push_str("\\\""),
+                        '\\' => output.push_str("\\\\"),
+                        '\n' => output.push_str("\\n"),
+                        '\r' => output.push_str("\\r"),
+                        '\t' => output.push_str("\\t"),
+                        _ => output.push(c),
+                    }
+                }
+                output.push('"');
+            } else {
+                output.push_str(value);
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    /// set every entry as an environment variable of the command, in order
+    /// a value set on the command before is overridden, the rest of the inherited
+    /// environment is left alone, call `env_clear` first to pass only these variables
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use std::process::Command;
+    ///
+    /// let env = Dictionary::from_dotenv_str("RUST_LOG=info\nPORT=8080\n").unwrap();
+    /// let mut cmd = Command::new("server");
+    /// cmd.env("PORT", "80");
+    /// env.apply_as_env(&mut cmd);
+    /// let back = Dictionary::from_command_env(&cmd);
+    /// assert_eq!(back.get(String::from("PORT")), Some(String::from("8080")));
+    /// ```
+    pub fn apply_as_env(&self, cmd: &mut Command) {
+        cmd.envs(self.iter());
+    }
+
+    /// the environment variables explicitly set on the command, not the inherited ones
+    /// variables removed with `env_remove` are skipped, names and values that are
+    /// not UTF-8 are converted lossily. Command keeps the variables sorted by name,
+    /// so that is the order of the dictionary
+    pub fn from_command_env(cmd: &Command) -> Dictionary<String, String> {
+        let mut env = Dictionary::new();
+        for (key, value) in cmd.get_envs() {
+            if let Some(value) = value {
+                env.push_back(
+                    key.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                );
+            }
+        }
+        env
+    }
+}
+
+fn parse_value(rest: &str, line: usize) -> Result<String, DotenvError> {
+    if let Some(quoted) = rest.strip_prefix('\'') {
+        // single quotes are taken literally
+        let end = match quoted.find('\'') {
+            Some(end) => end,
+            None => return Err(DotenvError::UnterminatedQuote { line }),
+        };
+        check_trailing(&quoted[end + 1..], line)?;
+        return Ok(quoted[..end].to_string());
+    }
+
+    if let Some(quoted) = rest.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    check_trailing(&quoted[i + 1..], line)?;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 'r')) => value.push('\r'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, other)) => value.push(other),
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        return Err(DotenvError::UnterminatedQuote { line });
+    }
+
+    // bare values run until an inline comment
+    let value = match rest.find(" #") {
+        Some(end) => &rest[..end],
+        None => rest,
+    };
+    Ok(value.trim_end().to_string())
+}
+
+fn check_trailing(rest: &str, line: usize) -> Result<(), DotenvError> {
+    let rest = rest.trim_start();
+    if rest.is_empty() || rest.starts_with('#') {
+        Ok(())
+    } else {
+        Err(DotenvError::TrailingCharacters { line })
+    }
+}
+
+fn needs_quotes(value: &str) -> bool {
+    value.is_empty()
+        || value.starts_with('\'')
+        || value.starts_with('"')
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(" #")
+        || value.contains(['\n', '\r', '\t', '\\'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dotenv() {
+        let input = "\
+# comment line
+
+export NAME=app
+URL=\"http://host/?a=1\" # trailing comment
+LITERAL='no \\n escapes'
+ESCAPED=\"line1\\nline2\"
+BARE=some value # comment
+EMPTY=
+";
+        let env = Dictionary::from_dotenv_str(input).unwrap();
+        assert_eq!(
+            env.keys(),
+            &vec![
+                String::from("NAME"),
+                String::from("URL"),
+                String::from("LITERAL"),
+                String::from("ESCAPED"),
+                String::from("BARE"),
+                String::from("EMPTY"),
+            ]
+        );
+        assert_eq!(env.get("URL".into()), Some("http://host/?a=1".into()));
+        assert_eq!(env.get("LITERAL".into()), Some("no \\n escapes".into()));
+        assert_eq!(env.get("ESCAPED".into()), Some("line1\nline2".into()));
+        assert_eq!(env.get("BARE".into()), Some("some value".into()));
+        assert_eq!(env.get("EMPTY".into()), Some(String::new()));
+    }
+
+    #[test]
+    fn duplicate_keys_keep_first_position() {
+        let env = Dictionary::from_dotenv_str("A=1\nB=2\nA=3\n").unwrap();
+        assert_eq!(env.keys(), &vec![String::from("A"), String::from("B")]);
+        assert_eq!(env.get("A".into()), Some("3".into()));
+    }
+
+    #[test]
+    fn dotenv_errors() {
+        assert_eq!(
+            Dictionary::from_dotenv_str("A=1\nB\n"),
+            Err(DotenvError::MissingEquals { line: 2 })
+        );
+        assert_eq!(
+            Dictionary::from_dotenv_str("=1"),
+            Err(DotenvError::EmptyKey { line: 1 })
+        );
+        assert_eq!(
+            Dictionary::from_dotenv_str("A=\"open"),
+            Err(DotenvError::UnterminatedQuote { line: 1 })
+        );
+        assert_eq!(
+            Dictionary::from_dotenv_str("A='x' y"),
+            Err(DotenvError::TrailingCharacters { line: 1 })
+        );
+    }
+
+    #[test]
+    fn dotenv_round_trip() {
+        let mut env = Dictionary::<String, String>::new();
+        env.push_back("PLAIN".into(), "value".into());
+        env.push_back("SPACES".into(), " padded ".into());
+        env.push_back("QUOTE".into(), "say \"hi\" # not a comment".into());
+        env.push_back("MULTI".into(), "a\nb".into());
+        env.push_back("EMPTY".into(), String::new());
+        env.push_back("WEIRD\"KEY'".into(), "x".into());
+        let output = env.to_dotenv_string().unwrap();
+        assert!(output.starts_with("PLAIN=value\n"));
+        assert_eq!(Dictionary::from_dotenv_str(&output), Ok(env));
+    }
+
+    #[test]
+    fn unwritable_keys() {
+        for key in ["", "A=B", "#A", "A#B", "A B", "export A", "A\nB", " A"] {
+            let env = Dictionary::from([
+                (String::from("OK"), String::from("1")),
+                (key.to_string(), String::from("2")),
+            ]);
+            assert_eq!(
+                env.to_dotenv_string(),
+                Err(DotenvError::InvalidKey { line: 2 })
+            );
+        }
+    }
+
+    #[test]
+    fn apply_as_env_overrides() {
+        let env = Dictionary::from_dotenv_str("B=2\nA=1\n").unwrap();
+        let mut cmd = Command::new("true");
+        cmd.env("A", "0").env("C", "3").env_remove("D");
+        env.apply_as_env(&mut cmd);
+        let set = Dictionary::from_command_env(&cmd);
+        assert_eq!(
+            set.keys(),
+            &vec![String::from("A"), String::from("B"), String::from("C")]
+        );
+        assert_eq!(set.get(String::from("A")), Some(String::from("1")));
+    }
+
+    #[test]
+    fn command_env_of_plain_command_is_empty() {
+        let cmd = Command::new("true");
+        assert!(Dictionary::from_command_env(&cmd).is_empty());
+        let mut cleared = Command::new("true");
+        cleared.env_clear();
+        Dictionary::from([(String::from("ONLY"), String::from("this"))]).apply_as_env(&mut cleared);
+        assert_eq!(
+            Dictionary::from_command_env(&cleared)
+                .to_dotenv_string()
+                .unwrap(),
+            "ONLY=this\n"
+        );
+    }
+}
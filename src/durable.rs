@@ -0,0 +1,536 @@
+use crate::dict::Dictionary;
+use crate::digest::DigestHasher;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// every log starts with this, the last byte is the format version
+const MAGIC: &[u8; 8] = b"RDWAL\0\0\x01";
+
+// a record is its payload length, a checksum of the payload, then the payload
+const RECORD_HEADER: usize = 4 + 8;
+
+const OP_PUSH_BACK: u8 = 1;
+const OP_PUSH_FRONT: u8 = 2;
+const OP_REMOVE: u8 = 3;
+const OP_CLEAR: u8 = 4;
+
+/// Keys and values that can be written to a write-ahead log
+pub trait WalCodec: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    /// None if the bytes are not a valid encoding
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl WalCodec for Vec<u8> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self);
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+impl WalCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl WalCodec for i64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        Some(i64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+impl WalCodec for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+}
+
+/// When a DurableDictionary asks the OS to flush the log to disk
+/// a write that has not been synced survives the process crashing,
+/// but may be lost if the machine loses power
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// sync after every mutation, the slowest and the safest
+    Always,
+    /// sync after every n mutations, at most the last n - 1 can be lost
+    Every(u32),
+    /// leave it to the OS, or to calls to `sync`
+    Never,
+}
+
+/// An ordered dictionary that writes every mutation to a write-ahead log
+/// the log is appended to before the change is made in memory, so after a crash
+/// `recover` rebuilds the dictionary as of the last complete record.
+/// Every read method of Dictionary is available through Deref.
+/// The log only grows, `compact` rewrites it as one record per entry.
+/// If a record can not be written in full it is cut back off the log, and if even
+/// that fails the store refuses further writes until it is recovered
+/// # Example
+/// ```
+/// use rust_dict::durable::{DurableDictionary, SyncPolicy};
+///
+/// let path = std::env::temp_dir().join(format!("doc_wal_{}", std::process::id()));
+/// let mut store = DurableDictionary::create(&path, SyncPolicy::Always).unwrap();
+/// store.push_back(String::from("job"), 1u64).unwrap();
+/// store.push_back(String::from("retry"), 3u64).unwrap();
+/// store.remove(String::from("job")).unwrap();
+/// drop(store);
+///
+/// let store = DurableDictionary::<String, u64>::recover(&path, SyncPolicy::Always).unwrap();
+/// assert_eq!(store.keys(), &vec![String::from("retry")]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct DurableDictionary<K: Hash + Eq, V> {
+    dict: Dictionary<K, V>,
+    log: File,
+    path: PathBuf,
+    policy: SyncPolicy,
+    // mutations written since the last sync
+    unsynced: u32,
+    // the length of the log up to the last complete record
+    log_len: u64,
+    // a failed append could not be cut back off the log
+    poisoned: bool,
+}
+
+impl<K: Hash + Eq + Clone + WalCodec, V: Clone + WalCodec> DurableDictionary<K, V> {
+    /// start an empty dictionary with a new log, replacing any file at the path
+    pub fn create<P: AsRef<Path>>(path: P, policy: SyncPolicy) -> io::Result<Self> {
+        let mut log = File::create(path.as_ref())?;
+        log.write_all(MAGIC)?;
+        log.sync_all()?;
+        Ok(DurableDictionary {
+            dict: Dictionary::new(),
+            log,
+            path: path.as_ref().to_path_buf(),
+            policy,
+            unsynced: 0,
+            log_len: MAGIC.len() as u64,
+            poisoned: false,
+        })
+    }
+
+    /// rebuild the dictionary from the log at the path, creating it if there is none
+    /// replay stops at the first record that is cut short or fails its checksum,
+    /// that record and anything after it is cut off the log so new records follow
+    /// the last consistent state. A file that is not a log, or a log written with
+    /// other key or value types, fails with InvalidData.
+    /// The log is read through a buffer a record at a time, not loaded whole
+    pub fn recover<P: AsRef<Path>>(path: P, policy: SyncPolicy) -> io::Result<Self> {
+        DurableDictionary::open_log(path.as_ref(), policy)
+    }
+
+    fn open_log(path: &Path, policy: SyncPolicy) -> io::Result<Self> {
+        if !path.exists() {
+            return DurableDictionary::create(path, policy);
+        }
+        let mut log = OpenOptions::new().read(true).write(true).open(path)?;
+        let len = log.metadata()?.len();
+        // records are read one at a time, a large log is never held in memory whole
+        let mut input = BufReader::new(&log);
+        let mut magic = Vec::with_capacity(MAGIC.len());
+        (&mut input)
+            .take(MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+        if magic.len() < MAGIC.len() && MAGIC.starts_with(&magic) {
+            // the crash came while the log was being created
+            drop(input);
+            drop(log);
+            return DurableDictionary::create(path, policy);
+        }
+        if magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a rust_dict write-ahead log",
+            ));
+        }
+
+        let mut dict = Dictionary::new();
+        let mut pos = MAGIC.len() as u64;
+        let mut payload = Vec::new();
+        while let Some(size) = read_record(&mut input, len - pos, &mut payload)? {
+            // a complete record that does not decode was written with other types,
+            // cutting it off would lose data
+            if replay(&mut dict, &payload).is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "a log record does not decode as this key and value type",
+                ));
+            }
+            pos += size;
+        }
+        drop(input);
+        if pos < len {
+            log.set_len(pos)?;
+            log.sync_all()?;
+        }
+        log.seek(SeekFrom::Start(pos))?;
+        Ok(DurableDictionary {
+            dict,
+            log,
+            path: path.to_path_buf(),
+            policy,
+            unsynced: 0,
+            log_len: pos,
+            poisoned: false,
+        })
+    }
+
+    /// log then add a key value pair at the back, see `Dictionary::push_back`
+    pub fn push_back(&mut self, key: K, value: V) -> io::Result<Option<V>> {
+        self.append(OP_PUSH_BACK, Some(&key), Some(&value))?;
+        Ok(self.dict.push_back(key, value))
+    }
+
+    /// log then add a key value pair at the front, see `Dictionary::push_front`
+    pub fn push_front(&mut self, key: K, value: V) -> io::Result<Option<V>> {
+        self.append(OP_PUSH_FRONT, Some(&key), Some(&value))?;
+        Ok(self.dict.push_front(key, value))
+    }
+
+    /// log then remove a key, nothing is logged if the key is not present
+    pub fn remove(&mut self, key: K) -> io::Result<Option<V>> {
+        if !self.dict.contains_key(&key) {
+            return Ok(None);
+        }
+        self.append(OP_REMOVE, Some(&key), None)?;
+        Ok(self.dict.remove(key))
+    }
+
+    /// log then remove every entry
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.append(OP_CLEAR, None, None)?;
+        self.dict.clear();
+        Ok(())
+    }
+
+    /// flush every record written so far to disk
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.log.sync_data()?;
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    /// rewrite the log as one record per entry
+    /// the new log is written next to the old one and renamed over it,
+    /// so a crash part way leaves the old log in place. The directory is synced
+    /// after the rename so the new log is the one found after a power loss
+    pub fn compact(&mut self) -> io::Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".compact");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut bytes = MAGIC.to_vec();
+        for (key, value) in self.dict.iter() {
+            push_record(&mut bytes, OP_PUSH_BACK, Some(key), Some(value));
+        }
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(&bytes)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        sync_parent_dir(&self.path)?;
+        self.log = OpenOptions::new().append(true).open(&self.path)?;
+        self.unsynced = 0;
+        self.log_len = bytes.len() as u64;
+        self.poisoned = false;
+        Ok(())
+    }
+
+    /// the entries, the log is closed
+    pub fn into_dictionary(self) -> Dictionary<K, V> {
+        self.dict
+    }
+
+    fn append(&mut self, op: u8, key: Option<&K>, value: Option<&V>) -> io::Result<()> {
+        if self.poisoned {
+            return Err(io::Error::other(
+                "an earlier write could not be cut back off the log, recover it first",
+            ));
+        }
+        let mut bytes = Vec::new();
+        push_record(&mut bytes, op, key, value);
+        // one write per record, so a crash leaves at most one record torn
+        let written = self.log.write_all(&bytes).and_then(|_| {
+            self.unsynced += 1;
+            match self.policy {
+                SyncPolicy::Always => self.sync(),
+                SyncPolicy::Every(n) if self.unsynced >= n => self.sync(),
+                _ => Ok(()),
+            }
+        });
+        match written {
+            Ok(()) => {
+                self.log_len += bytes.len() as u64;
+                Ok(())
+            }
+            Err(err) => {
+                self.abort_append();
+                Err(err)
+            }
+        }
+    }
+
+    // cut a record that failed part way back off the log, so the records after it
+    // are not lost behind a torn one on recovery
+    fn abort_append(&mut self) {
+        let cut = self
+            .log
+            .set_len(self.log_len)
+            .and_then(|_| self.log.seek(SeekFrom::Start(self.log_len)));
+        if cut.is_err() {
+            self.poisoned = true;
+        }
+    }
+}
+
+// make a rename in the directory of path durable
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()
+}
+
+// directories can not be opened as files to sync them outside unix
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+impl<K: Hash + Eq, V> Deref for DurableDictionary<K, V> {
+    type Target = Dictionary<K, V>;
+    fn deref(&self) -> &Dictionary<K, V> {
+        &self.dict
+    }
+}
+
+fn checksum(payload: &[u8]) -> u64 {
+    let mut hasher = DigestHasher::default();
+    hasher.write(payload);
+    hasher.finish()
+}
+
+// a payload is the op, then the key and the value each prefixed with its length
+fn push_record<K: WalCodec, V: WalCodec>(
+    out: &mut Vec<u8>,
+    op: u8,
+    key: Option<&K>,
+    value: Option<&V>,
+) {
+    let mut payload = vec![op];
+    if let Some(key) = key {
+        push_field(&mut payload, |out| key.encode(out));
+    }
+    if let Some(value) = value {
+        push_field(&mut payload, |out| value.encode(out));
+    }
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(&checksum(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
+}
+
+fn push_field<F: FnOnce(&mut Vec<u8>)>(out: &mut Vec<u8>, encode: F) {
+    let start = out.len();
+    out.extend_from_slice(&[0; 4]);
+    encode(out);
+    let len = (out.len() - start - 4) as u32;
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+// read the next record into payload and return its size in the log, None if it is
+// cut short or fails its checksum. remaining is the number of bytes left in the
+// log, so a torn length can not ask for a huge buffer
+fn read_record<R: Read>(
+    input: &mut R,
+    remaining: u64,
+    payload: &mut Vec<u8>,
+) -> io::Result<Option<u64>> {
+    if remaining < RECORD_HEADER as u64 {
+        return Ok(None);
+    }
+    let mut header = [0u8; RECORD_HEADER];
+    input.read_exact(&mut header)?;
+    let len = u32::from_le_bytes(header[..4].try_into().unwrap());
+    let sum = u64::from_le_bytes(header[4..].try_into().unwrap());
+    let size = RECORD_HEADER as u64 + len as u64;
+    if size > remaining {
+        return Ok(None);
+    }
+    payload.resize(len as usize, 0);
+    input.read_exact(payload)?;
+    if checksum(payload) != sum {
+        return Ok(None);
+    }
+    Ok(Some(size))
+}
+
+fn read_field(payload: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = u32::from_le_bytes(payload.get(..4)?.try_into().unwrap()) as usize;
+    let field = payload.get(4..4 + len)?;
+    Some((field, &payload[4 + len..]))
+}
+
+// apply one logged mutation, None if the payload does not decode
+fn replay<K: Hash + Eq + Clone + WalCodec, V: Clone + WalCodec>(
+    dict: &mut Dictionary<K, V>,
+    payload: &[u8],
+) -> Option<()> {
+    let (&op, rest) = payload.split_first()?;
+    match op {
+        OP_PUSH_BACK | OP_PUSH_FRONT => {
+            let (key, rest) = read_field(rest)?;
+            let (value, _) = read_field(rest)?;
+            let (key, value) = (K::decode(key)?, V::decode(value)?);
+            if op == OP_PUSH_BACK {
+                dict.push_back(key, value);
+            } else {
+                dict.push_front(key, value);
+            }
+        }
+        OP_REMOVE => {
+            let (key, _) = read_field(rest)?;
+            dict.remove(K::decode(key)?);
+        }
+        OP_CLEAR => dict.clear(),
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rust_dict_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn recover_cuts_torn_tail() {
+        let path = temp_path("wal_torn");
+        let mut store = DurableDictionary::create(&path, SyncPolicy::Every(2)).unwrap();
+        store.push_back(String::from("b"), 2i64).unwrap();
+        store.push_front(String::from("a"), 1i64).unwrap();
+        store.push_back(String::from("c"), 3i64).unwrap();
+        store.sync().unwrap();
+        drop(store);
+
+        // a crash in the middle of writing the next record
+        let good = fs::metadata(&path).unwrap().len();
+        let mut torn = Vec::new();
+        push_record(&mut torn, OP_REMOVE, Some(&String::from("a")), None::<&i64>);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&torn[..torn.len() - 1]).unwrap();
+        drop(file);
+
+        let mut store =
+            DurableDictionary::<String, i64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), good);
+        assert_eq!(store.keys(), &vec!["a".to_string(), "b".into(), "c".into()]);
+        store.remove("b".into()).unwrap();
+        drop(store);
+        let store = DurableDictionary::<String, i64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(store.values(), &vec![1, 3]);
+        fs::remove_file(&path).unwrap();
+
+        fs::write(&path, b"not a log").unwrap();
+        let error = DurableDictionary::<String, i64>::recover(&path, SyncPolicy::Never);
+        assert_eq!(error.err().unwrap().kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_append_keeps_later_records() {
+        let path = temp_path("wal_failed_append");
+        let mut store = DurableDictionary::create(&path, SyncPolicy::Never).unwrap();
+        store.push_back(String::from("a"), 1i64).unwrap();
+        // a write that stopped part way, as on a full disk
+        let mut torn = Vec::new();
+        push_record(&mut torn, OP_CLEAR, None::<&String>, None::<&i64>);
+        store.log.write_all(&torn[..5]).unwrap();
+        store.abort_append();
+        store.push_back(String::from("b"), 2i64).unwrap();
+        drop(store);
+        let mut store =
+            DurableDictionary::<String, i64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(store.values(), &vec![1, 2]);
+
+        // a log that can not be cut back refuses writes until it is recovered
+        store.log = File::open(&path).unwrap();
+        assert!(store.push_back(String::from("c"), 3).is_err());
+        assert!(store.poisoned);
+        assert!(store.push_back(String::from("d"), 4).is_err());
+        assert_eq!(store.len(), 2);
+        drop(store);
+        let store = DurableDictionary::<String, i64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(store.values(), &vec![1, 2]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn recover_cuts_a_torn_length() {
+        let path = temp_path("wal_torn_length");
+        let mut store = DurableDictionary::create(&path, SyncPolicy::Never).unwrap();
+        for i in 0..50u64 {
+            store.push_back(i % 7, i).unwrap();
+        }
+        store.remove(3).unwrap();
+        let expected = store.into_dictionary();
+        let len = fs::metadata(&path).unwrap().len();
+        // a record header whose length runs far past the end of the log
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0xff; RECORD_HEADER + 3]).unwrap();
+        drop(file);
+        let mut store = DurableDictionary::<u64, u64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(*store, expected);
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+        store.push_back(100, 100).unwrap();
+        drop(store);
+        let store = DurableDictionary::<u64, u64>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(store.get(100), Some(100));
+        assert_eq!(store.len(), expected.len() + 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn compact_rewrites_the_log() {
+        let path = temp_path("wal_compact");
+        let mut store = DurableDictionary::recover(&path, SyncPolicy::Never).unwrap();
+        for i in 0..100u64 {
+            store.push_back(i % 10, vec![i as u8]).unwrap();
+        }
+        store.clear().unwrap();
+        store.push_back(7, vec![1, 2]).unwrap();
+        let before = fs::metadata(&path).unwrap().len();
+        store.compact().unwrap();
+        assert!(fs::metadata(&path).unwrap().len() < before);
+        store.push_back(8, vec![3]).unwrap();
+        drop(store);
+        let store = DurableDictionary::<u64, Vec<u8>>::recover(&path, SyncPolicy::Never).unwrap();
+        assert_eq!(
+            store.into_dictionary(),
+            Dictionary::from([(7, vec![1, 2]), (8, vec![3])])
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
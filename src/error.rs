@@ -0,0 +1,60 @@
+use crate::dict::Sealed;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// Error returned by `Dictionary::try_push_back` and `Dictionary::try_insert` when the
+/// key is already in the dictionary, nothing is changed and the key and value are
+/// handed back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccupiedError<K, V> {
+    pub key: K,
+    pub value: V,
+    /// the position of the key already in the dictionary
+    pub index: usize,
+}
+
+impl<K: Debug, V> Display for OccupiedError<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "key {:?} is already at index {}", self.key, self.index)
+    }
+}
+
+impl<K: Debug, V: Debug> Error for OccupiedError<K, V> {}
+
+/// The conditions the fallible Dictionary methods report instead of panicking
+/// or overwriting, returned by `try_push_back`, `try_insert`, `insert` and `shift_insert`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DictError<K, V> {
+    /// the key is already in the dictionary
+    Occupied(OccupiedError<K, V>),
+    /// the position is past the end of the dictionary
+    IndexOutOfBounds { index: usize, len: usize },
+    /// the dictionary is sealed
+    Sealed,
+}
+
+impl<K: Debug, V> Display for DictError<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DictError::Occupied(occupied) => Display::fmt(occupied, f),
+            DictError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for length {}", index, len)
+            }
+            DictError::Sealed => Display::fmt(&Sealed, f),
+        }
+    }
+}
+
+impl<K: Debug, V: Debug> Error for DictError<K, V> {}
+
+impl<K, V> From<OccupiedError<K, V>> for DictError<K, V> {
+    fn from(occupied: OccupiedError<K, V>) -> DictError<K, V> {
+        DictError::Occupied(occupied)
+    }
+}
+
+impl<K, V> From<Sealed> for DictError<K, V> {
+    fn from(_: Sealed) -> DictError<K, V> {
+        DictError::Sealed
+    }
+}
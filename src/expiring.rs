@@ -0,0 +1,267 @@
+use crate::dict::Dictionary;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+struct Entry<V> {
+    value: V,
+    deadline: Instant,
+}
+
+/// An ordered dictionary where each entry carries a deadline, a session or token table
+/// reads treat an entry as absent from its deadline on. Expired entries stay stored
+/// until `purge_expired` removes them, or a write purges them when purge_on_write is set.
+/// The `_at` methods take the current time so callers can use their own clock
+/// # Example
+/// ```
+/// use rust_dict::expiring::ExpiringDictionary;
+/// use std::time::{Duration, Instant};
+///
+/// let mut sessions = ExpiringDictionary::new();
+/// sessions.push_back("alice", 1, Duration::from_secs(60));
+/// sessions.push_back("bob", 2, Duration::from_secs(3600));
+/// assert_eq!(sessions.get(&"alice"), Some(&1));
+///
+/// let later = Instant::now() + Duration::from_secs(120);
+/// assert_eq!(sessions.get_at(&"alice", later), None);
+/// assert_eq!(sessions.purge_expired_at(later), vec![("alice", 1)]);
+/// assert_eq!(sessions.keys(), &vec!["bob"]);
+/// ```
+pub struct ExpiringDictionary<K: Hash + Eq, V> {
+    dict: Dictionary<K, Entry<V>>,
+    purge_on_write: bool,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ExpiringDictionary<K, V> {
+    pub fn new() -> ExpiringDictionary<K, V> {
+        ExpiringDictionary {
+            dict: Dictionary::new(),
+            purge_on_write: false,
+        }
+    }
+
+    /// purge expired entries on every push_back, so the table does not grow with dead entries
+    pub fn set_purge_on_write(&mut self, purge: bool) {
+        self.purge_on_write = purge;
+    }
+
+    /// add or replace a value that expires after ttl, returning the previous live value
+    /// a replaced key keeps its position and takes the new deadline.
+    /// A ttl too large for the clock, such as Duration::MAX, never expires
+    pub fn push_back(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let now = Instant::now();
+        self.push_back_until_at(key, value, deadline_after(now, ttl), now)
+    }
+
+    /// add or replace a value that expires at deadline, returning the previous live value
+    pub fn push_back_until(&mut self, key: K, value: V, deadline: Instant) -> Option<V> {
+        self.push_back_until_at(key, value, deadline, Instant::now())
+    }
+
+    /// push_back_until with now as the current time
+    pub fn push_back_until_at(
+        &mut self,
+        key: K,
+        value: V,
+        deadline: Instant,
+        now: Instant,
+    ) -> Option<V> {
+        if self.purge_on_write {
+            self.purge_expired_at(now);
+        }
+        self.dict
+            .push_back(key, Entry { value, deadline })
+            .filter(|old| old.deadline > now)
+            .map(|old| old.value)
+    }
+
+    /// the value for the key if it has not expired
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// the value for the key if it has not expired at now
+    pub fn get_at(&self, key: &K, now: Instant) -> Option<&V> {
+        self.entry_at(key, now).map(|entry| &entry.value)
+    }
+
+    /// the deadline of the key if it has not expired
+    pub fn deadline(&self, key: &K) -> Option<Instant> {
+        self.entry_at(key, Instant::now())
+            .map(|entry| entry.deadline)
+    }
+
+    fn entry_at(&self, key: &K, now: Instant) -> Option<&Entry<V>> {
+        self.dict
+            .get_full(key)
+            .map(|(_, _, entry)| entry)
+            .filter(|entry| entry.deadline > now)
+    }
+
+    /// move the deadline of a live key to ttl from now, false if it is absent or expired
+    pub fn refresh(&mut self, key: &K, ttl: Duration) -> bool {
+        let now = Instant::now();
+        match self.dict.get_mut(key.clone()) {
+            Some(entry) if entry.deadline > now => {
+                entry.deadline = deadline_after(now, ttl);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// remove the key, returning its value if it had not expired
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let now = Instant::now();
+        self.dict
+            .remove(key.clone())
+            .filter(|entry| entry.deadline > now)
+            .map(|entry| entry.value)
+    }
+
+    /// remove the expired entries, returning them in order
+    pub fn purge_expired(&mut self) -> Vec<(K, V)> {
+        self.purge_expired_at(Instant::now())
+    }
+
+    /// remove the entries expired at now, returning them in order
+    pub fn purge_expired_at(&mut self, now: Instant) -> Vec<(K, V)> {
+        let expired: Vec<K> = self
+            .dict
+            .keys()
+            .iter()
+            .zip(self.dict.values())
+            .filter(|(_, entry)| entry.deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.dict
+            .remove_many(expired)
+            .into_iter()
+            .map(|(key, entry)| (key, entry.value))
+            .collect()
+    }
+
+    /// the number of stored entries, including expired ones not yet purged
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+
+    /// the stored keys in order, including expired ones not yet purged
+    pub fn keys(&self) -> &Vec<K> {
+        self.dict.keys()
+    }
+
+    /// the live entries in order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let now = Instant::now();
+        self.dict
+            .keys()
+            .iter()
+            .zip(self.dict.values())
+            .filter(move |(_, entry)| entry.deadline > now)
+            .map(|(key, entry)| (key, &entry.value))
+    }
+
+    /// the live entries as a Dictionary, dropping the deadlines
+    pub fn to_dictionary(&self) -> Dictionary<K, V> {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+// now + ttl, or when that overflows the clock a deadline so far out it never comes
+fn deadline_after(now: Instant, mut ttl: Duration) -> Instant {
+    loop {
+        match now.checked_add(ttl) {
+            Some(deadline) => return deadline,
+            None => ttl /= 2,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for ExpiringDictionary<K, V> {
+    fn default() -> ExpiringDictionary<K, V> {
+        ExpiringDictionary::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone + Debug, V: Clone + Debug> Debug for ExpiringDictionary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_entries_read_as_absent() {
+        let start = Instant::now();
+        let mut tokens = ExpiringDictionary::new();
+        tokens.push_back_until_at("a", 1, start + Duration::from_secs(10), start);
+        tokens.push_back_until_at("b", 2, start + Duration::from_secs(20), start);
+        let later = start + Duration::from_secs(15);
+        assert_eq!(tokens.get_at(&"a", start), Some(&1));
+        assert_eq!(tokens.get_at(&"a", later), None);
+        assert_eq!(tokens.get_at(&"b", later), Some(&2));
+        // replacing an expired key does not return the dead value, the key keeps its place
+        let deadline = later + Duration::from_secs(10);
+        assert_eq!(tokens.push_back_until_at("a", 3, deadline, later), None);
+        assert_eq!(tokens.push_back_until_at("b", 4, deadline, later), Some(2));
+        assert_eq!(tokens.keys(), &vec!["a", "b"]);
+    }
+
+    #[test]
+    fn purge_in_order() {
+        let start = Instant::now();
+        let mut tokens = ExpiringDictionary::new();
+        for (i, secs) in [5, 30, 1, 40].into_iter().enumerate() {
+            tokens.push_back_until_at(i, i * 10, start + Duration::from_secs(secs), start);
+        }
+        let later = start + Duration::from_secs(10);
+        assert_eq!(tokens.purge_expired_at(later), vec![(0, 0), (2, 20)]);
+        assert_eq!(tokens.purge_expired_at(later), vec![]);
+        assert_eq!(tokens.keys(), &vec![1, 3]);
+
+        tokens.set_purge_on_write(true);
+        let much_later = start + Duration::from_secs(35);
+        tokens.push_back_until_at(4, 40, much_later + Duration::from_secs(1), much_later);
+        assert_eq!(tokens.keys(), &vec![3, 4]);
+        assert!(tokens.refresh(&3, Duration::from_secs(60)));
+        assert!(!tokens.refresh(&9, Duration::from_secs(60)));
+        assert_eq!(tokens.to_dictionary().len(), 2);
+    }
+
+    #[test]
+    fn huge_ttl_never_expires() {
+        let mut tokens = ExpiringDictionary::new();
+        tokens.push_back("forever", 1, Duration::MAX);
+        assert!(tokens.refresh(&"forever", Duration::MAX));
+        let later = Instant::now() + Duration::from_secs(86400 * 365 * 100);
+        assert_eq!(tokens.get_at(&"forever", later), Some(&1));
+    }
+
+    #[test]
+    fn purge_keeps_capacity() {
+        let start = Instant::now();
+        let mut tokens = ExpiringDictionary::new();
+        for i in 0..100 {
+            tokens.push_back_until_at(i, i, start + Duration::from_secs(i % 2), start);
+        }
+        let capacity = tokens.dict.capacity();
+        assert_eq!(tokens.purge_expired_at(start).len(), 50);
+        assert_eq!(tokens.dict.capacity(), capacity);
+        assert_eq!(tokens.keys()[..3], [1, 3, 5]);
+    }
+}
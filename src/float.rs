@@ -0,0 +1,159 @@
+use crate::dict::Dictionary;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// Error returned when a NaN is used as a float key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanKeyError;
+
+impl Display for NanKeyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "NaN can not be used as a dictionary key")
+    }
+}
+
+impl Error for NanKeyError {}
+
+/// An f64 that is never NaN, so it can be hashed, compared for equality and sorted
+/// -0.0 and 0.0 are the same key
+#[derive(Debug, Clone, Copy)]
+pub struct FloatKey(f64);
+
+impl FloatKey {
+    /// wrap a float, rejecting NaN
+    pub fn new(value: f64) -> Result<FloatKey, NanKeyError> {
+        if value.is_nan() {
+            return Err(NanKeyError);
+        }
+        // fold -0.0 into 0.0 so equal keys hash the same
+        if value == 0.0 {
+            return Ok(FloatKey(0.0));
+        }
+        Ok(FloatKey(value))
+    }
+
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for FloatKey {
+    type Error = NanKeyError;
+    fn try_from(value: f64) -> Result<FloatKey, NanKeyError> {
+        FloatKey::new(value)
+    }
+}
+
+impl From<FloatKey> for f64 {
+    fn from(key: FloatKey) -> f64 {
+        key.0
+    }
+}
+
+impl PartialEq for FloatKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for FloatKey {}
+
+impl Hash for FloatKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl PartialOrd for FloatKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloatKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Display for FloatKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A Dictionary keyed by floats
+/// sort_by_keys orders the keys numerically
+pub type FloatDict<V> = Dictionary<FloatKey, V>;
+
+impl<V: Clone> Dictionary<FloatKey, V> {
+    /// push_back with a raw float key, NaN keys are rejected
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    /// # Example
+    /// ```
+    /// use rust_dict::float::{FloatDict, NanKeyError};
+    ///
+    /// let mut dict = FloatDict::<&str>::new();
+    /// dict.push_float(2.5, "b").unwrap();
+    /// dict.push_float(-1.0, "a").unwrap();
+    /// assert_eq!(dict.push_float(f64::NAN, "c"), Err(NanKeyError));
+    /// dict.sort_by_keys();
+    /// assert_eq!(dict.get_float(-1.0), Some("a"));
+    /// assert_eq!(dict.get_index(1), Some("b"));
+    /// ```
+    pub fn push_float(&mut self, key: f64, value: V) -> Result<Option<V>, NanKeyError> {
+        Ok(self.push_back(FloatKey::new(key)?, value))
+    }
+
+    /// get by a raw float key, NaN is never in the dictionary
+    pub fn get_float(&self, key: f64) -> Option<V> {
+        self.get(FloatKey::new(key).ok()?)
+    }
+
+    /// remove by a raw float key
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn remove_float(&mut self, key: f64) -> Option<V> {
+        self.remove(FloatKey::new(key).ok()?)
+    }
+
+    /// build a FloatDict from float key pairs, failing on the first NaN key
+    pub fn try_from_floats<I: IntoIterator<Item = (f64, V)>>(
+        iter: I,
+    ) -> Result<FloatDict<V>, NanKeyError> {
+        let mut dict = Dictionary::new();
+        for (key, value) in iter {
+            dict.push_float(key, value)?;
+        }
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_keys() {
+        let mut dict = FloatDict::<i32>::try_from_floats([(3.5, 1), (-2.0, 2), (0.0, 3)]).unwrap();
+        assert_eq!(dict.get_float(-0.0), Some(3));
+        assert_eq!(dict.push_float(-0.0, 4), Ok(Some(3)));
+        assert_eq!(dict.get_float(f64::NAN), None);
+        dict.sort_by_keys();
+        let keys: Vec<f64> = dict.keys().iter().map(|k| k.get()).collect();
+        assert_eq!(keys, vec![-2.0, 0.0, 3.5]);
+        assert_eq!(dict.remove_float(3.5), Some(1));
+    }
+
+    #[test]
+    fn nan_rejected() {
+        assert_eq!(FloatKey::new(f64::NAN), Err(NanKeyError));
+        assert_eq!(
+            FloatDict::<i32>::try_from_floats([(1.0, 1), (f64::NAN, 2)]),
+            Err(NanKeyError)
+        );
+    }
+}
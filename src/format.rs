@@ -0,0 +1,311 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// How FormatValue writes bools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoolCase {
+    /// true and false
+    #[default]
+    Lower,
+    /// TRUE and FALSE
+    Upper,
+    /// True and False, like python
+    Title,
+}
+
+/// How FormatValue writes byte strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BytesFormat {
+    /// lowercase hex with no separator, 0a1b
+    #[default]
+    Hex,
+    /// uppercase hex with no separator, 0A1B
+    UpperHex,
+    /// the bytes as a list, [10, 27]
+    List,
+}
+
+/// Options for formatting values with FormatValue
+/// set per dictionary through `DictionaryBuilder::format_options` or
+/// `Dictionary::set_format_options`, the default writes values the way Display does,
+/// with bytes as lowercase hex
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FormatOptions {
+    /// digits after the decimal point for floats, None for the shortest exact form
+    pub float_precision: Option<usize>,
+    pub bool_case: BoolCase,
+    pub bytes: BytesFormat,
+}
+
+impl FormatOptions {
+    /// the default options, usable in a const context
+    pub const fn new() -> FormatOptions {
+        FormatOptions {
+            float_precision: None,
+            bool_case: BoolCase::Lower,
+            bytes: BytesFormat::Hex,
+        }
+    }
+}
+
+/// Values that format according to FormatOptions
+/// used by `Dictionary::formatted` and by CSV export, so exported artifacts
+/// do not depend on how a type happens to implement Display
+pub trait FormatValue {
+    fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result;
+}
+
+impl FormatValue for bool {
+    fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+        let text = match (options.bool_case, self) {
+            (BoolCase::Lower, true) => "true",
+            (BoolCase::Lower, false) => "false",
+            (BoolCase::Upper, true) => "TRUE",
+            (BoolCase::Upper, false) => "FALSE",
+            (BoolCase::Title, true) => "True",
+            (BoolCase::Title, false) => "False",
+        };
+        out.write_str(text)
+    }
+}
+
+macro_rules! format_float {
+    ($($t:ty),*) => {
+        $(impl FormatValue for $t {
+            fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+                match options.float_precision {
+                    Some(precision) => write!(out, "{:.*}", precision, self),
+                    None => write!(out, "{}", self),
+                }
+            }
+        })*
+    };
+}
+
+format_float!(f32, f64);
+
+// types whose Display output has nothing to configure
+macro_rules! format_display {
+    ($($t:ty),*) => {
+        $(impl FormatValue for $t {
+            fn format_value(&self, _options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+                write!(out, "{}", self)
+            }
+        })*
+    };
+}
+
+format_display!(char, str, String, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl FormatValue for [u8] {
+    fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+        match options.bytes {
+            BytesFormat::Hex => self.iter().try_for_each(|b| write!(out, "{:02x}", b)),
+            BytesFormat::UpperHex => self.iter().try_for_each(|b| write!(out, "{:02X}", b)),
+            BytesFormat::List => write!(out, "{:?}", self),
+        }
+    }
+}
+
+impl FormatValue for Vec<u8> {
+    fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+        self.as_slice().format_value(options, out)
+    }
+}
+
+impl<T: FormatValue + ?Sized> FormatValue for &T {
+    fn format_value(&self, options: &FormatOptions, out: &mut dyn Write) -> fmt::Result {
+        (**self).format_value(options, out)
+    }
+}
+
+// writes the entries of a dictionary for Display, `{k: v, k2: v2}` on one line or,
+// with the alternate flag, one indented entry per line with a trailing comma.
+// Text that would be ambiguous between the delimiters is double quoted and escaped
+pub(crate) struct EntryWriter<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    pretty: bool,
+    empty: bool,
+}
+
+impl<'a, 'b> EntryWriter<'a, 'b> {
+    pub(crate) fn new(f: &'a mut Formatter<'b>) -> Result<EntryWriter<'a, 'b>, fmt::Error> {
+        f.write_str("{")?;
+        Ok(EntryWriter {
+            pretty: f.alternate(),
+            f,
+            empty: true,
+        })
+    }
+
+    pub(crate) fn entry<K: Display + ?Sized, V: Display + ?Sized>(
+        &mut self,
+        key: &K,
+        value: &V,
+    ) -> fmt::Result {
+        self.separate()?;
+        write_quoted(self.f, &key.to_string())?;
+        self.f.write_str(": ")?;
+        write_quoted(self.f, &value.to_string())?;
+        self.terminate()
+    }
+
+    // a line that is not an entry, written as is
+    pub(crate) fn note(&mut self, text: &str) -> fmt::Result {
+        self.separate()?;
+        self.f.write_str(text)?;
+        self.terminate()
+    }
+
+    pub(crate) fn finish(self) -> fmt::Result {
+        if self.pretty && !self.empty {
+            self.f.write_str("\n")?;
+        }
+        self.f.write_str("}")
+    }
+
+    fn separate(&mut self) -> fmt::Result {
+        let separator = match (self.pretty, self.empty) {
+            (true, _) => "\n    ",
+            (false, true) => "",
+            (false, false) => ", ",
+        };
+        self.empty = false;
+        self.f.write_str(separator)
+    }
+
+    fn terminate(&mut self) -> fmt::Result {
+        if self.pretty {
+            self.f.write_str(",")?;
+        }
+        Ok(())
+    }
+}
+
+fn needs_quotes(text: &str) -> bool {
+    text.is_empty()
+        || text.trim() != text
+        || text
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '"' | '\\' | ',' | ':' | '{' | '}'))
+}
+
+fn write_quoted(f: &mut Formatter, text: &str) -> fmt::Result {
+    if !needs_quotes(text) {
+        return f.write_str(text);
+    }
+    f.write_str("\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// A Display proxy that formats a Dictionary with its FormatOptions,
+/// created with `Dictionary::formatted`
+pub struct Formatted<'a, K, V, S: BuildIndex<K>> {
+    dict: &'a Dictionary<K, V, S>,
+}
+
+impl<'a, K: FormatValue, V: FormatValue, S: BuildIndex<K>> Display for Formatted<'a, K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // mirrors the Display of Dictionary
+        let options = self.dict.format_options();
+        let mut out = EntryWriter::new(f)?;
+        let (mut key_text, mut value_text) = (String::new(), String::new());
+        for (key, value) in self.dict.keys().iter().zip(self.dict.values()) {
+            key_text.clear();
+            value_text.clear();
+            key.format_value(options, &mut key_text)?;
+            value.format_value(options, &mut value_text)?;
+            out.entry(key_text.as_str(), value_text.as_str())?;
+        }
+        out.finish()
+    }
+}
+
+impl<K, V, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// format the dictionary like Display, but with FormatValue and its FormatOptions
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::format::{BoolCase, FormatOptions};
+    ///
+    /// let mut dict = Dictionary::<&str, f64>::builder()
+    ///     .format_options(FormatOptions {
+    ///         float_precision: Some(2),
+    ///         ..FormatOptions::default()
+    ///     })
+    ///     .build();
+    /// dict.push_back("ratio", 1.0 / 3.0);
+    /// assert_eq!(dict.formatted().to_string(), "{ratio: 0.33}");
+    ///
+    /// let mut flags = Dictionary::from([("debug", true)]);
+    /// flags.set_format_options(FormatOptions {
+    ///     bool_case: BoolCase::Title,
+    ///     ..FormatOptions::default()
+    /// });
+    /// assert_eq!(format!("{:#}", flags.formatted()), "{\n    debug: True,\n}");
+    /// ```
+    pub fn formatted(&self) -> Formatted<'_, K, V, S> {
+        Formatted { dict: self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format<T: FormatValue>(value: T, options: FormatOptions) -> String {
+        let mut out = String::new();
+        value.format_value(&options, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn value_options() {
+        let default = FormatOptions::default();
+        assert_eq!(format(0.1f64 + 0.2, default), "0.30000000000000004");
+        let fixed = FormatOptions {
+            float_precision: Some(3),
+            ..default
+        };
+        assert_eq!(format(2.0f32, fixed), "2.000");
+        assert_eq!(format(7u8, fixed), "7");
+        let upper = FormatOptions {
+            bool_case: BoolCase::Upper,
+            bytes: BytesFormat::UpperHex,
+            ..default
+        };
+        assert_eq!(format(false, upper), "FALSE");
+        assert_eq!(format(vec![0x0a, 0xff], default), "0aff");
+        assert_eq!(format(vec![0x0a, 0xff], upper), "0AFF");
+        let list = FormatOptions {
+            bytes: BytesFormat::List,
+            ..default
+        };
+        assert_eq!(format(&[1u8, 2][..], list), "[1, 2]");
+    }
+
+    #[test]
+    fn formatted_dictionary() {
+        let mut dict = Dictionary::from([(b"id".to_vec(), 1.5f64)]);
+        assert_eq!(dict.formatted().to_string(), "{6964: 1.5}");
+        dict.set_format_options(FormatOptions {
+            float_precision: Some(0),
+            bytes: BytesFormat::List,
+            ..FormatOptions::default()
+        });
+        assert_eq!(dict.formatted().to_string(), r#"{"[105, 100]": 2}"#);
+        assert_eq!(dict.clone().format_options(), dict.format_options());
+    }
+}
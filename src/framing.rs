@@ -0,0 +1,409 @@
+use crate::dict::Dictionary;
+use crate::durable::WalCodec;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::io::{self, Read, Write};
+
+// every stream written by write_to starts with this, then the format version byte
+const MAGIC: &[u8; 7] = b"RDICT\0\0";
+
+/// the format version write_to writes, the byte after the magic in the header
+pub const FORMAT_VERSION: u8 = 1;
+
+// read_from reserves at most this many entries up front, so a corrupt count
+// can not ask for an arbitrary allocation
+const MAX_RESERVE: u64 = 1 << 20;
+
+/// Errors raised while decoding length prefixed bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// the buffer ends inside the frame starting at this byte offset
+    Truncated { offset: usize },
+    /// the key framed at this byte offset is already in the dictionary
+    RepeatedKey { offset: usize },
+}
+
+impl Display for FrameError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FrameError::Truncated { offset } => {
+                write!(f, "offset {}: buffer ends inside a frame", offset)
+            }
+            FrameError::RepeatedKey { offset } => {
+                write!(f, "offset {}: key is repeated", offset)
+            }
+        }
+    }
+}
+
+impl Error for FrameError {}
+
+/// reads the rest of a stream written in an older format version, everything
+/// after the version byte, for read_from_any_version
+pub type Migration<K, V> = fn(&mut dyn Read) -> io::Result<Dictionary<K, V>>;
+
+/// The migrations read_from_any_version falls back on for streams that are not in
+/// the current format version, one per older version
+pub struct MigrationRegistry<K: Hash + Eq, V> {
+    migrations: Vec<(u8, Migration<K, V>)>,
+}
+
+impl<K: Hash + Eq, V> MigrationRegistry<K, V> {
+    /// a registry with no migrations, only the current version can be read
+    pub fn new() -> Self {
+        MigrationRegistry {
+            migrations: Vec::new(),
+        }
+    }
+
+    /// read streams in version with migration, replacing an earlier one for it
+    /// # Panics
+    /// panics if version is FORMAT_VERSION, which is always read natively
+    pub fn register(mut self, version: u8, migration: Migration<K, V>) -> Self {
+        assert!(
+            version != FORMAT_VERSION,
+            "the current format version can not be migrated"
+        );
+        self.migrations.retain(|(v, _)| *v != version);
+        self.migrations.push((version, migration));
+        self
+    }
+
+    fn get(&self, version: u8) -> Option<Migration<K, V>> {
+        self.migrations
+            .iter()
+            .find(|(v, _)| *v == version)
+            .map(|(_, migration)| *migration)
+    }
+}
+
+impl<K: Hash + Eq, V> Default for MigrationRegistry<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dictionary<Vec<u8>, Vec<u8>> {
+    /// Decode entries framed as a little endian u32 length then the bytes,
+    /// key then value for every entry, in order
+    /// an empty buffer is an empty dictionary.
+    /// A repeated key is an error, like in read_from
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(b"id".to_vec(), b"7".to_vec())]);
+    /// let bytes = dict.to_length_prefixed_bytes();
+    /// assert_eq!(bytes, b"\x02\0\0\0id\x01\0\0\x007");
+    /// assert_eq!(Dictionary::from_length_prefixed_bytes(&bytes), Ok(dict));
+    /// ```
+    pub fn from_length_prefixed_bytes(
+        buf: &[u8],
+    ) -> Result<Dictionary<Vec<u8>, Vec<u8>>, FrameError> {
+        let mut dict = Dictionary::new();
+        let mut pos = 0;
+        while pos < buf.len() {
+            let offset = pos;
+            let key = read_frame(buf, &mut pos)?.to_vec();
+            let value = read_frame(buf, &mut pos)?;
+            if dict.contains_key(&key) {
+                return Err(FrameError::RepeatedKey { offset });
+            }
+            dict.push_back(key, value.to_vec());
+        }
+        Ok(dict)
+    }
+
+    /// encode the entries in order for from_length_prefixed_bytes
+    /// # Panics
+    /// panics if a key or value is longer than u32::MAX bytes
+    pub fn to_length_prefixed_bytes(&self) -> Vec<u8> {
+        let size: usize = self.iter().map(|(k, v)| 8 + k.len() + v.len()).sum();
+        let mut buf = Vec::with_capacity(size);
+        for (key, value) in self.iter() {
+            write_frame(&mut buf, key);
+            write_frame(&mut buf, value);
+        }
+        buf
+    }
+}
+
+impl<K: Hash + Eq + Clone + WalCodec, V: Clone + WalCodec> Dictionary<K, V> {
+    /// stream the entries in order as a binary encoding, read back with read_from
+    /// the stream is a header with the format version and entry count, then the key
+    /// and value of every entry each as a little endian u32 length and the WalCodec bytes.
+    /// Writes go straight to out, wrap it in a BufWriter for files and sockets
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let table = Dictionary::from([(String::from("b"), 2u64), (String::from("a"), 1u64)]);
+    /// let mut bytes = Vec::new();
+    /// table.write_to(&mut bytes).unwrap();
+    /// assert_eq!(Dictionary::read_from(bytes.as_slice()).unwrap(), table);
+    /// ```
+    pub fn write_to<W: Write>(&self, mut out: W) -> io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&[FORMAT_VERSION])?;
+        out.write_all(&(self.len() as u64).to_le_bytes())?;
+        let mut buf = Vec::new();
+        for (key, value) in self.iter() {
+            buf.clear();
+            key.encode(&mut buf);
+            write_stream_frame(&mut out, &buf)?;
+            buf.clear();
+            value.encode(&mut buf);
+            write_stream_frame(&mut out, &buf)?;
+        }
+        Ok(())
+    }
+
+    /// read a dictionary written by write_to, in its order
+    /// fails with InvalidData if the header is wrong, the stream is in another format
+    /// version, a key or value does not decode or a key is repeated,
+    /// and with UnexpectedEof if the stream ends early
+    pub fn read_from<R: Read>(input: R) -> io::Result<Dictionary<K, V>> {
+        Self::read_from_any_version(input, &MigrationRegistry::new())
+    }
+
+    /// read_from, but a stream in an older format version is handed to the migration
+    /// registered for that version, after its version byte
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::framing::MigrationRegistry;
+    /// use std::io::Read;
+    ///
+    /// // version 0 was a single byte count then a byte per key and value
+    /// fn from_v0(input: &mut dyn Read) -> std::io::Result<Dictionary<u64, u64>> {
+    ///     let mut bytes = Vec::new();
+    ///     input.read_to_end(&mut bytes)?;
+    ///     Ok(bytes[1..].chunks(2).map(|kv| (kv[0] as u64, kv[1] as u64)).collect())
+    /// }
+    ///
+    /// let registry = MigrationRegistry::new().register(0, from_v0);
+    /// let old = b"RDICT\0\0\x00\x01\x05\x07";
+    /// let dict = Dictionary::read_from_any_version(&old[..], &registry).unwrap();
+    /// assert_eq!(dict, Dictionary::from([(5, 7)]));
+    /// assert!(Dictionary::<u64, u64>::read_from(&old[..]).is_err());
+    /// ```
+    pub fn read_from_any_version<R: Read>(
+        mut input: R,
+        migrations: &MigrationRegistry<K, V>,
+    ) -> io::Result<Dictionary<K, V>> {
+        let mut magic = [0u8; 7];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a dictionary stream"));
+        }
+        let mut version = [0u8; 1];
+        input.read_exact(&mut version)?;
+        match version[0] {
+            FORMAT_VERSION => read_v1(&mut input),
+            version => match migrations.get(version) {
+                Some(migration) => migration(&mut input),
+                None => Err(invalid_data("unsupported format version")),
+            },
+        }
+    }
+}
+
+fn read_v1<K: Hash + Eq + Clone + WalCodec, V: Clone + WalCodec, R: Read>(
+    input: &mut R,
+) -> io::Result<Dictionary<K, V>> {
+    let mut len = [0u8; 8];
+    input.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    let mut dict = Dictionary::with_capacity(len.min(MAX_RESERVE) as usize);
+    let mut buf = Vec::new();
+    for _ in 0..len {
+        read_stream_frame(input, &mut buf)?;
+        let key = K::decode(&buf).ok_or_else(|| invalid_data("key does not decode"))?;
+        read_stream_frame(input, &mut buf)?;
+        let value = V::decode(&buf).ok_or_else(|| invalid_data("value does not decode"))?;
+        if dict.contains_key(&key) {
+            return Err(invalid_data("key is repeated"));
+        }
+        dict.push_back(key, value);
+    }
+    Ok(dict)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_stream_frame<W: Write>(out: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len =
+        u32::try_from(bytes.len()).map_err(|_| invalid_data("frame longer than u32::MAX bytes"))?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(bytes)
+}
+
+// read one frame into buf, reusing its allocation
+fn read_stream_frame<R: Read>(input: &mut R, buf: &mut Vec<u8>) -> io::Result<()> {
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    buf.clear();
+    input.by_ref().take(len as u64).read_to_end(buf)?;
+    if buf.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+fn read_frame<'a>(buf: &'a [u8], pos: &mut usize) -> Result<&'a [u8], FrameError> {
+    let offset = *pos;
+    let truncated = FrameError::Truncated { offset };
+    let start = offset.checked_add(4).ok_or(truncated.clone())?;
+    let len = buf.get(offset..start).ok_or(truncated.clone())?;
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+    let end = start.checked_add(len).ok_or(truncated.clone())?;
+    let bytes = buf.get(start..end).ok_or(truncated)?;
+    *pos = end;
+    Ok(bytes)
+}
+
+fn write_frame(buf: &mut Vec<u8>, bytes: &[u8]) {
+    let len = u32::try_from(bytes.len()).expect("frame longer than u32::MAX bytes");
+    buf.extend_from_slice(&len.to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framing_round_trip() {
+        let mut dict = Dictionary::new();
+        dict.push_back(b"z".to_vec(), Vec::new());
+        dict.push_back(Vec::new(), vec![0, 255, 0]);
+        dict.push_back(b"a".to_vec(), vec![1; 300]);
+        let bytes = dict.to_length_prefixed_bytes();
+        assert_eq!(bytes.len(), 3 * 8 + 1 + 3 + 1 + 300);
+        assert_eq!(Dictionary::from_length_prefixed_bytes(&bytes), Ok(dict));
+        assert_eq!(
+            Dictionary::from_length_prefixed_bytes(&[]),
+            Ok(Dictionary::new())
+        );
+    }
+
+    #[test]
+    fn stream_round_trip() {
+        let mut table: Dictionary<u64, Vec<u8>> = (0..200)
+            .rev()
+            .map(|i| (i, vec![i as u8; i as usize]))
+            .collect();
+        table.push_back(1000, Vec::new());
+        let mut bytes = Vec::new();
+        table.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[..7], MAGIC);
+        assert_eq!(bytes[7], FORMAT_VERSION);
+        let read = Dictionary::<u64, Vec<u8>>::read_from(io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(read, table);
+
+        let empty: Dictionary<String, u64> = Dictionary::new();
+        let mut bytes = Vec::new();
+        empty.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(Dictionary::read_from(bytes.as_slice()).unwrap(), empty);
+    }
+
+    #[test]
+    fn bad_streams() {
+        let table = Dictionary::from([(String::from("key"), 7u64)]);
+        let mut bytes = Vec::new();
+        table.write_to(&mut bytes).unwrap();
+        let read = |bytes: &[u8]| {
+            Dictionary::<String, u64>::read_from(bytes)
+                .unwrap_err()
+                .kind()
+        };
+        assert_eq!(
+            read(&bytes[..bytes.len() - 1]),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(read(&bytes[..4]), io::ErrorKind::UnexpectedEof);
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert_eq!(read(&wrong_magic), io::ErrorKind::InvalidData);
+        // a u64 value frame that is not 8 bytes long
+        let mut short_value = bytes[..bytes.len() - 12].to_vec();
+        short_value.extend_from_slice(&[1, 0, 0, 0, 7]);
+        assert_eq!(read(&short_value), io::ErrorKind::InvalidData);
+        // the same key twice
+        let mut repeated = bytes[..8].to_vec();
+        repeated.extend_from_slice(&2u64.to_le_bytes());
+        repeated.extend_from_slice(&bytes[16..]);
+        repeated.extend_from_slice(&bytes[16..]);
+        assert_eq!(read(&repeated), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn versions_dispatch() {
+        fn from_v0(input: &mut dyn Read) -> io::Result<Dictionary<String, u64>> {
+            let mut rest = Vec::new();
+            input.read_to_end(&mut rest)?;
+            Ok(Dictionary::from([(String::from_utf8(rest).unwrap(), 0)]))
+        }
+        let table = Dictionary::from([(String::from("key"), 7u64)]);
+        let mut bytes = Vec::new();
+        table.write_to(&mut bytes).unwrap();
+        let registry = MigrationRegistry::new().register(0, from_v0);
+        assert_eq!(
+            Dictionary::read_from_any_version(bytes.as_slice(), &registry).unwrap(),
+            table
+        );
+        let mut old = bytes[..8].to_vec();
+        old[7] = 0;
+        old.extend_from_slice(b"legacy");
+        assert_eq!(
+            Dictionary::read_from_any_version(old.as_slice(), &registry).unwrap(),
+            Dictionary::from([(String::from("legacy"), 0)])
+        );
+        let unknown = Dictionary::<String, u64>::read_from(old.as_slice()).unwrap_err();
+        assert_eq!(unknown.kind(), io::ErrorKind::InvalidData);
+        old[7] = 2;
+        assert!(Dictionary::read_from_any_version(old.as_slice(), &registry).is_err());
+    }
+
+    #[test]
+    fn truncated_frames() {
+        let dict = Dictionary::from([(b"key".to_vec(), b"value".to_vec())]);
+        let bytes = dict.to_length_prefixed_bytes();
+        assert_eq!(
+            Dictionary::from_length_prefixed_bytes(&bytes[..bytes.len() - 1]),
+            Err(FrameError::Truncated { offset: 7 })
+        );
+        // a key with no value frame after it
+        assert_eq!(
+            Dictionary::from_length_prefixed_bytes(&bytes[..7]),
+            Err(FrameError::Truncated { offset: 7 })
+        );
+        assert_eq!(
+            Dictionary::from_length_prefixed_bytes(&bytes[..2]),
+            Err(FrameError::Truncated { offset: 0 })
+        );
+        // an offset near usize::MAX is truncated, not an overflow
+        let offset = usize::MAX - 2;
+        assert_eq!(
+            read_frame(&bytes, &mut offset.clone()),
+            Err(FrameError::Truncated { offset })
+        );
+    }
+
+    #[test]
+    fn repeated_frames() {
+        let dict = Dictionary::from([(b"key".to_vec(), b"value".to_vec())]);
+        let mut bytes = dict.to_length_prefixed_bytes();
+        let first = bytes.len();
+        bytes.extend_from_slice(&dict.to_length_prefixed_bytes());
+        assert_eq!(
+            Dictionary::from_length_prefixed_bytes(&bytes),
+            Err(FrameError::RepeatedKey { offset: first })
+        );
+    }
+}
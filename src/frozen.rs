@@ -0,0 +1,131 @@
+use crate::dict::{DictIntoIter, DictIter, Dictionary};
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+/// An immutable Dictionary that can be hashed, created with `Dictionary::freeze`
+/// every read method of Dictionary is available through Deref, nothing can mutate it.
+/// Two frozen dictionaries are equal and hash the same when they hold the same
+/// pairs in the same order, so they can be used as keys in other maps,
+/// like a frozendict or a tuple of items in python.
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+/// use std::collections::HashSet;
+///
+/// let tags = Dictionary::from([("env", "prod"), ("region", "eu")]).freeze();
+/// let mut seen = HashSet::new();
+/// seen.insert(tags.clone());
+/// assert!(seen.contains(&Dictionary::from([("env", "prod"), ("region", "eu")]).freeze()));
+/// assert_eq!(tags.get("env"), Some("prod"));
+/// ```
+#[derive(Clone)]
+pub struct FrozenDictionary<K: Hash + Eq, V> {
+    dict: Dictionary<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
+    /// make the dictionary immutable and hashable
+    pub fn freeze(self) -> FrozenDictionary<K, V> {
+        let mut dict = self;
+        dict.shrink_to_fit();
+        FrozenDictionary { dict }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> FrozenDictionary<K, V> {
+    /// a mutable copy of the dictionary
+    pub fn thaw(&self) -> Dictionary<K, V> {
+        self.dict.clone()
+    }
+
+    /// the dictionary, mutable again
+    pub fn into_dictionary(self) -> Dictionary<K, V> {
+        self.dict
+    }
+}
+
+impl<K: Hash + Eq, V> Deref for FrozenDictionary<K, V> {
+    type Target = Dictionary<K, V>;
+    fn deref(&self) -> &Dictionary<K, V> {
+        &self.dict
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: PartialEq> PartialEq for FrozenDictionary<K, V> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.dict == rhs.dict
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Eq> Eq for FrozenDictionary<K, V> {}
+
+impl<K: Hash + Eq + Clone, V: Hash + Clone> Hash for FrozenDictionary<K, V> {
+    /// hashes the pairs in order, consistent with Eq
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dict.hash(state);
+    }
+}
+
+impl<K: Hash + Eq + Debug, V: Debug> Debug for FrozenDictionary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.dict, f)
+    }
+}
+
+impl<K: Hash + Eq + Display, V: Display> Display for FrozenDictionary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.dict, f)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> From<Dictionary<K, V>> for FrozenDictionary<K, V> {
+    fn from(dict: Dictionary<K, V>) -> FrozenDictionary<K, V> {
+        dict.freeze()
+    }
+}
+
+impl<K: Hash + Eq, V> IntoIterator for FrozenDictionary<K, V> {
+    type Item = (K, V);
+    type IntoIter = DictIntoIter<K, V>;
+    fn into_iter(self) -> DictIntoIter<K, V> {
+        self.dict.into_iter()
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V: Clone> IntoIterator for &'a FrozenDictionary<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = DictIter<'a, K, V>;
+    fn into_iter(self) -> DictIter<'a, K, V> {
+        self.dict.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn frozen_as_key() {
+        let mut routes: HashMap<FrozenDictionary<&str, i32>, &str> = HashMap::new();
+        routes.insert(Dictionary::from([("a", 1), ("b", 2)]).freeze(), "first");
+        routes.insert(Dictionary::from([("b", 2), ("a", 1)]).freeze(), "second");
+        // order is part of the identity
+        assert_eq!(routes.len(), 2);
+        let key = Dictionary::from([("a", 1), ("b", 2)]).freeze();
+        assert_eq!(routes.get(&key), Some(&"first"));
+    }
+
+    #[test]
+    fn read_through_and_thaw() {
+        let frozen = Dictionary::from([(1, 'x'), (2, 'y')]).freeze();
+        assert_eq!(frozen.len(), 2);
+        assert_eq!(frozen.get_index(1), Some('y'));
+        assert_eq!(frozen.index_of(&1), Some(0));
+        let mut thawed = frozen.thaw();
+        thawed.push_back(3, 'z');
+        assert_eq!(frozen.len(), 2);
+        assert_eq!(thawed.len(), 3);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::hash::Hash;
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// group the entries and fold each group into one value in a single pass
+    /// `group` picks the group of each entry and every group starts from a clone of `init`,
+    /// groups are ordered by the position of their first entry
+    /// like `df.groupby(...).agg(...)`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let sales = Dictionary::from([("apple", 3), ("avocado", 5), ("banana", 2), ("apricot", 1)]);
+    /// let by_letter = sales.aggregate(|k, _| k.chars().next().unwrap(), 0, |total, _, v| *total += v);
+    /// assert_eq!(by_letter.keys(), &vec!['a', 'b']);
+    /// assert_eq!(by_letter.values(), &vec![9, 2]);
+    /// ```
+    pub fn aggregate<G, B, FG, FF>(&self, group: FG, init: B, fold: FF) -> Dictionary<G, B>
+    where
+        G: Hash + Eq + Clone,
+        B: Clone,
+        FG: Fn(&K, &V) -> G,
+        FF: Fn(&mut B, &K, &V),
+    {
+        let mut groups = Dictionary::new();
+        for (key, value) in self.iter() {
+            let g = group(key, value);
+            if !groups.contains_key(&g) {
+                groups.push_back(g.clone(), init.clone());
+            }
+            if let Some(acc) = groups.get_mut(g) {
+                fold(acc, key, value);
+            }
+        }
+        groups
+    }
+
+    /// the reverse mapping, from each value to its key
+    /// values are ordered by their first occurrence. When a value occurs more than
+    /// once the last key wins, like `{v: k for k, v in d.items()}` in python,
+    /// use invert_grouped to keep every key
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let codes = Dictionary::from([("ok", 200), ("created", 201), ("success", 200)]);
+    /// let names = codes.invert();
+    /// assert_eq!(names.keys(), &vec![200, 201]);
+    /// assert_eq!(names.get(200), Some("success"));
+    ///
+    /// let all = codes.invert_grouped();
+    /// assert_eq!(all.get(200), Some(vec!["ok", "success"]));
+    /// ```
+    pub fn invert(&self) -> Dictionary<V, K>
+    where
+        V: Hash + Eq,
+    {
+        let mut inverted = Dictionary::with_capacity(self.len());
+        for (key, value) in self.iter() {
+            inverted.push_back(value.clone(), key.clone());
+        }
+        inverted
+    }
+
+    /// the reverse mapping, from each value to every key that has it
+    /// values are ordered by their first occurrence and keys keep their order
+    pub fn invert_grouped(&self) -> Dictionary<V, Vec<K>>
+    where
+        V: Hash + Eq,
+    {
+        let mut inverted: Dictionary<V, Vec<K>> = Dictionary::new();
+        for (key, value) in self.iter() {
+            match inverted.get_mut(value.clone()) {
+                Some(keys) => keys.push(key.clone()),
+                None => {
+                    inverted.push_back(value.clone(), vec![key.clone()]);
+                }
+            }
+        }
+        inverted
+    }
+
+    /// the keys bucketed by their value, the same as invert_grouped
+    /// values are ordered by their first occurrence and keys keep their order
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let status = Dictionary::from([("web", "up"), ("db", "down"), ("cache", "up")]);
+    /// let groups = status.group_by_values();
+    /// assert_eq!(groups.keys(), &vec!["up", "down"]);
+    /// assert_eq!(groups.get("up"), Some(vec!["web", "cache"]));
+    /// ```
+    pub fn group_by_values(&self) -> Dictionary<V, Vec<K>>
+    where
+        V: Hash + Eq,
+    {
+        self.invert_grouped()
+    }
+
+    /// bucket the entries by the group `group` picks for each of them
+    /// groups are ordered by the position of their first entry and every bucket keeps
+    /// the order of its entries, like `df.groupby(...)` without the aggregation
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let scores = Dictionary::from([("ann", 91), ("bob", 78), ("cat", 85), ("dan", 97)]);
+    /// let grades = scores.group_by(|_, score| if *score >= 90 { 'A' } else { 'B' });
+    /// assert_eq!(grades.keys(), &vec!['A', 'B']);
+    /// assert_eq!(grades.get('A').unwrap().keys(), &vec!["ann", "dan"]);
+    /// assert_eq!(grades.get('B').unwrap().get("cat"), Some(85));
+    /// ```
+    pub fn group_by<G, F>(&self, group: F) -> Dictionary<G, Dictionary<K, V>>
+    where
+        G: Hash + Eq + Clone,
+        F: Fn(&K, &V) -> G,
+    {
+        self.aggregate(group, Dictionary::new(), |bucket, key, value| {
+            bucket.push_back(key.clone(), value.clone());
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_counts_and_sums() {
+        let dict: Dictionary<i32, i32> = (1..=10).map(|i| (i, i * i)).collect();
+        let parity = dict.aggregate(
+            |k, _| if k % 2 == 0 { "even" } else { "odd" },
+            (0, 0),
+            |(count, sum), _, v| {
+                *count += 1;
+                *sum += v;
+            },
+        );
+        assert_eq!(parity.keys(), &vec!["odd", "even"]);
+        assert_eq!(parity.get("odd"), Some((5, 165)));
+        assert_eq!(parity.get("even"), Some((5, 220)));
+        let empty = Dictionary::<i32, i32>::new().aggregate(|k, _| *k, 0, |_, _, _| {});
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn invert_unique_values() {
+        let dict: Dictionary<char, u32> = ('a'..='e').zip(1..).collect();
+        let inverted = dict.invert();
+        assert_eq!(inverted.keys(), &vec![1, 2, 3, 4, 5]);
+        assert_eq!(inverted.get(3), Some('c'));
+        assert_eq!(inverted.invert(), dict);
+    }
+
+    #[test]
+    fn invert_grouped_keeps_every_key() {
+        let dict = Dictionary::from([("x", 'b'), ("y", 'a'), ("z", 'b'), ("w", 'b')]);
+        let grouped = dict.invert_grouped();
+        assert_eq!(grouped.keys(), &vec!['b', 'a']);
+        assert_eq!(grouped.get('b'), Some(vec!["x", "z", "w"]));
+        assert_eq!(dict.invert().get('b'), Some("w"));
+        assert!(Dictionary::<u8, u8>::new().invert_grouped().is_empty());
+    }
+
+    #[test]
+    fn group_by_keeps_order_within_groups() {
+        let dict: Dictionary<u32, u32> = (1..=9).map(|i| (i, i % 3)).collect();
+        let by_rest = dict.group_by(|_, v| *v);
+        assert_eq!(by_rest.keys(), &vec![1, 2, 0]);
+        assert_eq!(by_rest.get(0).unwrap().keys(), &vec![3, 6, 9]);
+        assert_eq!(by_rest.get(1).unwrap().values(), &vec![1, 1, 1]);
+        assert_eq!(dict.group_by_values().get(2), Some(vec![2, 5, 8]));
+        let total: usize = by_rest.values().iter().map(|bucket| bucket.len()).sum();
+        assert_eq!(total, dict.len());
+        assert!(Dictionary::<u8, u8>::new().group_by(|k, _| *k).is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+/// How a Dictionary grows its keys, values and index when it runs out of room,
+/// set with `DictionaryBuilder::growth`
+/// every push that needs room and every `reserve` and `try_reserve` asks the
+/// strategy for the new capacity, and all three containers are grown to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrowthStrategy {
+    /// jump to the next power of two, cheap amortized pushes at the cost of up to
+    /// twice the memory that is needed
+    #[default]
+    Doubling,
+    /// grow to exactly the capacity that is needed, the least memory but every
+    /// push past the capacity reallocates, best with reserve up front
+    Exact,
+    /// grow in steps of this many entries, a steady append workload reallocates
+    /// once per chunk and wastes less than a chunk. Chunked(0) grows like Exact
+    Chunked(usize),
+}
+
+impl GrowthStrategy {
+    /// the capacity to grow to from capacity when at least required entries
+    /// have to fit, never less than required
+    /// # Example
+    /// ```
+    /// use rust_dict::growth::GrowthStrategy;
+    ///
+    /// assert_eq!(GrowthStrategy::Doubling.next_capacity(8, 9), 16);
+    /// assert_eq!(GrowthStrategy::Doubling.next_capacity(8, 40), 40);
+    /// assert_eq!(GrowthStrategy::Exact.next_capacity(8, 9), 9);
+    /// assert_eq!(GrowthStrategy::Chunked(100).next_capacity(100, 101), 200);
+    /// ```
+    pub fn next_capacity(&self, capacity: usize, required: usize) -> usize {
+        match *self {
+            GrowthStrategy::Doubling => {
+                let doubled = capacity
+                    .saturating_add(1)
+                    .max(2)
+                    .checked_next_power_of_two()
+                    .unwrap_or(usize::MAX);
+                doubled.max(required)
+            }
+            GrowthStrategy::Exact | GrowthStrategy::Chunked(0) => required,
+            GrowthStrategy::Chunked(chunk) => required.div_ceil(chunk).saturating_mul(chunk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::Dictionary;
+
+    #[test]
+    fn next_capacities() {
+        let doubling: Vec<usize> = [0, 1, 2, 3, 4, 7, 8]
+            .into_iter()
+            .map(|c| GrowthStrategy::Doubling.next_capacity(c, c + 1))
+            .collect();
+        assert_eq!(doubling, vec![2, 2, 4, 4, 8, 8, 16]);
+        assert_eq!(
+            GrowthStrategy::Doubling.next_capacity(usize::MAX - 1, usize::MAX),
+            usize::MAX
+        );
+        assert_eq!(GrowthStrategy::Exact.next_capacity(0, 1), 1);
+        assert_eq!(GrowthStrategy::Chunked(0).next_capacity(3, 4), 4);
+        assert_eq!(GrowthStrategy::Chunked(16).next_capacity(0, 1), 16);
+        assert_eq!(GrowthStrategy::Chunked(16).next_capacity(16, 33), 48);
+    }
+
+    #[test]
+    fn containers_follow_the_strategy() {
+        let mut exact = Dictionary::<u32, u32>::builder()
+            .growth(GrowthStrategy::Exact)
+            .build();
+        let mut chunked = Dictionary::<u32, u32>::builder()
+            .growth(GrowthStrategy::Chunked(10))
+            .build();
+        for i in 0..25 {
+            exact.push_back(i, i);
+            chunked.push_back(i, i);
+            assert_eq!(exact.capacity(), exact.len());
+        }
+        assert_eq!(chunked.capacity(), 30);
+        assert!(chunked.keys().capacity() >= 30 && chunked.values().capacity() >= 30);
+        chunked.reserve(1);
+        assert_eq!(chunked.capacity(), 40);
+        exact.try_reserve(5).unwrap();
+        assert_eq!(exact.capacity(), 30);
+        assert_eq!(exact.clone().growth_strategy(), GrowthStrategy::Exact);
+        assert_eq!(
+            chunked.split_off(20).growth_strategy(),
+            GrowthStrategy::Chunked(10)
+        );
+        let mut doubling: Dictionary<u32, u32> = Dictionary::new();
+        doubling.reserve(3);
+        doubling.reserve(1);
+        assert_eq!(doubling.capacity(), 4);
+        doubling.extend((0..5).map(|i| (i, i)));
+        assert_eq!(doubling.capacity(), 8);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+// below this capacity unused space is not worth reporting
+const SHRINK_MIN_CAPACITY: usize = 16;
+
+/// A maintenance action recommended by `Dictionary::health`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Maintenance {
+    /// most of the reserved capacity is unused, call `shrink_to_fit`
+    Shrink,
+    /// the key index does not match the key order, call `rebuild_index`
+    RebuildIndex,
+}
+
+impl Display for Maintenance {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Maintenance::Shrink => write!(f, "shrink"),
+            Maintenance::RebuildIndex => write!(f, "rebuild_index"),
+        }
+    }
+}
+
+/// A maintenance report for a long lived Dictionary
+/// returned by `Dictionary::health`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictHealth {
+    /// number of key value pairs
+    pub len: usize,
+    /// number of pairs the dictionary can hold before resizing
+    pub capacity: usize,
+    /// capacity that is reserved but not used
+    pub wasted_capacity: usize,
+    /// entries in the key index over the buckets it has allocated, 0.0 when nothing is allocated
+    pub load_factor: f64,
+    /// false if the key index has drifted from the key order
+    pub index_consistent: bool,
+    /// actions to take, empty when the dictionary is healthy
+    pub recommended: Vec<Maintenance>,
+}
+
+impl DictHealth {
+    /// true if there is nothing to do
+    pub fn is_healthy(&self) -> bool {
+        self.recommended.is_empty()
+    }
+}
+
+impl Display for DictHealth {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "len: {}", self.len)?;
+        writeln!(f, "capacity: {}", self.capacity)?;
+        writeln!(f, "wasted capacity: {}", self.wasted_capacity)?;
+        writeln!(f, "load factor: {:.2}", self.load_factor)?;
+        writeln!(f, "index consistent: {}", self.index_consistent)?;
+        if self.recommended.is_empty() {
+            write!(f, "recommended: -")
+        } else {
+            let actions: Vec<String> = self.recommended.iter().map(|a| a.to_string()).collect();
+            write!(f, "recommended: {}", actions.join(", "))
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// Check the dictionary for wasted space and a drifted index
+    /// meant to be polled by long lived services to automate maintenance
+    /// this is O(n), every key is checked against the index
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::health::Maintenance;
+    ///
+    /// let mut dict: Dictionary<i32, i32> = (0..1000).map(|i| (i, i)).collect();
+    /// dict.truncate(10);
+    /// let health = dict.health();
+    /// assert_eq!(health.recommended, vec![Maintenance::Shrink]);
+    /// dict.shrink_to_fit();
+    /// assert!(dict.health().is_healthy());
+    /// ```
+    pub fn health(&self) -> DictHealth {
+        let len = self.len();
+        let capacity = self.capacity();
+        let wasted_capacity = capacity.saturating_sub(len);
+        let (entries, buckets) = self.index_load();
+        let load_factor = if buckets == 0 {
+            0.0
+        } else {
+            entries as f64 / buckets as f64
+        };
+        let index_consistent = self.index_is_consistent();
+
+        let mut recommended = Vec::new();
+        if !index_consistent {
+            recommended.push(Maintenance::RebuildIndex);
+        }
+        if capacity > SHRINK_MIN_CAPACITY && wasted_capacity > len.saturating_mul(3) {
+            recommended.push(Maintenance::Shrink);
+        }
+
+        DictHealth {
+            len,
+            capacity,
+            wasted_capacity,
+            load_factor,
+            index_consistent,
+            recommended,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healthy_dict() {
+        let dict = Dictionary::from([(1, 1), (2, 2), (3, 3)]);
+        let health = dict.health();
+        assert!(health.is_healthy());
+        assert!(health.index_consistent);
+        assert!(health.load_factor > 0.0 && health.load_factor <= 1.0);
+        assert!(health.to_string().ends_with("recommended: -"));
+        assert!(Dictionary::<i32, i32>::new().health().is_healthy());
+    }
+
+    #[test]
+    fn rebuild_index_repairs() {
+        let mut dict = Dictionary::from([(1, 1), (2, 2)]);
+        dict.push_front(3, 3);
+        // point two keys at the same position, as a bug in the index upkeep would
+        *dict.key_map_mut().get_mut(&2).unwrap() = 1;
+        let health = dict.health();
+        assert!(!health.index_consistent);
+        assert_eq!(health.recommended, vec![Maintenance::RebuildIndex]);
+        dict.rebuild_index();
+        assert!(dict.health().is_healthy());
+        assert_eq!(dict.get(3), Some(3));
+        assert_eq!(dict.index_of(&2), Some(2));
+    }
+}
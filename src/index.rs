@@ -0,0 +1,209 @@
+use std::collections::{BTreeMap, HashMap, TryReserveError};
+use std::hash::{BuildHasher, Hash};
+
+/// The map from each key to its position that a Dictionary looks keys up in
+/// implemented for HashMap and BTreeMap, the Dictionary only ever stores positions
+pub trait IndexBackend<K> {
+    fn get(&self, key: &K) -> Option<&usize>;
+    fn get_mut(&mut self, key: &K) -> Option<&mut usize>;
+    /// add or replace the position of a key, returning the old position
+    fn insert(&mut self, key: K, position: usize) -> Option<usize>;
+    fn remove(&mut self, key: &K) -> Option<usize>;
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    /// number of keys the index can hold without allocating
+    fn capacity(&self) -> usize;
+    fn reserve(&mut self, additional: usize);
+    /// reserve like `reserve`, returning the allocation failure instead of aborting
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>;
+    fn shrink_to_fit(&mut self);
+    /// release capacity down to at least min_capacity entries
+    fn shrink_to(&mut self, min_capacity: usize);
+    /// change every stored position in place, in no particular order
+    fn for_each_position<F: FnMut(&mut usize)>(&mut self, f: F);
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Creates the index for a Dictionary, the third type parameter of `Dictionary<K, V, S>`
+/// every BuildHasher builds a HashMap index, BTreeIndex builds a BTreeMap index
+pub trait BuildIndex<K> {
+    type Index: IndexBackend<K>;
+    fn build_index(&self, capacity: usize) -> Self::Index;
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Clone> BuildIndex<K> for S {
+    type Index = HashMap<K, usize, S>;
+    fn build_index(&self, capacity: usize) -> HashMap<K, usize, S> {
+        HashMap::with_capacity_and_hasher(capacity, self.clone())
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher> IndexBackend<K> for HashMap<K, usize, S> {
+    #[inline]
+    fn get(&self, key: &K) -> Option<&usize> {
+        HashMap::get(self, key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut usize> {
+        HashMap::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, position: usize) -> Option<usize> {
+        HashMap::insert(self, key, position)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: &K) -> Option<usize> {
+        HashMap::remove(self, key)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        HashMap::capacity(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        HashMap::reserve(self, additional)
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        HashMap::try_reserve(self, additional)
+    }
+
+    fn shrink_to_fit(&mut self) {
+        HashMap::shrink_to_fit(self)
+    }
+
+    fn shrink_to(&mut self, min_capacity: usize) {
+        HashMap::shrink_to(self, min_capacity)
+    }
+
+    fn for_each_position<F: FnMut(&mut usize)>(&mut self, f: F) {
+        self.values_mut().for_each(f)
+    }
+}
+
+/// Index a Dictionary with a BTreeMap instead of a HashMap
+/// keys only need Ord, nothing is hashed, lookups are O(log n) and the memory
+/// used by the index does not depend on a random seed
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+/// use rust_dict::index::BTreeIndex;
+///
+/// let mut dict: Dictionary<&str, i32, BTreeIndex> = Dictionary::with_index(BTreeIndex);
+/// dict.push_back("b", 2);
+/// dict.push_back("a", 1);
+/// assert_eq!(dict.keys(), &vec!["b", "a"]);
+/// assert_eq!(dict.get("a"), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BTreeIndex;
+
+impl<K: Ord> BuildIndex<K> for BTreeIndex {
+    type Index = BTreeMap<K, usize>;
+    fn build_index(&self, _capacity: usize) -> BTreeMap<K, usize> {
+        BTreeMap::new()
+    }
+}
+
+impl<K: Ord> IndexBackend<K> for BTreeMap<K, usize> {
+    #[inline]
+    fn get(&self, key: &K) -> Option<&usize> {
+        BTreeMap::get(self, key)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut usize> {
+        BTreeMap::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert(&mut self, key: K, position: usize) -> Option<usize> {
+        BTreeMap::insert(self, key, position)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: &K) -> Option<usize> {
+        BTreeMap::remove(self, key)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    // a BTreeMap allocates per node, it has no spare capacity to report
+    fn capacity(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn reserve(&mut self, _additional: usize) {}
+
+    fn try_reserve(&mut self, _additional: usize) -> Result<(), TryReserveError> {
+        Ok(())
+    }
+
+    fn shrink_to_fit(&mut self) {}
+
+    fn shrink_to(&mut self, _min_capacity: usize) {}
+
+    fn for_each_position<F: FnMut(&mut usize)>(&mut self, f: F) {
+        self.values_mut().for_each(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::Dictionary;
+
+    // Ord but not Hash, only usable with the BTreeMap index
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    struct Version(u32, u32);
+
+    #[test]
+    fn btree_index() {
+        let mut dict: Dictionary<Version, &str, BTreeIndex> = Dictionary::with_index(BTreeIndex);
+        dict.push_back(Version(1, 2), "b");
+        dict.push_back(Version(1, 0), "a");
+        dict.push_front(Version(2, 0), "c");
+        assert_eq!(dict.get(Version(1, 0)), Some("a"));
+        assert_eq!(dict.get_index(0), Some("c"));
+        assert_eq!(dict.remove(Version(2, 0)), Some("c"));
+        assert_eq!(dict.get(Version(1, 2)), Some("b"));
+        assert_eq!(dict.keys(), &vec![Version(1, 2), Version(1, 0)]);
+        assert!(dict.index_is_consistent());
+    }
+
+    #[test]
+    fn btree_index_sorting() {
+        let mut dict: Dictionary<i32, i32, BTreeIndex> = Dictionary::with_index(BTreeIndex);
+        dict.extend([(3, 30), (1, 10), (2, 20)]);
+        dict.sort_by_keys();
+        assert_eq!(dict.keys(), &vec![1, 2, 3]);
+        assert_eq!(dict.get(3), Some(30));
+        let doubled: Dictionary<i32, i32, BTreeIndex> =
+            dict.iter().map(|(k, v)| (*k, v * 2)).collect();
+        assert_eq!(doubled.get(2), Some(40));
+    }
+}
@@ -0,0 +1,560 @@
+use crate::dict::Dictionary;
+use crate::value::DictValue;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// objects and arrays nested deeper than this are rejected instead of overflowing the stack
+pub const MAX_DEPTH: usize = 128;
+
+/// Errors raised while parsing JSON
+/// the offsets are byte offsets into the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonError {
+    /// the input ended in the middle of a value
+    UnexpectedEnd,
+    /// a character that can not start or continue the value being parsed
+    UnexpectedCharacter { offset: usize },
+    /// a backslash escape that JSON does not define, or a bad \u escape
+    InvalidEscape { offset: usize },
+    /// a number that is malformed or out of range
+    InvalidNumber { offset: usize },
+    /// the document is valid JSON but not an object
+    NotAnObject,
+    /// objects and arrays are nested more than MAX_DEPTH deep
+    TooDeep { offset: usize },
+    /// there was something other than whitespace after the document
+    TrailingCharacters { offset: usize },
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            JsonError::UnexpectedCharacter { offset } => {
+                write!(f, "offset {}: unexpected character", offset)
+            }
+            JsonError::InvalidEscape { offset } => write!(f, "offset {}: invalid escape", offset),
+            JsonError::InvalidNumber { offset } => write!(f, "offset {}: invalid number", offset),
+            JsonError::NotAnObject => write!(f, "the document is not a JSON object"),
+            JsonError::TooDeep { offset } => {
+                write!(f, "offset {}: nested more than {} deep", offset, MAX_DEPTH)
+            }
+            JsonError::TrailingCharacters { offset } => {
+                write!(
+                    f,
+                    "offset {}: unexpected characters after the document",
+                    offset
+                )
+            }
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+impl Dictionary<String, DictValue> {
+    /// Parse a JSON object into a Dictionary, keys keep the order they appear in
+    /// nested objects become `DictValue::Dict` and keep their order too.
+    /// Integers that fit an i64 become `DictValue::Int`, other numbers become `DictValue::Float`.
+    /// When a key is repeated the last value wins but the key keeps its first position
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::value::DictValue;
+    ///
+    /// let doc = Dictionary::from_json_str(r#"{"z": 1, "a": {"y": true, "b": null}}"#).unwrap();
+    /// assert_eq!(doc.keys(), &vec![String::from("z"), String::from("a")]);
+    /// assert_eq!(doc.get("z".into()), Some(DictValue::Int(1)));
+    /// assert_eq!(doc.to_json_string(), r#"{"z":1,"a":{"y":true,"b":null}}"#);
+    /// ```
+    pub fn from_json_str(input: &str) -> Result<Dictionary<String, DictValue>, JsonError> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            pos: 0,
+            depth: 0,
+        };
+        parser.skip_whitespace();
+        if parser.peek() != Some(b'{') {
+            // still report malformed input as such
+            parser.parse_value()?;
+            return Err(JsonError::NotAnObject);
+        }
+        let dict = parser.parse_object()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.input.len() {
+            return Err(JsonError::TrailingCharacters { offset: parser.pos });
+        }
+        Ok(dict)
+    }
+
+    /// the dictionary as compact JSON, entries in order
+    /// floats that are not finite have no JSON form and are written as null
+    pub fn to_json_string(&self) -> String {
+        let mut output = String::new();
+        write_object(&mut output, self, None, 0);
+        output
+    }
+
+    /// the dictionary as JSON indented by two spaces, entries in order
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::value::DictValue;
+    ///
+    /// let mut doc = Dictionary::new();
+    /// doc.push_back(String::from("name"), DictValue::from("app"));
+    /// doc.push_back(String::from("ports"), DictValue::from(vec![DictValue::Int(80)]));
+    /// assert_eq!(
+    ///     doc.to_json_string_pretty(),
+    ///     "{\n  \"name\": \"app\",\n  \"ports\": [\n    80\n  ]\n}"
+    /// );
+    /// ```
+    pub fn to_json_string_pretty(&self) -> String {
+        let mut output = String::new();
+        write_object(&mut output, self, Some(2), 0);
+        output
+    }
+
+    /// read a file holding a JSON object
+    /// a file that is not valid JSON fails with `io::ErrorKind::InvalidData`
+    /// wrapping the JsonError
+    pub fn load_json<P: AsRef<Path>>(path: P) -> io::Result<Dictionary<String, DictValue>> {
+        let input = fs::read_to_string(path)?;
+        Dictionary::from_json_str(&input).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// write the dictionary to a file as pretty JSON, replacing what was there
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut output = self.to_json_string_pretty();
+        output.push('\n');
+        fs::write(path, output)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(b) if b == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(JsonError::UnexpectedCharacter { offset: self.pos }),
+            None => Err(JsonError::UnexpectedEnd),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<DictValue, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(JsonError::UnexpectedEnd),
+            Some(b'{') => self.parse_object().map(DictValue::from),
+            Some(b'[') => self.parse_list(),
+            Some(b'"') => self.parse_string().map(DictValue::String),
+            Some(b't') => self.parse_literal("true", DictValue::Bool(true)),
+            Some(b'f') => self.parse_literal("false", DictValue::Bool(false)),
+            Some(b'n') => self.parse_literal("null", DictValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(JsonError::UnexpectedCharacter { offset: self.pos }),
+        }
+    }
+
+    fn parse_literal(&mut self, word: &str, value: DictValue) -> Result<DictValue, JsonError> {
+        for &byte in word.as_bytes() {
+            self.expect(byte)?;
+        }
+        Ok(value)
+    }
+
+    fn enter(&mut self) -> Result<(), JsonError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(JsonError::TooDeep { offset: self.pos });
+        }
+        Ok(())
+    }
+
+    fn parse_object(&mut self) -> Result<Dictionary<String, DictValue>, JsonError> {
+        self.enter()?;
+        self.expect(b'{')?;
+        let mut dict = Dictionary::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(dict);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            let value = self.parse_value()?;
+            dict.push_back(key, value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(dict);
+                }
+                Some(_) => return Err(JsonError::UnexpectedCharacter { offset: self.pos }),
+                None => return Err(JsonError::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<DictValue, JsonError> {
+        self.enter()?;
+        self.expect(b'[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            self.depth -= 1;
+            return Ok(DictValue::List(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    self.depth -= 1;
+                    return Ok(DictValue::List(items));
+                }
+                Some(_) => return Err(JsonError::UnexpectedCharacter { offset: self.pos }),
+                None => return Err(JsonError::UnexpectedEnd),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"')?;
+        let mut value = String::new();
+        loop {
+            // copy the run up to the next quote or escape in one go
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'"' || byte == b'\\' || byte < 0x20 {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // the input is a str and the run stops at an ascii byte, so it is valid utf-8
+            value.push_str(std::str::from_utf8(&self.input[start..self.pos]).unwrap());
+            match self.peek() {
+                None => return Err(JsonError::UnexpectedEnd),
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.parse_escape(&mut value)?;
+                }
+                Some(_) => return Err(JsonError::UnexpectedCharacter { offset: self.pos }),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, value: &mut String) -> Result<(), JsonError> {
+        let offset = self.pos - 1;
+        let c = match self.peek() {
+            None => return Err(JsonError::UnexpectedEnd),
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.pos += 1;
+                let high = self.parse_hex(offset)?;
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    // a surrogate pair, the low half must follow as another \u escape
+                    if self.input.get(self.pos..self.pos + 2) != Some(b"\\u") {
+                        return Err(JsonError::InvalidEscape { offset });
+                    }
+                    self.pos += 2;
+                    let low = self.parse_hex(offset)?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(JsonError::InvalidEscape { offset });
+                    }
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+                let c = char::from_u32(code).ok_or(JsonError::InvalidEscape { offset })?;
+                value.push(c);
+                return Ok(());
+            }
+            Some(_) => return Err(JsonError::InvalidEscape { offset }),
+        };
+        self.pos += 1;
+        value.push(c);
+        Ok(())
+    }
+
+    fn parse_hex(&mut self, offset: usize) -> Result<u32, JsonError> {
+        let digits = self
+            .input
+            .get(self.pos..self.pos + 4)
+            .ok_or(JsonError::UnexpectedEnd)?;
+        // from_str_radix would also take a sign, as in \u+041
+        if !digits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(JsonError::InvalidEscape { offset });
+        }
+        let code = digits.iter().fold(0, |code, &digit| {
+            code * 16 + (digit as char).to_digit(16).unwrap()
+        });
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<DictValue, JsonError> {
+        let start = self.pos;
+        let mut integer = true;
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        // JSON allows no leading zeros, so a 0 must stand alone before the fraction
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(JsonError::InvalidNumber { offset: start }),
+        }
+        if self.peek() == Some(b'.') {
+            integer = false;
+            self.pos += 1;
+            self.require_digits(start)?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            integer = false;
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.require_digits(start)?;
+        }
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        if integer {
+            if let Ok(n) = text.parse::<i64>() {
+                return Ok(DictValue::Int(n));
+            }
+        }
+        match text.parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(DictValue::Float(n)),
+            _ => Err(JsonError::InvalidNumber { offset: start }),
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn require_digits(&mut self, start: usize) -> Result<(), JsonError> {
+        match self.peek() {
+            Some(b'0'..=b'9') => {
+                self.skip_digits();
+                Ok(())
+            }
+            _ => Err(JsonError::InvalidNumber { offset: start }),
+        }
+    }
+}
+
+// indent is the step for pretty output, None writes everything on one line
+fn write_value(output: &mut String, value: &DictValue, indent: Option<usize>, level: usize) {
+    match value {
+        DictValue::Null => output.push_str("null"),
+        DictValue::Bool(b) => output.push_str(if *b { "true" } else { "false" }),
+        DictValue::Int(n) => write!(output, "{}", n).unwrap(),
+        // Debug keeps the fraction so 1.0 reads back as a Float, not an Int
+        DictValue::Float(n) if n.is_finite() => write!(output, "{:?}", n).unwrap(),
+        DictValue::Float(_) => output.push_str("null"),
+        DictValue::String(s) => write_string(output, s),
+        DictValue::List(items) => {
+            if items.is_empty() {
+                output.push_str("[]");
+                return;
+            }
+            output.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                newline(output, indent, level + 1);
+                write_value(output, item, indent, level + 1);
+            }
+            newline(output, indent, level);
+            output.push(']');
+        }
+        DictValue::Dict(dict) => write_object(output, dict, indent, level),
+    }
+}
+
+fn write_object(
+    output: &mut String,
+    dict: &Dictionary<String, DictValue>,
+    indent: Option<usize>,
+    level: usize,
+) {
+    if dict.is_empty() {
+        output.push_str("{}");
+        return;
+    }
+    output.push('{');
+    for (i, (key, value)) in dict.iter().enumerate() {
+        if i > 0 {
+            output.push(',');
+        }
+        newline(output, indent, level + 1);
+        write_string(output, key);
+        output.push(':');
+        if indent.is_some() {
+            output.push(' ');
+        }
+        write_value(output, value, indent, level + 1);
+    }
+    newline(output, indent, level);
+    output.push('}');
+}
+
+fn newline(output: &mut String, indent: Option<usize>, level: usize) {
+    if let Some(step) = indent {
+        output.push('\n');
+        output.extend(std::iter::repeat_n(' ', step * level));
+    }
+}
+
+fn write_string(output: &mut String, s: &str) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let input = r#"{
+            "name": "café \"quoted\"\n",
+            "count": -12,
+            "ratio": 1.0,
+            "big": 1e400000000000000000000,
+            "emoji": "😀",
+            "nested": {"z": [1, 2.5, [], {}], "a": false},
+            "name": "renamed"
+        }"#;
+        // out of range for an f64
+        let offset = input.find("1e4").unwrap();
+        assert_eq!(
+            Dictionary::from_json_str(input),
+            Err(JsonError::InvalidNumber { offset })
+        );
+
+        let input = input.replace("1e400000000000000000000", "1e3");
+        let doc = Dictionary::from_json_str(&input).unwrap();
+        let keys: Vec<&str> = doc.keys().iter().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            vec!["name", "count", "ratio", "big", "emoji", "nested"]
+        );
+        assert_eq!(doc.get("name".into()), Some(DictValue::from("renamed")));
+        assert_eq!(doc.get("ratio".into()), Some(DictValue::Float(1.0)));
+        assert_eq!(doc.get("big".into()), Some(DictValue::Float(1000.0)));
+        assert_eq!(doc.get("emoji".into()), Some(DictValue::from("😀")));
+
+        let compact = doc.to_json_string();
+        assert!(compact.starts_with(r#"{"name":"renamed","count":-12,"ratio":1.0,"#));
+        assert_eq!(Dictionary::from_json_str(&compact), Ok(doc.clone()));
+        assert_eq!(
+            Dictionary::from_json_str(&doc.to_json_string_pretty()),
+            Ok(doc)
+        );
+    }
+
+    #[test]
+    fn json_errors() {
+        assert_eq!(
+            Dictionary::from_json_str("[1]"),
+            Err(JsonError::NotAnObject)
+        );
+        assert_eq!(
+            Dictionary::from_json_str("[1"),
+            Err(JsonError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Dictionary::from_json_str(r#"{"a": 01}"#),
+            Err(JsonError::UnexpectedCharacter { offset: 7 })
+        );
+        assert_eq!(
+            Dictionary::from_json_str(r#"{"a": "\x"}"#),
+            Err(JsonError::InvalidEscape { offset: 7 })
+        );
+        assert_eq!(
+            Dictionary::from_json_str(r#"{"a": "\u+041"}"#),
+            Err(JsonError::InvalidEscape { offset: 7 })
+        );
+        assert_eq!(
+            Dictionary::from_json_str("{} {}"),
+            Err(JsonError::TrailingCharacters { offset: 3 })
+        );
+        let deep = format!("{{\"a\":{}{}}}", "[".repeat(200), "]".repeat(200));
+        assert!(matches!(
+            Dictionary::from_json_str(&deep),
+            Err(JsonError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn json_files() {
+        let path = std::env::temp_dir().join(format!("rust_dict_json_{}.json", std::process::id()));
+        let mut doc = Dictionary::new();
+        doc.push_back(String::from("b"), DictValue::Int(1));
+        doc.push_back(String::from("a"), DictValue::Null);
+        doc.save_json(&path).unwrap();
+        assert_eq!(Dictionary::load_json(&path).unwrap(), doc);
+        fs::write(&path, "{").unwrap();
+        let error = Dictionary::load_json(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,167 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+/// the most suggestions a KeyError carries
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// How close two keys are, used to suggest keys when a lookup fails
+/// the default is no distance, so any key type can opt in with an empty impl
+/// string keys use the edit distance
+pub trait SuggestKey {
+    fn distance(&self, _other: &Self) -> Option<usize> {
+        None
+    }
+}
+
+impl SuggestKey for String {
+    fn distance(&self, other: &Self) -> Option<usize> {
+        Some(edit_distance(self, other))
+    }
+}
+
+impl SuggestKey for &str {
+    fn distance(&self, other: &Self) -> Option<usize> {
+        Some(edit_distance(self, other))
+    }
+}
+
+macro_rules! no_suggestions {
+    ($($t:ty),*) => {
+        $(impl SuggestKey for $t {})*
+    };
+}
+
+no_suggestions!(bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Error returned when a key is not in the dictionary
+/// carries the missing key and up to MAX_SUGGESTIONS of the closest keys that are,
+/// like the "did you mean" hint on a python KeyError
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyError<K> {
+    pub key: K,
+    /// the closest existing keys, closest first
+    pub suggestions: Vec<K>,
+}
+
+impl<K: Debug> Display for KeyError<K> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "key {:?} not found", self.key)?;
+        if !self.suggestions.is_empty() {
+            let names: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|k| format!("{:?}", k))
+                .collect();
+            write!(f, ", did you mean {}?", names.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<K: Debug> Error for KeyError<K> {}
+
+impl<K: Hash + Eq + Clone + SuggestKey, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// get a value, or a KeyError with suggestions when the key is missing
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("username", 1), ("email", 2)]);
+    /// let err = dict.try_get("usrname").unwrap_err();
+    /// assert_eq!(err.suggestions, vec!["username"]);
+    /// assert_eq!(err.to_string(), "key \"usrname\" not found, did you mean \"username\"?");
+    /// ```
+    pub fn try_get(&self, key: K) -> Result<V, KeyError<K>> {
+        match self.get(key.clone()) {
+            Some(value) => Ok(value),
+            None => Err(self.key_error(key)),
+        }
+    }
+
+    /// get a mutable reference to a value, or a KeyError when the key is missing
+    pub fn try_get_mut(&mut self, key: K) -> Result<&mut V, KeyError<K>> {
+        if !self.contains_key(&key) {
+            return Err(self.key_error(key));
+        }
+        Ok(self.get_mut(key).expect("checked above"))
+    }
+
+    /// remove a key, or a KeyError when it is missing
+    /// # Panics
+    /// panics if the dictionary is sealed
+    pub fn try_remove(&mut self, key: K) -> Result<V, KeyError<K>> {
+        if !self.contains_key(&key) {
+            return Err(self.key_error(key));
+        }
+        Ok(self.remove(key).expect("checked above"))
+    }
+
+    /// build the KeyError for a missing key
+    pub fn key_error(&self, key: K) -> KeyError<K> {
+        let mut close: Vec<(usize, &K)> = self
+            .keys()
+            .iter()
+            .filter_map(|k| key.distance(k).map(|d| (d, k)))
+            .filter(|(d, _)| *d <= 2)
+            .collect();
+        // stable, ties keep dictionary order
+        close.sort_by_key(|(d, _)| *d);
+        KeyError {
+            suggestions: close
+                .into_iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|(_, k)| k.clone())
+                .collect(),
+            key,
+        }
+    }
+}
+
+// levenshtein distance over chars, one row at a time
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn suggestions_closest_first() {
+        let mut dict = Dictionary::<String, i32>::new();
+        for key in ["colour", "color", "collar", "cooler", "dolor", "apple"] {
+            dict.push_back(key.into(), 0);
+        }
+        let err = dict.try_get("colr".into()).unwrap_err();
+        assert_eq!(err.suggestions, vec!["color", "colour", "collar"]);
+        assert_eq!(dict.try_remove("color".into()), Ok(0));
+        *dict.try_get_mut("apple".into()).unwrap() = 5;
+        assert_eq!(dict.try_get("apple".into()), Ok(5));
+
+        let numbers = Dictionary::from([(1, 'a')]);
+        let err = numbers.try_get(2).unwrap_err();
+        assert!(err.suggestions.is_empty());
+        assert_eq!(err.to_string(), "key 2 not found");
+    }
+}
@@ -0,0 +1,296 @@
+use crate::dict::Dictionary;
+use crate::digest::DigestHasher;
+use crate::index::{BuildIndex, IndexBackend};
+use std::hash::Hasher;
+
+/// the length of the `~` and 8 hex digit hash that end a truncated String key
+pub const HASH_SUFFIX_LEN: usize = 9;
+
+/// Keys that can be shortened to fit a length limit, enabled with
+/// `DictionaryBuilder::max_key_len`
+/// the short key has to depend only on the key and the limit so lookups with the
+/// original key find it, and a key that already fits must not change
+pub trait TruncateKey: Sized {
+    /// None if the key fits in max_len
+    fn truncate_key(&self, max_len: usize) -> Option<Self>;
+
+    /// the nth short key to try when the ones before it are held by other keys,
+    /// n = 0 is truncate_key. Past 0 this has to give a key of at most max_len even
+    /// for a key that fits, the default has no alternatives and a collision panics
+    fn truncate_key_nth(&self, max_len: usize, n: usize) -> Option<Self> {
+        if n == 0 {
+            self.truncate_key(max_len)
+        } else {
+            None
+        }
+    }
+}
+
+/// cuts the key to max_len bytes on a char boundary, ending in `~` and the
+/// hex of a 32 bit hash of the whole key so keys with a common prefix stay apart.
+/// The hash has no random seed, the same key truncates the same way in every process
+/// Two keys whose hashes collide are told apart by rehashing the later one with
+/// a counter, see `TruncateKey::truncate_key_nth`
+impl TruncateKey for String {
+    fn truncate_key(&self, max_len: usize) -> Option<String> {
+        self.truncate_key_nth(max_len, 0)
+    }
+
+    fn truncate_key_nth(&self, max_len: usize, n: usize) -> Option<String> {
+        if n == 0 && self.len() <= max_len {
+            return None;
+        }
+        let mut hasher = DigestHasher::default();
+        hasher.write(self.as_bytes());
+        if n > 0 {
+            hasher.write_usize(n);
+        }
+        let mut end = max_len.saturating_sub(HASH_SUFFIX_LEN).min(self.len());
+        while !self.is_char_boundary(end) {
+            end -= 1;
+        }
+        Some(format!("{}~{:08x}", &self[..end], hasher.finish() as u32))
+    }
+}
+
+// the nth short key for a key of at most the length, None if the key is kept as is
+pub(crate) type Truncate<K> = fn(&K, usize, usize) -> Option<K>;
+
+/// The key length limit of a Dictionary and the originals of the keys it shortened
+/// an original is kept until the dictionary is cleared, even if its key is removed,
+/// so its short key is not handed to another key
+#[derive(Debug, Clone)]
+pub(crate) struct KeyLimit<K, I> {
+    max_len: usize,
+    truncate: Truncate<K>,
+    // (original, short) pairs
+    entries: Vec<(K, K)>,
+    // short key to its position in entries
+    by_short: I,
+    // original key to its position in entries
+    by_original: I,
+}
+
+impl<K: Eq + Clone, I: IndexBackend<K>> KeyLimit<K, I> {
+    pub(crate) fn new(max_len: usize, truncate: Truncate<K>, by_short: I, by_original: I) -> Self {
+        KeyLimit {
+            max_len,
+            truncate,
+            entries: Vec::new(),
+            by_short,
+            by_original,
+        }
+    }
+
+    /// the key to store for key, None if it is stored as is
+    /// a key seen before keeps its short key, otherwise the first candidate that no
+    /// other original holds and that is not a key of the dictionary by `taken`
+    /// # Panics
+    /// panics if the candidates run out, which a String key never does
+    pub(crate) fn shorten<F: Fn(&K) -> bool>(&self, key: &K, taken: F) -> Option<K> {
+        if let Some(&i) = self.by_original.get(key) {
+            return Some(self.entries[i].1.clone());
+        }
+        let mut n = 0;
+        let mut candidate = (self.truncate)(key, self.max_len, n);
+        loop {
+            let clash = match &candidate {
+                Some(short) => self.by_short.contains_key(short) || taken(short),
+                None => self.by_short.contains_key(key),
+            };
+            if !clash {
+                return candidate;
+            }
+            n += 1;
+            candidate = Some(
+                (self.truncate)(key, self.max_len, n)
+                    .expect("truncated key collides and TruncateKey has no alternative"),
+            );
+        }
+    }
+
+    /// shorten a key that may be inserted, remembering its original
+    pub(crate) fn shorten_new<F: Fn(&K) -> bool>(&mut self, key: K, taken: F) -> K {
+        match self.shorten(&key, taken) {
+            Some(short) => {
+                self.remember(short.clone(), key);
+                short
+            }
+            None => key,
+        }
+    }
+
+    fn remember(&mut self, short: K, original: K) {
+        if self.by_short.get(&short).is_none() && self.by_original.get(&original).is_none() {
+            self.by_short.insert(short.clone(), self.entries.len());
+            self.by_original
+                .insert(original.clone(), self.entries.len());
+            self.entries.push((original, short));
+        }
+    }
+
+    pub(crate) fn original(&self, short: &K) -> Option<&K> {
+        self.by_short.get(short).map(|&i| &self.entries[i].0)
+    }
+
+    /// the same limit with no originals
+    pub(crate) fn empty_like<J: IndexBackend<K>>(
+        &self,
+        by_short: J,
+        by_original: J,
+    ) -> KeyLimit<K, J> {
+        KeyLimit::new(self.max_len, self.truncate, by_short, by_original)
+    }
+
+    /// remember the originals from, for the keys that has them
+    pub(crate) fn copy_originals<'a, It>(&mut self, from: &KeyLimit<K, I>, keys: It)
+    where
+        K: 'a,
+        It: Iterator<Item = &'a K>,
+    {
+        for key in keys {
+            if let Some(original) = from.original(key) {
+                self.remember(key.clone(), original.clone());
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.by_short.clear();
+        self.by_original.clear();
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// the longest key the dictionary stores, set through `DictionaryBuilder::max_key_len`
+    pub fn max_key_len(&self) -> Option<usize> {
+        self.key_limit().map(|limit| limit.max_len)
+    }
+
+    /// the key as it was inserted, for a key of the dictionary
+    /// a key that was truncated to fit `max_key_len` gives the original, any other
+    /// key gives itself. None if the key is not in the dictionary
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::<String, u32>::builder().max_key_len(16).build();
+    /// dict.push_back("service.http.request_timeout_ms".into(), 5000);
+    /// dict.push_back("retries".into(), 3);
+    /// let short = dict.keys()[0].clone();
+    /// assert_eq!(short.len(), 16);
+    /// assert!(short.starts_with("service"));
+    /// assert_eq!(dict.original_key(&short).unwrap(), "service.http.request_timeout_ms");
+    /// assert_eq!(dict.get("service.http.request_timeout_ms".into()), Some(5000));
+    /// assert_eq!(dict.original_key(&"retries".into()).unwrap(), "retries");
+    /// ```
+    pub fn original_key(&self, key: &K) -> Option<&K> {
+        let i = self.stored_index_of(key)?;
+        let key = &self.keys()[i];
+        Some(
+            self.key_limit()
+                .and_then(|limit| limit.original(key))
+                .unwrap_or(key),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_truncation() {
+        assert_eq!(String::from("short").truncate_key(5), None);
+        let long = String::from("a_rather_long_key_name");
+        let short = long.truncate_key(12).unwrap();
+        assert_eq!(short.len(), 12);
+        assert_eq!(&short[..3], "a_r");
+        assert_eq!(long.truncate_key(12), Some(short.clone()));
+        assert_eq!(short.truncate_key(12), None);
+        let other = String::from("a_rather_long_key_other").truncate_key(12);
+        assert_ne!(other, Some(short));
+        // the prefix is cut on a char boundary
+        let wide = String::from("ééééééé").truncate_key(12).unwrap();
+        assert!(wide.starts_with("é~"));
+        assert_eq!(wide.len(), 11);
+    }
+
+    #[test]
+    fn originals_survive_mutation() {
+        let mut dict = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        let long = |i: u32| format!("metrics.{}.latency_p99", i);
+        for i in 0..4 {
+            dict.push_back(long(i), i);
+        }
+        dict.push_front(long(9), 9);
+        dict.push_back(long(2), 20);
+        assert_eq!(dict.len(), 5);
+        assert!(dict.keys().iter().all(|key| key.len() <= 12));
+        let rest = dict.split_off(3);
+        assert_eq!(rest.original_key(&rest.keys()[1]), Some(&long(3)));
+        assert_eq!(rest.max_key_len(), Some(12));
+        let mut sum = dict.clone() + rest;
+        assert_eq!(sum.original_key(&sum.keys()[4]), Some(&long(3)));
+        assert_eq!(sum.remove(long(9)), Some(9));
+        assert_eq!(sum.original_key(&long(9).truncate_key(12).unwrap()), None);
+        assert_eq!(sum.original_key(&dict.keys()[2]), Some(&long(1)));
+        assert_eq!(Dictionary::<String, u32>::new().max_key_len(), None);
+    }
+
+    #[test]
+    fn lookups_take_the_original_key() {
+        let long = String::from("service.http.request_timeout_ms");
+        let mut dict = Dictionary::<String, u32>::builder().max_key_len(16).build();
+        dict.push_back(String::from("retries"), 3);
+        dict.push_back(long.clone(), 5000);
+        assert!(dict.contains_key(&long));
+        assert_eq!(dict.index_of(&long), Some(1));
+        assert_eq!(dict.get_full(&long), Some((1, &dict.keys()[1], &5000)));
+        assert_eq!(dict.original_key(&dict.keys()[1]), Some(&long));
+        let mut reordered = dict.clone();
+        assert!(reordered.move_to_front(long.clone()));
+        assert!(reordered.eq_ignore_order(&dict));
+        assert!(dict.diff(&reordered).changed.is_empty());
+    }
+
+    #[test]
+    fn colliding_hashes_get_distinct_keys() {
+        // these two share a prefix and a 32 bit hash
+        let (first, second) = (
+            String::from("metrics.host205081.cpu"),
+            String::from("metrics.host851190.cpu"),
+        );
+        assert_eq!(first.truncate_key(12), second.truncate_key(12));
+        let mut dict = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        dict.push_back(first.clone(), 1);
+        dict.push_back(second.clone(), 2);
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(first.clone()), Some(1));
+        assert_eq!(dict.get(second.clone()), Some(2));
+        assert_eq!(dict.original_key(&dict.keys()[1]), Some(&second));
+        assert!(dict.keys().iter().all(|key| key.len() <= 12));
+        assert_eq!(dict.remove(first.clone()), Some(1));
+        assert_eq!(dict.get(second.clone()), Some(2));
+
+        // a key that fits and equals a short key is kept apart from it either way round
+        let short = second.truncate_key(12).unwrap();
+        let mut plain = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        plain.push_back(short.clone(), 1);
+        plain.push_back(second.clone(), 2);
+        plain.push_back(first.clone(), 3);
+        plain.push_back(short.clone(), 4);
+        assert_eq!(plain.len(), 3);
+        assert_eq!(plain.get(short.clone()), Some(4));
+        assert_eq!(plain.get(second.clone()), Some(2));
+        assert_eq!(plain.get(first.clone()), Some(3));
+        let mut long_first = Dictionary::<String, u32>::builder().max_key_len(12).build();
+        long_first.push_back(second.clone(), 1);
+        long_first.push_back(short.clone(), 2);
+        assert_eq!(long_first.get(second), Some(1));
+        assert_eq!(long_first.get(short.clone()), Some(2));
+        assert_eq!(long_first.get(first), None);
+        assert_eq!(long_first.original_key(&long_first.keys()[1]), Some(&short));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::dict::Dictionary;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// A value for a LazyDictionary, either ready or computed on first use
+pub enum Lazy<V> {
+    Value(V),
+    Thunk(Box<dyn FnOnce() -> V>),
+}
+
+/// An ordered dictionary whose values can be computed on demand
+/// a thunk runs the first time its key is read and the result is kept,
+/// so expensive values in a config or registry are only built if something asks for them.
+/// A thunk that panics poisons its key, like `std::sync::LazyLock`
+/// # Example
+/// ```
+/// use rust_dict::lazy::LazyDictionary;
+///
+/// let mut registry = LazyDictionary::<&str, String>::new();
+/// registry.insert_value("name", "app".to_string());
+/// registry.insert_thunk("banner", || "expensive".repeat(2));
+/// assert!(!registry.is_forced(&"banner"));
+/// assert_eq!(registry.get("banner"), Some(&"expensiveexpensive".to_string()));
+/// assert!(registry.is_forced(&"banner"));
+/// assert_eq!(registry.keys(), &vec!["name", "banner"]);
+/// ```
+pub struct LazyDictionary<K: Hash + Eq, V> {
+    // None until the thunk for the key has run
+    values: Dictionary<K, Option<V>>,
+    thunks: HashMap<K, Box<dyn FnOnce() -> V>>,
+    // keys whose thunk panicked, a thunk is used up once it starts so there is
+    // nothing to run again
+    poisoned: HashSet<K>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LazyDictionary<K, V> {
+    pub fn new() -> LazyDictionary<K, V> {
+        LazyDictionary {
+            values: Dictionary::new(),
+            thunks: HashMap::new(),
+            poisoned: HashSet::new(),
+        }
+    }
+
+    /// add or replace an entry, a replaced key keeps its position
+    pub fn insert(&mut self, key: K, value: Lazy<V>) {
+        self.poisoned.remove(&key);
+        match value {
+            Lazy::Value(value) => {
+                self.thunks.remove(&key);
+                self.values.push_back(key, Some(value));
+            }
+            Lazy::Thunk(thunk) => {
+                self.thunks.insert(key.clone(), thunk);
+                self.values.push_back(key, None);
+            }
+        }
+    }
+
+    pub fn insert_value(&mut self, key: K, value: V) {
+        self.insert(key, Lazy::Value(value));
+    }
+
+    pub fn insert_thunk<F: FnOnce() -> V + 'static>(&mut self, key: K, thunk: F) {
+        self.insert(key, Lazy::Thunk(Box::new(thunk)));
+    }
+
+    /// get the value for a key, running its thunk first if it has not run yet
+    /// # Panics
+    /// panics if the thunk for the key panicked on an earlier get, the key stays
+    /// poisoned until it is inserted again or removed
+    pub fn get(&mut self, key: K) -> Option<&V> {
+        self.force(&key);
+        self.values.get_full(&key).and_then(|(_, _, v)| v.as_ref())
+    }
+
+    /// true if the key has a value that is ready, false for pending thunks and missing keys
+    pub fn is_forced(&self, key: &K) -> bool {
+        matches!(self.values.get_full(key), Some((_, _, Some(_))))
+    }
+
+    /// true if the thunk for the key panicked
+    pub fn is_poisoned(&self, key: &K) -> bool {
+        self.poisoned.contains(key)
+    }
+
+    /// run every pending thunk, in order
+    /// # Panics
+    /// panics if a key is poisoned, see get
+    pub fn force_all(&mut self) {
+        for key in self.values.keys_snapshot() {
+            self.force(&key);
+        }
+    }
+
+    /// remove an entry, a pending thunk is returned without running it
+    pub fn remove(&mut self, key: K) -> Option<Lazy<V>> {
+        self.poisoned.remove(&key);
+        let value = self.values.remove(key.clone())?;
+        match value {
+            Some(value) => Some(Lazy::Value(value)),
+            None => self.thunks.remove(&key).map(Lazy::Thunk),
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<K> {
+        self.values.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// force every value and return a plain Dictionary in the same order
+    pub fn into_dictionary(mut self) -> Dictionary<K, V> {
+        self.force_all();
+        self.values
+            .into_iter()
+            .map(|(k, v)| (k, v.expect("forced")))
+            .collect()
+    }
+
+    fn force(&mut self, key: &K) {
+        assert!(
+            !self.poisoned.contains(key),
+            "the thunk for this key panicked"
+        );
+        if let Some(thunk) = self.thunks.remove(key) {
+            // cleared once the thunk returns, so it stays if the thunk unwinds
+            self.poisoned.insert(key.clone());
+            let value = thunk();
+            self.poisoned.remove(key);
+            if let Some(slot) = self.values.get_mut(key.clone()) {
+                *slot = Some(value);
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for LazyDictionary<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn thunk_runs_once() {
+        let calls = Rc::new(Cell::new(0));
+        let mut dict = LazyDictionary::<i32, i32>::new();
+        let counter = calls.clone();
+        dict.insert_thunk(1, move || {
+            counter.set(counter.get() + 1);
+            10
+        });
+        assert_eq!(calls.get(), 0);
+        assert_eq!(dict.get(1), Some(&10));
+        assert_eq!(dict.get(1), Some(&10));
+        assert_eq!(calls.get(), 1);
+        assert_eq!(dict.get(2), None);
+    }
+
+    #[test]
+    fn replace_and_convert() {
+        let mut dict = LazyDictionary::<i32, i32>::new();
+        dict.insert_thunk(1, || panic!("replaced before it ran"));
+        dict.insert_thunk(2, || 20);
+        dict.insert_value(1, 1);
+        assert!(matches!(dict.remove(2), Some(Lazy::Thunk(_))));
+        dict.insert_thunk(3, || 30);
+        let plain = dict.into_dictionary();
+        assert_eq!(plain.keys(), &vec![1, 3]);
+        assert_eq!(plain.values(), &vec![1, 30]);
+    }
+
+    #[test]
+    fn panicking_thunk_poisons() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut dict = LazyDictionary::<i32, i32>::new();
+        dict.insert_thunk(1, || panic!("thunk failed"));
+        dict.insert_value(2, 2);
+        assert!(catch_unwind(AssertUnwindSafe(|| dict.get(1).copied())).is_err());
+        assert!(dict.is_poisoned(&1));
+        assert!(!dict.is_forced(&1));
+        // a later read panics instead of seeing a missing value
+        assert!(catch_unwind(AssertUnwindSafe(|| dict.get(1).copied())).is_err());
+        assert_eq!(dict.get(2), Some(&2));
+        dict.insert_thunk(1, || 10);
+        assert!(!dict.is_poisoned(&1));
+        assert_eq!(dict.get(1), Some(&10));
+    }
+}
@@ -1 +1,57 @@
+#[macro_use]
+mod macros;
+
+pub mod arena;
+pub mod assert;
+pub mod audit;
+pub mod bounded;
+pub mod builder;
+pub mod canonical;
+pub mod cast;
+pub mod changes;
+pub mod concurrent;
+pub mod counter;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod cursor;
+pub mod default_dict;
 pub mod dict;
+pub mod diff;
+pub mod digest;
+pub mod dotenv;
+pub mod durable;
+pub mod error;
+pub mod expiring;
+pub mod float;
+pub mod format;
+pub mod framing;
+pub mod frozen;
+pub mod group;
+pub mod growth;
+pub mod health;
+pub mod index;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod key_error;
+pub mod key_limit;
+pub mod lazy;
+pub mod map_like;
+pub mod memory;
+pub mod merge;
+pub mod multi;
+pub mod observe;
+pub mod page;
+pub mod parsed;
+pub mod persistent;
+pub mod pool;
+pub mod preview;
+pub mod queue;
+pub mod range;
+pub mod redact;
+pub mod registry;
+pub mod set;
+pub mod slice;
+pub mod summary;
+pub mod value;
+pub mod view;
+pub mod visit;
@@ -0,0 +1,91 @@
+/// Create a Dictionary from a list of key value pairs, keeping the literal order
+/// `dict!{}` creates an empty dictionary and `dict!(capacity: n)` an empty one with reserved capacity
+/// # Example
+/// ```
+/// use rust_dict::dict;
+/// use rust_dict::dict::Dictionary;
+///
+/// let dict = dict! {
+///     "b" => 2,
+///     "a" => 1,
+/// };
+/// assert_eq!(dict.keys(), &vec!["b", "a"]);
+/// assert_eq!(dict.capacity(), 2);
+///
+/// let empty: Dictionary<i32, i32> = dict!(capacity: 8);
+/// assert!(empty.is_empty());
+/// assert_eq!(empty.capacity(), 8);
+/// ```
+#[macro_export]
+macro_rules! dict {
+    (@unit $($t:tt)*) => {
+        ()
+    };
+    () => {
+        $crate::dict::Dictionary::new()
+    };
+    (capacity: $cap:expr $(,)?) => {
+        $crate::dict::Dictionary::with_capacity($cap)
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {{
+        let size = <[()]>::len(&[$($crate::dict!(@unit $key)),+]);
+        let mut dict = $crate::dict::Dictionary::with_capacity(size);
+        $(
+            dict.push_back($key, $value);
+        )+
+        dict
+    }};
+}
+
+/// Assert that two dictionaries are equal, order included
+/// on failure the panic message lists the keys that are missing, extra, changed or moved
+/// with their positions, see `assert::mismatch_report`, instead of two Debug dumps
+/// # Example
+/// ```
+/// use rust_dict::assert_dict_eq;
+/// use rust_dict::dict::Dictionary;
+///
+/// let mut dict = Dictionary::from([("a", 1)]);
+/// dict.push_back("b", 2);
+/// assert_dict_eq!(dict, Dictionary::from([("a", 1), ("b", 2)]));
+/// ```
+#[macro_export]
+macro_rules! assert_dict_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(report) = $crate::assert::mismatch_report(left, right) {
+                    panic!("assertion `left == right` failed\n{}", report);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if let Some(report) = $crate::assert::mismatch_report(left, right) {
+                    panic!(
+                        "assertion `left == right` failed: {}\n{}",
+                        format_args!($($arg)+),
+                        report
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dict::Dictionary;
+
+    #[test]
+    fn dict_macro() {
+        let dict = dict! {3 => "c", 1 => "a", 2 => "b"};
+        assert_eq!(dict.keys(), &vec![3, 1, 2]);
+        assert_eq!(dict.get(1), Some("a"));
+
+        let empty: Dictionary<i32, i32> = dict! {};
+        assert_eq!(empty.len(), 0);
+    }
+}
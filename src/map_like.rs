@@ -0,0 +1,130 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::hash::Hash;
+
+/// The operations every map type in the crate supports
+/// write code against this trait to run it on any of them
+/// values are returned by clone so types that can not hand out references,
+/// like ones behind a lock, can implement it as well.
+/// FrozenDictionary does not implement it since insert and remove would break its
+/// immutability, read only code can take the Dictionary it derefs to instead
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+/// use rust_dict::map_like::MapLike;
+///
+/// fn count<M: MapLike<&'static str, u32>>(map: &mut M, word: &'static str) {
+///     let n = map.get(&word).unwrap_or(0);
+///     map.insert(word, n + 1);
+/// }
+///
+/// let mut dict = Dictionary::new();
+/// count(&mut dict, "a");
+/// count(&mut dict, "a");
+/// assert_eq!(MapLike::get(&dict, &"a"), Some(2));
+/// ```
+pub trait MapLike<K, V> {
+    /// a copy of the value for the key
+    fn get(&self, key: &K) -> Option<V>;
+
+    /// add or replace the value for the key, returning the previous value
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    /// remove the key, returning its value
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    /// number of entries
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// copies of every entry, in the order of the map if it has one
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_>;
+
+    /// call f with every entry, in the order of the map if it has one
+    /// maps that can lend their entries override this to skip the copies
+    fn for_each_entry<F: FnMut(&K, &V)>(&self, mut f: F) {
+        for (key, value) in self.iter() {
+            f(&key, &value);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> MapLike<K, V> for Dictionary<K, V, S> {
+    fn get(&self, key: &K) -> Option<V> {
+        self.get_full(key).map(|(_, _, v)| v.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.push_back(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        Dictionary::remove(self, key.clone())
+    }
+
+    fn len(&self) -> usize {
+        Dictionary::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        Box::new(Dictionary::iter(self).map(|(k, v)| (k.clone(), v.clone())))
+    }
+
+    fn for_each_entry<F: FnMut(&K, &V)>(&self, mut f: F) {
+        for (key, value) in Dictionary::iter(self) {
+            f(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi::MultiDict;
+
+    fn exercise<M: MapLike<i32, i32>>(map: &mut M) {
+        assert!(map.is_empty());
+        assert_eq!(map.insert(1, 10), None);
+        assert_eq!(map.insert(2, 20), None);
+        assert_eq!(map.insert(1, 11), Some(10));
+        assert_eq!(map.len(), 2);
+        assert!(map.contains(&2));
+        assert_eq!(map.remove(&2), Some(20));
+        assert_eq!(map.remove(&2), None);
+        let mut seen = Vec::new();
+        map.for_each_entry(|k, v| seen.push((*k, *v)));
+        assert_eq!(seen, vec![(1, 11)]);
+        map.insert(3, 30);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![(1, 11), (3, 30)]);
+    }
+
+    #[test]
+    fn dictionary_is_map_like() {
+        exercise(&mut Dictionary::new());
+    }
+
+    #[test]
+    fn multi_dict_is_map_like() {
+        exercise(&mut MultiDict::new());
+        let mut multi: MultiDict<i32, i32> = [(1, 1), (2, 2), (1, 3)].into_iter().collect();
+        assert_eq!(MapLike::len(&multi), 2);
+        assert_eq!(multi.iter().count(), 3);
+        assert_eq!(
+            MapLike::iter(&multi).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2)]
+        );
+        assert_eq!(MapLike::insert(&mut multi, 1, 9), Some(1));
+        assert_eq!(multi.get_all(&1), vec![&9]);
+    }
+}
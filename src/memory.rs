@@ -0,0 +1,180 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::fmt::{self, Display, Formatter};
+use std::mem::size_of;
+
+/// The heap bytes of a Dictionary, returned by `Dictionary::memory_stats`
+/// and `Dictionary::deep_memory_stats`
+/// the byte counts are estimates from capacities and type sizes, the allocator's
+/// own overhead and the control bytes of the hash table are not included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// number of key value pairs
+    pub len: usize,
+    /// number of pairs the dictionary can hold before resizing
+    pub capacity: usize,
+    /// bytes allocated for the key column
+    pub keys_bytes: usize,
+    /// bytes allocated for the value column
+    pub values_bytes: usize,
+    /// bytes allocated for the key index, which holds its own copy of every key
+    pub index_bytes: usize,
+}
+
+impl MemoryStats {
+    /// the bytes of the keys, values and index together
+    pub fn total_bytes(&self) -> usize {
+        self.keys_bytes + self.values_bytes + self.index_bytes
+    }
+}
+
+impl Display for MemoryStats {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "len: {}", self.len)?;
+        writeln!(f, "capacity: {}", self.capacity)?;
+        writeln!(f, "keys: {} bytes", self.keys_bytes)?;
+        writeln!(f, "values: {} bytes", self.values_bytes)?;
+        writeln!(f, "index: {} bytes", self.index_bytes)?;
+        write!(f, "total: {} bytes", self.total_bytes())
+    }
+}
+
+/// Types that can report the heap memory they own, for `Dictionary::deep_memory_stats`
+/// the size of the value itself is not included, a String reports its buffer
+/// and a u64 reports 0
+pub trait DeepSize {
+    fn heap_size(&self) -> usize;
+}
+
+// types that own no heap memory
+macro_rules! deep_size_zero {
+    ($($t:ty),*) => {
+        $(impl DeepSize for $t {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        })*
+    };
+}
+
+deep_size_zero!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, &str
+);
+
+impl DeepSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: DeepSize> DeepSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(DeepSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: DeepSize> DeepSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        size_of::<T>() + (**self).heap_size()
+    }
+}
+
+impl<T: DeepSize> DeepSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, DeepSize::heap_size)
+    }
+}
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// the heap bytes used by the keys, values and index map
+    /// only the memory of the three containers is counted, not heap memory owned by the
+    /// keys and values themselves, use deep_memory_stats for that
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict: Dictionary<u64, u32> = (0..100).map(|i| (i, 0)).collect();
+    /// let stats = dict.memory_stats();
+    /// assert_eq!(stats.len, 100);
+    /// assert!(stats.keys_bytes >= 100 * 8);
+    /// assert!(stats.values_bytes >= 100 * 4);
+    /// assert!(stats.total_bytes() > stats.keys_bytes + stats.values_bytes);
+    /// ```
+    pub fn memory_stats(&self) -> MemoryStats {
+        let (_, index_capacity) = self.index_load();
+        MemoryStats {
+            len: self.len(),
+            capacity: self.capacity(),
+            keys_bytes: self.keys().capacity() * size_of::<K>(),
+            values_bytes: self.values().capacity() * size_of::<V>(),
+            index_bytes: index_capacity * (size_of::<K>() + size_of::<usize>()),
+        }
+    }
+
+    /// memory_stats plus the heap memory owned by the keys and values
+    /// the index is counted with a second copy of the heap memory of each key.
+    /// This is O(n), every key and value is asked for its size
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(String::from("k"), vec![0u8; 1000])]);
+    /// let shallow = dict.memory_stats();
+    /// let deep = dict.deep_memory_stats();
+    /// assert_eq!(deep.values_bytes, shallow.values_bytes + 1000);
+    /// assert!(deep.keys_bytes > shallow.keys_bytes);
+    /// ```
+    pub fn deep_memory_stats(&self) -> MemoryStats
+    where
+        K: DeepSize,
+        V: DeepSize,
+    {
+        let mut stats = self.memory_stats();
+        let key_heap: usize = self.keys().iter().map(DeepSize::heap_size).sum();
+        let value_heap: usize = self.values().iter().map(DeepSize::heap_size).sum();
+        stats.keys_bytes += key_heap;
+        stats.values_bytes += value_heap;
+        stats.index_bytes += key_heap;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shallow_stats_follow_capacity() {
+        let mut dict: Dictionary<u32, u64> = Dictionary::with_capacity(64);
+        let empty = dict.memory_stats();
+        assert_eq!(empty.len, 0);
+        assert!(empty.keys_bytes >= 64 * 4);
+        assert!(empty.values_bytes >= 64 * 8);
+        for i in 0..10 {
+            dict.push_back(i, 0);
+        }
+        assert_eq!(dict.memory_stats().total_bytes(), empty.total_bytes());
+        dict.shrink_to_fit();
+        let shrunk = dict.memory_stats();
+        assert_eq!(shrunk.capacity, 10);
+        assert!(shrunk.total_bytes() < empty.total_bytes());
+        assert!(shrunk.to_string().starts_with("len: 10\ncapacity: 10\n"));
+        assert_eq!(
+            Dictionary::<u32, u32>::new().memory_stats().total_bytes(),
+            0
+        );
+    }
+
+    #[test]
+    fn deep_sizes() {
+        assert_eq!(7u64.heap_size(), 0);
+        assert_eq!(String::with_capacity(12).heap_size(), 12);
+        let nested = vec![String::with_capacity(3), String::with_capacity(5)];
+        assert_eq!(nested.heap_size(), 2 * size_of::<String>() + 8);
+        assert_eq!(Some(Box::new(1u32)).heap_size(), 4);
+        let dict = Dictionary::from([(1u8, String::with_capacity(100))]);
+        let deep = dict.deep_memory_stats();
+        assert_eq!(deep.values_bytes, dict.memory_stats().values_bytes + 100);
+        assert_eq!(deep.index_bytes, dict.memory_stats().index_bytes);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::value::DictValue;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// The depth `DictValue::deep_merge` allows unless told otherwise
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// How `DictValue::deep_merge` combines two values
+/// nested dictionaries are always merged key by key, keys only in the incoming
+/// dictionary are pushed to the back and keys in both are merged recursively.
+/// The strategies differ in how lists are combined, any other pair of values is
+/// resolved by taking the incoming value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepMergeStrategy {
+    /// the incoming list replaces the existing list
+    OverwriteLists,
+    /// the incoming items are appended to the existing list
+    AppendLists,
+    /// lists are merged position by position, recursing into dictionaries at the
+    /// same position, with any extra incoming items appended
+    RecurseDicts,
+}
+
+/// Error returned when a merge would go deeper than the configured limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeDepthError {
+    pub max_depth: usize,
+}
+
+impl Display for MergeDepthError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "value is nested deeper than {} levels", self.max_depth)
+    }
+}
+
+impl Error for MergeDepthError {}
+
+impl DictValue {
+    /// merge another value into this one, the core operation of layered config
+    /// the incoming value is checked against DEFAULT_MAX_DEPTH before anything is changed
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::merge::DeepMergeStrategy;
+    /// use rust_dict::value::DictValue;
+    ///
+    /// let mut base = Dictionary::new();
+    /// base.push_back("port".to_string(), DictValue::Int(80));
+    /// base.push_back("tags".to_string(), DictValue::List(vec!["a".into()]));
+    /// let mut layer = Dictionary::new();
+    /// layer.push_back("tags".to_string(), DictValue::List(vec!["b".into()]));
+    /// layer.push_back("debug".to_string(), DictValue::Bool(true));
+    ///
+    /// let mut config = DictValue::from(base);
+    /// config.deep_merge(DictValue::from(layer), DeepMergeStrategy::AppendLists).unwrap();
+    /// let config = config.as_dict().unwrap();
+    /// assert_eq!(config.keys(), &vec!["port", "tags", "debug"]);
+    /// assert_eq!(config.get("tags".into()), Some(DictValue::List(vec!["a".into(), "b".into()])));
+    /// ```
+    pub fn deep_merge(
+        &mut self,
+        other: DictValue,
+        strategy: DeepMergeStrategy,
+    ) -> Result<(), MergeDepthError> {
+        self.deep_merge_with_limit(other, strategy, DEFAULT_MAX_DEPTH)
+    }
+
+    /// deep_merge with a custom depth limit
+    /// a scalar has depth 0 and each enclosing list or dictionary adds one
+    pub fn deep_merge_with_limit(
+        &mut self,
+        other: DictValue,
+        strategy: DeepMergeStrategy,
+        max_depth: usize,
+    ) -> Result<(), MergeDepthError> {
+        // the merge only recurses as deep as the incoming value goes,
+        // so checking it up front means a failed merge leaves self untouched
+        if other.depth_exceeds(max_depth) {
+            return Err(MergeDepthError { max_depth });
+        }
+        self.merge_value(other, strategy);
+        Ok(())
+    }
+
+    fn merge_value(&mut self, other: DictValue, strategy: DeepMergeStrategy) {
+        match (self, other) {
+            (DictValue::Dict(existing), DictValue::Dict(incoming)) => {
+                for (key, value) in *incoming {
+                    match existing.get_mut(key.clone()) {
+                        Some(current) => current.merge_value(value, strategy),
+                        None => {
+                            existing.push_back(key, value);
+                        }
+                    }
+                }
+            }
+            (DictValue::List(existing), DictValue::List(incoming)) => match strategy {
+                DeepMergeStrategy::OverwriteLists => *existing = incoming,
+                DeepMergeStrategy::AppendLists => existing.extend(incoming),
+                DeepMergeStrategy::RecurseDicts => {
+                    for (i, item) in incoming.into_iter().enumerate() {
+                        match existing.get_mut(i) {
+                            Some(current) => current.merge_value(item, strategy),
+                            None => existing.push(item),
+                        }
+                    }
+                }
+            },
+            (current, incoming) => *current = incoming,
+        }
+    }
+
+    // iterative so a pathologically deep value can not overflow the stack
+    fn depth_exceeds(&self, max_depth: usize) -> bool {
+        let mut stack: Vec<(&DictValue, usize)> = vec![(self, 0)];
+        while let Some((value, depth)) = stack.pop() {
+            match value {
+                DictValue::Dict(dict) => {
+                    if depth + 1 > max_depth {
+                        return true;
+                    }
+                    stack.extend(dict.values().iter().map(|v| (v, depth + 1)));
+                }
+                DictValue::List(items) => {
+                    if depth + 1 > max_depth {
+                        return true;
+                    }
+                    stack.extend(items.iter().map(|v| (v, depth + 1)));
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::Dictionary;
+
+    fn dict(pairs: Vec<(&str, DictValue)>) -> DictValue {
+        DictValue::from(
+            pairs
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect::<Dictionary<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn recurse_nested_dicts() {
+        let mut base = dict(vec![
+            (
+                "db",
+                dict(vec![("host", "a".into()), ("port", DictValue::Int(1))]),
+            ),
+            ("name", "app".into()),
+        ]);
+        let layer = dict(vec![
+            (
+                "db",
+                dict(vec![("port", DictValue::Int(2)), ("user", "me".into())]),
+            ),
+            ("name", "other".into()),
+        ]);
+        base.deep_merge(layer, DeepMergeStrategy::OverwriteLists)
+            .unwrap();
+        let expected = dict(vec![
+            (
+                "db",
+                dict(vec![
+                    ("host", "a".into()),
+                    ("port", DictValue::Int(2)),
+                    ("user", "me".into()),
+                ]),
+            ),
+            ("name", "other".into()),
+        ]);
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn list_strategies() {
+        let base = DictValue::List(vec![
+            dict(vec![("a", DictValue::Int(1))]),
+            DictValue::Int(2),
+        ]);
+        let layer = DictValue::List(vec![
+            dict(vec![("b", DictValue::Int(3))]),
+            DictValue::Int(4),
+            DictValue::Int(5),
+        ]);
+
+        let mut overwrite = base.clone();
+        overwrite
+            .deep_merge(layer.clone(), DeepMergeStrategy::OverwriteLists)
+            .unwrap();
+        assert_eq!(overwrite, layer);
+
+        let mut append = base.clone();
+        append
+            .deep_merge(layer.clone(), DeepMergeStrategy::AppendLists)
+            .unwrap();
+        assert_eq!(append.as_list().unwrap().len(), 5);
+
+        let mut recurse = base.clone();
+        recurse
+            .deep_merge(layer, DeepMergeStrategy::RecurseDicts)
+            .unwrap();
+        assert_eq!(
+            recurse,
+            DictValue::List(vec![
+                dict(vec![("a", DictValue::Int(1)), ("b", DictValue::Int(3))]),
+                DictValue::Int(4),
+                DictValue::Int(5),
+            ])
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut deep = DictValue::Int(0);
+        for _ in 0..10 {
+            deep = DictValue::List(vec![deep]);
+        }
+        let mut base = DictValue::from(Dictionary::new());
+        assert_eq!(
+            base.deep_merge_with_limit(deep.clone(), DeepMergeStrategy::AppendLists, 9),
+            Err(MergeDepthError { max_depth: 9 })
+        );
+        assert_eq!(base, DictValue::from(Dictionary::new()));
+        assert!(base
+            .deep_merge_with_limit(deep, DeepMergeStrategy::AppendLists, 10)
+            .is_ok());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::dict::Dictionary;
+use crate::map_like::MapLike;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+use std::iter::Zip;
+use std::slice::Iter;
+
+/// An ordered map that can hold the same key more than once
+/// every pair keeps its place in the overall insertion order, like HTTP headers
+/// or a query string, and the values of a key can be read back together
+/// # Example
+/// ```
+/// use rust_dict::multi::MultiDict;
+///
+/// let mut headers = MultiDict::new();
+/// headers.add("accept", "text/html");
+/// headers.add("host", "example.com");
+/// headers.add("accept", "application/json");
+/// assert_eq!(headers.get_all(&"accept"), vec![&"text/html", &"application/json"]);
+/// assert_eq!(headers.len(), 3);
+/// assert_eq!(headers.keys_len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct MultiDict<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    // the positions of every pair with the key, in order
+    positions: HashMap<K, Vec<usize>>,
+}
+
+impl<K: Hash + Eq + Clone, V> MultiDict<K, V> {
+    pub fn new() -> MultiDict<K, V> {
+        MultiDict::with_capacity(0)
+    }
+
+    pub fn with_capacity(size: usize) -> MultiDict<K, V> {
+        MultiDict {
+            keys: Vec::with_capacity(size),
+            values: Vec::with_capacity(size),
+            positions: HashMap::with_capacity(size),
+        }
+    }
+
+    /// add a pair to the back, earlier values of the key are kept
+    pub fn add(&mut self, key: K, value: V) {
+        self.positions
+            .entry(key.clone())
+            .or_default()
+            .push(self.keys.len());
+        self.keys.push(key);
+        self.values.push(value);
+    }
+
+    /// replace every value of the key with one value
+    /// the value takes the position of the first pair with the key, or the back for a new key
+    pub fn set(&mut self, key: K, value: V) {
+        match self.positions.get(&key).and_then(|p| p.first().copied()) {
+            Some(first) => {
+                self.values[first] = value;
+                let mut seen = false;
+                self.retain_pairs(|k, _| {
+                    if *k != key {
+                        return true;
+                    }
+                    let keep = !seen;
+                    seen = true;
+                    keep
+                });
+            }
+            None => self.add(key, value),
+        }
+    }
+
+    /// the first value of the key
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let first = *self.positions.get(key)?.first()?;
+        Some(&self.values[first])
+    }
+
+    /// the last value of the key
+    pub fn get_last(&self, key: &K) -> Option<&V> {
+        let last = *self.positions.get(key)?.last()?;
+        Some(&self.values[last])
+    }
+
+    /// every value of the key in insertion order, empty for a missing key
+    pub fn get_all(&self, key: &K) -> Vec<&V> {
+        match self.positions.get(key) {
+            Some(positions) => positions.iter().map(|&i| &self.values[i]).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// remove every pair with the key, returning the values in insertion order
+    pub fn remove_all(&mut self, key: &K) -> Vec<V> {
+        if !self.positions.contains_key(key) {
+            return Vec::new();
+        }
+        let keys = std::mem::take(&mut self.keys);
+        let values = std::mem::take(&mut self.values);
+        let mut removed = Vec::new();
+        for (k, v) in keys.into_iter().zip(values) {
+            if k == *key {
+                removed.push(v);
+            } else {
+                self.keys.push(k);
+                self.values.push(v);
+            }
+        }
+        self.recompute_positions();
+        removed
+    }
+
+    /// keep only the pairs the predicate returns true for
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, keep: F) {
+        self.retain_pairs(keep);
+    }
+
+    fn retain_pairs<F: FnMut(&K, &V) -> bool>(&mut self, mut keep: F) {
+        let keys = std::mem::take(&mut self.keys);
+        let values = std::mem::take(&mut self.values);
+        for (k, v) in keys.into_iter().zip(values) {
+            if keep(&k, &v) {
+                self.keys.push(k);
+                self.values.push(v);
+            }
+        }
+        self.recompute_positions();
+    }
+
+    fn recompute_positions(&mut self) {
+        self.positions.clear();
+        for (i, key) in self.keys.iter().enumerate() {
+            self.positions.entry(key.clone()).or_default().push(i);
+        }
+    }
+
+    /// the number of pairs, counting repeated keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// the number of distinct keys
+    pub fn keys_len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+        self.positions.clear();
+    }
+
+    /// the key of every pair, repeats included
+    pub fn keys(&self) -> &Vec<K> {
+        &self.keys
+    }
+
+    pub fn values(&self) -> &Vec<V> {
+        &self.values
+    }
+
+    /// every pair in insertion order
+    pub fn iter(&self) -> Zip<Iter<'_, K>, Iter<'_, V>> {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// each distinct key with all of its values, keys in order of first appearance
+    /// # Example
+    /// ```
+    /// use rust_dict::multi::MultiDict;
+    ///
+    /// let query: MultiDict<&str, i32> = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+    /// let grouped: Vec<(&&str, Vec<&i32>)> = query.iter_grouped().collect();
+    /// assert_eq!(grouped, vec![(&"a", vec![&1, &3]), (&"b", vec![&2])]);
+    /// ```
+    pub fn iter_grouped(&self) -> impl Iterator<Item = (&K, Vec<&V>)> + '_ {
+        self.keys.iter().enumerate().filter_map(move |(i, key)| {
+            let positions = &self.positions[key];
+            if positions[0] != i {
+                return None;
+            }
+            Some((key, positions.iter().map(|&p| &self.values[p]).collect()))
+        })
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> MultiDict<K, V> {
+    /// collect the values of each key into a Dictionary
+    /// keys are in order of first appearance and each Vec keeps insertion order
+    /// # Example
+    /// ```
+    /// use rust_dict::multi::MultiDict;
+    ///
+    /// let multi: MultiDict<&str, i32> = [("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+    /// let grouped = multi.into_grouped();
+    /// assert_eq!(grouped.get("a"), Some(vec![1, 3]));
+    /// assert_eq!(MultiDict::from_grouped(grouped).values(), &vec![1, 3, 2]);
+    /// ```
+    pub fn into_grouped(self) -> Dictionary<K, Vec<V>> {
+        let mut grouped: Dictionary<K, Vec<V>> = Dictionary::with_capacity(self.positions.len());
+        for (key, value) in self.keys.into_iter().zip(self.values) {
+            match grouped.get_mut(key.clone()) {
+                Some(values) => values.push(value),
+                None => {
+                    grouped.push_back(key, vec![value]);
+                }
+            }
+        }
+        grouped
+    }
+
+    /// one pair per value, the values of each key kept together in key order
+    /// keys with an empty Vec are dropped
+    pub fn from_grouped(grouped: Dictionary<K, Vec<V>>) -> MultiDict<K, V> {
+        let mut multi = MultiDict::with_capacity(grouped.len());
+        for (key, values) in grouped {
+            for value in values {
+                multi.add(key.clone(), value);
+            }
+        }
+        multi
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Default for MultiDict<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for MultiDict<K, V> {
+    /// the same pairs in the same order
+    fn eq(&self, rhs: &Self) -> bool {
+        self.keys == rhs.keys && self.values == rhs.values
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for MultiDict<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.keys.iter().zip(self.values.iter()))
+            .finish()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> FromIterator<(K, V)> for MultiDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> MultiDict<K, V> {
+        let mut multi = MultiDict::new();
+        multi.extend(iter);
+        multi
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Extend<(K, V)> for MultiDict<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.add(key, value);
+        }
+    }
+}
+
+impl<K, V> IntoIterator for MultiDict<K, V> {
+    type Item = (K, V);
+    type IntoIter = Zip<std::vec::IntoIter<K>, std::vec::IntoIter<V>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().zip(self.values)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> MapLike<K, V> for MultiDict<K, V> {
+    /// the first value, MapLike sees each key with its first value
+    fn get(&self, key: &K) -> Option<V> {
+        MultiDict::get(self, key).cloned()
+    }
+
+    /// replaces every value of the key like set, returning the first one
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let previous = MultiDict::get(self, &key).cloned();
+        self.set(key, value);
+        previous
+    }
+
+    /// removes every value of the key, returning the first one
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_all(key).into_iter().next()
+    }
+
+    /// the number of distinct keys
+    fn len(&self) -> usize {
+        self.keys_len()
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    /// each distinct key with its first value, in order of first appearance
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, V)> + '_> {
+        Box::new(
+            self.keys
+                .iter()
+                .enumerate()
+                .filter(|(i, key)| self.positions[*key][0] == *i)
+                .map(|(i, key)| (key.clone(), self.values[i].clone())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_remove_set() {
+        let mut query: MultiDict<&str, i32> = [("x", 1), ("y", 2), ("x", 3), ("z", 4), ("x", 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(query.get(&"x"), Some(&1));
+        assert_eq!(query.get_last(&"x"), Some(&5));
+        assert_eq!(query.remove_all(&"y"), vec![2]);
+        assert!(query.get_all(&"y").is_empty());
+        assert_eq!(query.get_all(&"z"), vec![&4]);
+
+        query.set("x", 9);
+        assert_eq!(query.keys(), &vec!["x", "z"]);
+        assert_eq!(query.values(), &vec![9, 4]);
+        query.set("w", 0);
+        assert_eq!(query.keys_len(), 3);
+        query.retain(|_, v| *v > 0);
+        assert_eq!(
+            query.into_iter().collect::<Vec<_>>(),
+            vec![("x", 9), ("z", 4)]
+        );
+    }
+
+    #[test]
+    fn grouped_round_trip() {
+        let multi: MultiDict<i32, char> = [(2, 'a'), (1, 'b'), (2, 'c')].into_iter().collect();
+        let grouped = multi.clone().into_grouped();
+        assert_eq!(grouped.keys(), &vec![2, 1]);
+        assert_eq!(grouped.values(), &vec![vec!['a', 'c'], vec!['b']]);
+        let back = MultiDict::from_grouped(grouped);
+        assert_eq!(back.keys(), &vec![2, 2, 1]);
+        assert_eq!(back.clone().into_grouped(), multi.into_grouped());
+        assert_eq!(format!("{:?}", back), "{2: 'a', 2: 'c', 1: 'b'}");
+    }
+}
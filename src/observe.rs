@@ -0,0 +1,189 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+
+/// One mutation of a Dictionary, reported to the observer set with `Dictionary::observe`
+/// index is the position at the time of the mutation, so applying the mutations in
+/// the order they are reported keeps a mirror of the dictionary in step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation<'a, K, V> {
+    /// a new key was added at index
+    Insert {
+        index: usize,
+        key: &'a K,
+        value: &'a V,
+    },
+    /// the value of an existing key was replaced or combined, the key did not move
+    Update {
+        index: usize,
+        key: &'a K,
+        old: &'a V,
+        value: &'a V,
+    },
+    /// the entry at index was removed, the entries after it shifted down
+    Remove {
+        index: usize,
+        key: &'a K,
+        value: &'a V,
+    },
+    /// the key at index was renamed from old by rename_keys, its value did not change
+    Rename {
+        index: usize,
+        old: &'a K,
+        key: &'a K,
+    },
+    /// the entries were reordered by a sort, reverse, move or swap, keys is the new order
+    Reorder { keys: &'a [K] },
+    /// every entry was removed
+    Clear,
+}
+
+// the callback the dictionary reports mutations to, Send and Sync so an observed
+// dictionary can still be shared between threads
+pub(crate) type Observer<K, V> = Box<dyn FnMut(Mutation<'_, K, V>) + Send + Sync>;
+
+impl<K: Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// call f after every mutation, replacing any previous observer
+    /// push_back, push_front, insert, shift_insert, set_index, the remove and pop methods, retain,
+    /// remove_many, drain, truncate, split_off, clear, update_from, rename_keys, the sort,
+    /// reverse, move and swap methods and CursorMut inserts and removes are reported.
+    /// Values changed through get_mut, get_index_mut, iter_mut or a cursor are not.
+    /// The observer is not cloned with the dictionary
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::observe::Mutation;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let mut dict = Dictionary::new();
+    /// let sink = Arc::clone(&log);
+    /// dict.observe(move |change| {
+    ///     let line = match change {
+    ///         Mutation::Insert { key, .. } => format!("+{}", key),
+    ///         Mutation::Update { key, old, value, .. } => format!("{}: {} -> {}", key, old, value),
+    ///         Mutation::Remove { key, .. } => format!("-{}", key),
+    ///         Mutation::Rename { old, key, .. } => format!("{} => {}", old, key),
+    ///         Mutation::Reorder { keys } => format!("{:?}", keys),
+    ///         Mutation::Clear => "clear".to_string(),
+    ///     };
+    ///     sink.lock().unwrap().push(line);
+    /// });
+    /// dict.push_back("b", 1);
+    /// dict.push_back("a", 2);
+    /// dict.push_back("b", 3);
+    /// dict.sort_by_keys();
+    /// dict.remove("b");
+    /// dict.rename_keys(&Dictionary::from([("a", "c")])).unwrap();
+    /// assert_eq!(
+    ///     *log.lock().unwrap(),
+    ///     vec!["+b", "+a", "b: 1 -> 3", r#"["a", "b"]"#, "-b", "a => c"]
+    /// );
+    /// ```
+    pub fn observe<F>(&mut self, f: F)
+    where
+        F: FnMut(Mutation<'_, K, V>) + Send + Sync + 'static,
+    {
+        self.set_observer(Some(Box::new(f)));
+    }
+
+    /// stop reporting mutations and drop the observer
+    pub fn unobserve(&mut self) {
+        self.set_observer(None);
+    }
+
+    pub fn is_observed(&self) -> bool {
+        self.observer().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Mirror = Arc<Mutex<Vec<(u32, u32)>>>;
+
+    // keeps a Vec in step with the dictionary from the reported mutations alone
+    fn mirrored(dict: &mut Dictionary<u32, u32>) -> Mirror {
+        let mirror: Mirror = Arc::default();
+        let sink = Arc::clone(&mirror);
+        dict.observe(move |change| {
+            let mut entries = sink.lock().unwrap();
+            match change {
+                Mutation::Insert { index, key, value } => entries.insert(index, (*key, *value)),
+                Mutation::Update { index, value, .. } => entries[index].1 = *value,
+                Mutation::Remove { index, key, .. } => {
+                    assert_eq!(entries.remove(index).0, *key);
+                }
+                Mutation::Rename { index, old, key } => {
+                    assert_eq!(std::mem::replace(&mut entries[index].0, *key), *old);
+                }
+                Mutation::Reorder { keys } => {
+                    let old = std::mem::take(&mut *entries);
+                    for key in keys {
+                        entries.push(*old.iter().find(|(k, _)| k == key).unwrap());
+                    }
+                }
+                Mutation::Clear => entries.clear(),
+            }
+        });
+        mirror
+    }
+
+    fn assert_mirrors(dict: &Dictionary<u32, u32>, mirror: &Mirror) {
+        let entries: Vec<(u32, u32)> = dict.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(*mirror.lock().unwrap(), entries);
+    }
+
+    #[test]
+    fn mirror_follows_mutations() {
+        let mut dict = Dictionary::new();
+        let mirror = mirrored(&mut dict);
+        for i in 0..10 {
+            dict.push_back(i, i * 10);
+        }
+        dict.push_front(20, 0);
+        dict.push_back(3, 33);
+        dict.insert_after(&5, 21, 1);
+        assert_mirrors(&dict, &mirror);
+        dict.remove(4);
+        dict.pop_front();
+        dict.pop_back();
+        dict.swap_remove(1);
+        dict.retain(|k, _| k % 3 != 0);
+        assert_mirrors(&dict, &mirror);
+        dict.drain(1..2);
+        dict.sort_by_values();
+        dict.reverse();
+        dict.move_index(0, 2);
+        assert_mirrors(&dict, &mirror);
+        let first = dict.keys()[0];
+        let second = dict.keys()[1];
+        dict.rename_keys(&Dictionary::from([(first, second), (second, first)]))
+            .unwrap();
+        dict.rename_keys(&Dictionary::from([(first, 100)])).unwrap();
+        assert_mirrors(&dict, &mirror);
+        let _rest = dict.split_off(1);
+        assert_mirrors(&dict, &mirror);
+        dict.clear();
+        assert!(mirror.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unobserved_and_clones() {
+        let mut dict = Dictionary::from([(1, 1)]);
+        let count = Arc::new(Mutex::new(0));
+        let sink = Arc::clone(&count);
+        dict.observe(move |_| *sink.lock().unwrap() += 1);
+        assert!(dict.is_observed());
+        let mut copy = dict.clone();
+        assert!(!copy.is_observed());
+        copy.push_back(2, 2);
+        dict.push_back(1, 5);
+        dict.truncate(0);
+        assert_eq!(*count.lock().unwrap(), 2);
+        dict.unobserve();
+        dict.push_back(3, 3);
+        assert_eq!(*count.lock().unwrap(), 2);
+    }
+}
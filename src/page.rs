@@ -0,0 +1,224 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::Zip;
+use std::slice::Iter;
+
+/// Error returned when a continuation token can not be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageTokenError {
+    /// the token string is not a token
+    Malformed,
+    /// entries before the token position were removed or reordered since it was issued
+    Invalidated,
+}
+
+impl Display for PageTokenError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            PageTokenError::Malformed => write!(f, "malformed page token"),
+            PageTokenError::Invalidated => {
+                write!(f, "page token no longer matches the dictionary")
+            }
+        }
+    }
+}
+
+impl Error for PageTokenError {}
+
+/// An opaque position to continue paginating from
+/// The token remembers where the previous page ended and a fingerprint of the last key
+/// on it. Pushing to the back of the dictionary between requests is fine, removing or
+/// reordering earlier entries makes the token fail with `Invalidated` rather than
+/// silently skipping or repeating entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PageToken {
+    offset: usize,
+    fingerprint: u64,
+}
+
+impl PageToken {
+    /// encode the token as a string for handing to API clients
+    pub fn encode(&self) -> String {
+        format!("{:x}.{:016x}", self.offset, self.fingerprint)
+    }
+
+    /// decode a token produced by encode
+    pub fn decode(token: &str) -> Result<PageToken, PageTokenError> {
+        let (offset, fingerprint) = token.split_once('.').ok_or(PageTokenError::Malformed)?;
+        let offset = usize::from_str_radix(offset, 16).map_err(|_| PageTokenError::Malformed)?;
+        let fingerprint =
+            u64::from_str_radix(fingerprint, 16).map_err(|_| PageTokenError::Malformed)?;
+        Ok(PageToken {
+            offset,
+            fingerprint,
+        })
+    }
+}
+
+/// A borrowed window of consecutive entries, returned by `Dictionary::page`
+#[derive(Debug)]
+pub struct DictPage<'a, K, V> {
+    keys: &'a [K],
+    values: &'a [V],
+    start: usize,
+    total: usize,
+}
+
+impl<'a, K: Hash, V> DictPage<'a, K, V> {
+    /// position of the first entry of the page in the dictionary
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// number of entries on the page
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// number of entries in the whole dictionary when the page was taken
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn keys(&self) -> &'a [K] {
+        self.keys
+    }
+
+    pub fn values(&self) -> &'a [V] {
+        self.values
+    }
+
+    pub fn iter(&self) -> Zip<Iter<'a, K>, Iter<'a, V>> {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// true if there are entries after this page
+    pub fn has_next(&self) -> bool {
+        self.start + self.keys.len() < self.total
+    }
+
+    /// a token to continue after this page
+    /// None when the page is empty, there is nothing to continue from
+    pub fn next_token(&self) -> Option<PageToken> {
+        let last = self.keys.last()?;
+        Some(PageToken {
+            offset: self.start + self.keys.len(),
+            fingerprint: fingerprint(last),
+        })
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// borrow page number page_idx, counting from 0, with up to page_size entries
+    /// pages past the end are empty
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict: Dictionary<i32, i32> = (0..5).map(|i| (i, i * 10)).collect();
+    /// let first = dict.page(0, 2);
+    /// assert_eq!(first.keys(), &[0, 1]);
+    /// let token = first.next_token().unwrap();
+    /// dict.push_back(5, 50);
+    /// let second = dict.page_after(token, 10).unwrap();
+    /// assert_eq!(second.keys(), &[2, 3, 4, 5]);
+    /// ```
+    pub fn page(&self, page_idx: usize, page_size: usize) -> DictPage<'_, K, V> {
+        let start = page_idx.saturating_mul(page_size);
+        self.window(start, page_size)
+    }
+
+    /// borrow up to page_size entries starting where the token's page ended
+    pub fn page_after(
+        &self,
+        token: PageToken,
+        page_size: usize,
+    ) -> Result<DictPage<'_, K, V>, PageTokenError> {
+        let last = token
+            .offset
+            .checked_sub(1)
+            .and_then(|i| self.keys().get(i))
+            .ok_or(PageTokenError::Invalidated)?;
+        if fingerprint(last) != token.fingerprint {
+            return Err(PageTokenError::Invalidated);
+        }
+        Ok(self.window(token.offset, page_size))
+    }
+
+    fn window(&self, start: usize, size: usize) -> DictPage<'_, K, V> {
+        let total = self.len();
+        let start = start.min(total);
+        let end = start.saturating_add(size).min(total);
+        DictPage {
+            keys: &self.keys()[start..end],
+            values: &self.values()[start..end],
+            start,
+            total,
+        }
+    }
+}
+
+fn fingerprint<K: Hash>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages() {
+        let dict: Dictionary<i32, i32> = (0..7).map(|i| (i, i)).collect();
+        assert_eq!(dict.page(1, 3).keys(), &[3, 4, 5]);
+        assert_eq!(dict.page(1, 3).start(), 3);
+        assert!(dict.page(1, 3).has_next());
+        let last = dict.page(2, 3);
+        assert_eq!(last.values(), &[6]);
+        assert!(!last.has_next());
+        assert!(dict.page(5, 3).is_empty());
+        assert_eq!(dict.page(5, 3).next_token(), None);
+        assert_eq!(dict.page(usize::MAX, usize::MAX).len(), 0);
+    }
+
+    #[test]
+    fn walk_all_pages_with_tokens() {
+        let dict: Dictionary<i32, i32> = (0..10).map(|i| (i, i)).collect();
+        let mut seen = Vec::new();
+        let mut page = dict.page(0, 4);
+        loop {
+            seen.extend(page.keys().iter().copied());
+            match page.next_token() {
+                Some(token) if page.has_next() => page = dict.page_after(token, 4).unwrap(),
+                _ => break,
+            }
+        }
+        assert_eq!(seen, (0..10).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn token_invalidated_by_removal() {
+        let mut dict: Dictionary<i32, i32> = (0..6).map(|i| (i, i)).collect();
+        let token = dict.page(0, 3).next_token().unwrap();
+        let decoded = PageToken::decode(&token.encode()).unwrap();
+        assert_eq!(decoded, token);
+        dict.remove(0);
+        assert_eq!(
+            dict.page_after(decoded, 3).err(),
+            Some(PageTokenError::Invalidated)
+        );
+        assert_eq!(
+            PageToken::decode("nope").err(),
+            Some(PageTokenError::Malformed)
+        );
+    }
+}
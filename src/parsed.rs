@@ -0,0 +1,105 @@
+use crate::dict::Dictionary;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Error returned by `Dictionary::get_parsed`, naming the key that failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GetParseError {
+    /// the key is not in the dictionary
+    Missing { key: String },
+    /// the value could not be parsed, with the parser's error message
+    Invalid {
+        key: String,
+        value: String,
+        message: String,
+    },
+}
+
+impl Display for GetParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            GetParseError::Missing { key } => write!(f, "missing key {:?}", key),
+            GetParseError::Invalid {
+                key,
+                value,
+                message,
+            } => write!(f, "key {:?}: can not parse {:?}: {}", key, value, message),
+        }
+    }
+}
+
+impl Error for GetParseError {}
+
+impl Dictionary<String, String> {
+    /// parse the value of a key with FromStr
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let env = Dictionary::from_dotenv_str("PORT=8080\nDEBUG=yes\n").unwrap();
+    /// assert_eq!(env.get_parsed::<u16>("PORT"), Ok(8080));
+    /// assert!(env.get_parsed::<bool>("DEBUG").is_err());
+    /// assert_eq!(env.get_parsed_or("WORKERS", 4), Ok(4));
+    /// ```
+    pub fn get_parsed<T>(&self, key: &str) -> Result<T, GetParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.get(key.to_string()) {
+            Some(value) => parse(key, value),
+            None => Err(GetParseError::Missing {
+                key: key.to_string(),
+            }),
+        }
+    }
+
+    /// parse the value of a key, or use the default if the key is missing
+    /// a value that is present but does not parse is still an error
+    pub fn get_parsed_or<T>(&self, key: &str, default: T) -> Result<T, GetParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.get(key.to_string()) {
+            Some(value) => parse(key, value),
+            None => Ok(default),
+        }
+    }
+}
+
+fn parse<T>(key: &str, value: String) -> Result<T, GetParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|e: T::Err| GetParseError::Invalid {
+        key: key.to_string(),
+        message: e.to_string(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors_name_the_key() {
+        let mut config = Dictionary::<String, String>::new();
+        config.push_back("timeout".into(), "3.5".into());
+        config.push_back("retries".into(), "many".into());
+        assert_eq!(config.get_parsed::<f64>("timeout"), Ok(3.5));
+        let err = config.get_parsed::<u32>("retries").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "key \"retries\": can not parse \"many\": invalid digit found in string"
+        );
+        assert_eq!(
+            config.get_parsed::<u32>("port"),
+            Err(GetParseError::Missing { key: "port".into() })
+        );
+        assert!(config.get_parsed_or("retries", 3u32).is_err());
+    }
+}
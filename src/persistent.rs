@@ -0,0 +1,320 @@
+use crate::dict::Dictionary;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+/// the number of entry slots in each shared chunk
+pub const CHUNK_SIZE: usize = 32;
+
+// the index is split into this many shared maps by key hash,
+// a change copies one of them instead of the whole index
+const INDEX_SHARDS: usize = 64;
+
+// a removed entry leaves an empty slot so later positions do not move, empty
+// slots at the back are given up right away and the rest once they outnumber the
+// entries, see remove_in_place
+type Chunk<K, V> = Vec<Option<(K, V)>>;
+
+/// An immutable ordered dictionary where every change returns a new handle
+/// the entries live in Arc backed chunks and the index in Arc backed shards,
+/// a new handle deep copies only the chunk and the index shard the change touched and
+/// shares everything else with the handle it came from. Sharing still copies the
+/// pointer to every chunk and shard, so each change costs about len / CHUNK_SIZE
+/// pointer copies and refcount increments on top of the copied chunk and shard.
+/// Old handles stay valid and unchanged, so keeping a history of snapshots costs
+/// little more than the changes.
+/// Removed entries leave gaps that are reused at the back and renumbered away once
+/// they outnumber the entries, so a handle holds at most about twice its length in slots
+/// # Example
+/// ```
+/// use rust_dict::persistent::PersistentDictionary;
+///
+/// let v1 = PersistentDictionary::new().insert("a", 1).insert("b", 2);
+/// let v2 = v1.insert("c", 3).remove(&"a");
+/// assert_eq!(v1.keys().collect::<Vec<_>>(), vec![&"a", &"b"]);
+/// assert_eq!(v2.keys().collect::<Vec<_>>(), vec![&"b", &"c"]);
+/// assert_eq!(v2.get(&"a"), None);
+/// assert_eq!(v1.get(&"a"), Some(&1));
+/// ```
+pub struct PersistentDictionary<K, V> {
+    chunks: Vec<Arc<Chunk<K, V>>>,
+    // key to slot, the slot of an entry is its chunk * CHUNK_SIZE + offset
+    index: Vec<Arc<HashMap<K, usize>>>,
+    hasher: RandomState,
+    len: usize,
+    // slots handed out so far, removed entries included
+    slots: usize,
+}
+
+impl<K, V> Clone for PersistentDictionary<K, V> {
+    /// a new handle to the same contents, nothing is copied but pointers
+    fn clone(&self) -> Self {
+        PersistentDictionary {
+            chunks: self.chunks.clone(),
+            index: self.index.clone(),
+            hasher: self.hasher.clone(),
+            len: self.len,
+            slots: self.slots,
+        }
+    }
+}
+
+impl<K, V> PersistentDictionary<K, V> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// the entries in order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.chunks
+            .iter()
+            .flat_map(|chunk| chunk.iter())
+            .filter_map(|slot| slot.as_ref().map(|(k, v)| (k, v)))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PersistentDictionary<K, V> {
+    pub fn new() -> PersistentDictionary<K, V> {
+        PersistentDictionary {
+            chunks: Vec::new(),
+            index: (0..INDEX_SHARDS)
+                .map(|_| Arc::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+            len: 0,
+            slots: 0,
+        }
+    }
+
+    /// a new handle with the key set to the value
+    /// a key already present keeps its position, a new key goes to the back
+    pub fn insert(&self, key: K, value: V) -> PersistentDictionary<K, V> {
+        let mut next = self.clone();
+        next.insert_in_place(key, value);
+        next
+    }
+
+    /// a new handle without the key, the other entries keep their order
+    /// returns a handle equal to self if the key is not present
+    pub fn remove(&self, key: &K) -> PersistentDictionary<K, V> {
+        let mut next = self.clone();
+        next.remove_in_place(key);
+        next
+    }
+
+    // Arc::make_mut copies a chunk or shard only while another handle shares it,
+    // so changing a handle nothing else points to copies nothing
+    fn insert_in_place(&mut self, key: K, value: V) {
+        let shard = self.shard_of(&key);
+        if let Some(&slot) = self.index[shard].get(&key) {
+            *self.slot_mut(slot) = Some((key, value));
+            return;
+        }
+        let slot = self.slots;
+        if slot.is_multiple_of(CHUNK_SIZE) {
+            self.chunks.push(Arc::new(Vec::with_capacity(CHUNK_SIZE)));
+        }
+        Arc::make_mut(&mut self.index[shard]).insert(key.clone(), slot);
+        Arc::make_mut(self.chunks.last_mut().unwrap()).push(Some((key, value)));
+        self.slots += 1;
+        self.len += 1;
+    }
+
+    fn remove_in_place(&mut self, key: &K) {
+        let shard = self.shard_of(key);
+        let slot = match self.index[shard].get(key) {
+            Some(&slot) => slot,
+            None => return,
+        };
+        Arc::make_mut(&mut self.index[shard]).remove(key);
+        *self.slot_mut(slot) = None;
+        self.len -= 1;
+        self.trim_back();
+        // renumber once most slots are empty so iteration stays proportional to len
+        if self.slots - self.len > self.len + CHUNK_SIZE {
+            self.renumber();
+        }
+    }
+
+    // give up the empty slots at the back, the next new key takes the first of them
+    fn trim_back(&mut self) {
+        while matches!(
+            self.chunks.last().and_then(|chunk| chunk.last()),
+            Some(None)
+        ) {
+            let chunk = self.chunks.last_mut().unwrap();
+            if chunk.len() == 1 {
+                self.chunks.pop();
+            } else {
+                Arc::make_mut(chunk).pop();
+            }
+            self.slots -= 1;
+        }
+    }
+
+    // move the entries into fresh chunks with no empty slots, keeping the hasher
+    fn renumber(&mut self) {
+        let entries: Vec<(K, V)> = self.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        let mut fresh = PersistentDictionary::new();
+        fresh.hasher = self.hasher.clone();
+        for (key, value) in entries {
+            fresh.insert_in_place(key, value);
+        }
+        *self = fresh;
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let slot = *self.index[self.shard_of(key)].get(key)?;
+        self.chunks[slot / CHUNK_SIZE][slot % CHUNK_SIZE]
+            .as_ref()
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index[self.shard_of(key)].contains_key(key)
+    }
+
+    /// copy the entries into a mutable Dictionary
+    pub fn to_dictionary(&self) -> Dictionary<K, V> {
+        let mut dict = Dictionary::with_capacity(self.len);
+        for (key, value) in self.iter() {
+            dict.push_back(key.clone(), value.clone());
+        }
+        dict
+    }
+
+    fn shard_of(&self, key: &K) -> usize {
+        self.hasher.hash_one(key) as usize % INDEX_SHARDS
+    }
+
+    // the slot in this handle's own copy of its chunk
+    fn slot_mut(&mut self, slot: usize) -> &mut Option<(K, V)> {
+        &mut Arc::make_mut(&mut self.chunks[slot / CHUNK_SIZE])[slot % CHUNK_SIZE]
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone + PartialEq> PartialEq for PersistentDictionary<K, V> {
+    /// the same entries in the same order
+    fn eq(&self, rhs: &Self) -> bool {
+        self.len == rhs.len && self.iter().eq(rhs.iter())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for PersistentDictionary<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for PersistentDictionary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> FromIterator<(K, V)> for PersistentDictionary<K, V> {
+    /// builds the chunks in place, nothing is shared yet so nothing is copied
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> PersistentDictionary<K, V> {
+        let mut dict = PersistentDictionary::new();
+        for (key, value) in iter {
+            dict.insert_in_place(key, value);
+        }
+        dict
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> From<&Dictionary<K, V>> for PersistentDictionary<K, V> {
+    fn from(dict: &Dictionary<K, V>) -> PersistentDictionary<K, V> {
+        dict.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_share_untouched_chunks() {
+        let base: PersistentDictionary<usize, usize> = (0..100).map(|i| (i, i)).collect();
+        let changed = base.insert(5, 50);
+        assert_eq!(base.get(&5), Some(&5));
+        assert_eq!(changed.get(&5), Some(&50));
+        // only the first chunk was copied
+        assert!(!Arc::ptr_eq(&base.chunks[0], &changed.chunks[0]));
+        assert!(Arc::ptr_eq(&base.chunks[1], &changed.chunks[1]));
+        let shared = base
+            .index
+            .iter()
+            .zip(&changed.index)
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count();
+        assert_eq!(shared, INDEX_SHARDS);
+        assert_eq!(changed.to_dictionary().keys(), base.to_dictionary().keys());
+    }
+
+    #[test]
+    fn removal_and_compaction() {
+        let mut dict: PersistentDictionary<u32, u32> = (0..200).map(|i| (i, i)).collect();
+        let history = dict.clone();
+        for i in 0..150 {
+            dict = dict.remove(&i);
+        }
+        assert_eq!(dict.len(), 50);
+        assert!(dict.slots <= 2 * dict.len() + CHUNK_SIZE);
+        assert_eq!(
+            dict.keys().copied().collect::<Vec<u32>>(),
+            (150..200).collect::<Vec<u32>>()
+        );
+        assert_eq!(dict.remove(&7), dict);
+        assert_eq!(history.len(), 200);
+        assert_eq!(history.get(&3), Some(&3));
+        let dict = dict.insert(0, 0);
+        assert_eq!(dict.keys().last(), Some(&0));
+    }
+
+    #[test]
+    fn slots_stay_bounded() {
+        // a sliding window adds at the back and removes from the front
+        let mut window: PersistentDictionary<u32, u32> = (0..10).map(|i| (i, i)).collect();
+        for i in 10..10_000 {
+            window = window.insert(i, i).remove(&(i - 10));
+            assert!(window.slots <= 2 * window.len() + CHUNK_SIZE);
+        }
+        assert_eq!(window.len(), 10);
+        assert!(window.chunks.len() <= 2);
+        assert_eq!(window.keys().next(), Some(&9990));
+
+        // removing and re-adding at the back reuses the slot
+        let mut stack: PersistentDictionary<u32, u32> = (0..40).map(|i| (i, i)).collect();
+        for _ in 0..100 {
+            stack = stack.remove(&39).remove(&38).insert(38, 0).insert(39, 0);
+        }
+        assert_eq!(stack.slots, 40);
+        assert_eq!(stack.chunks.len(), 2);
+        let trimmed = stack
+            .remove(&39)
+            .remove(&38)
+            .remove(&37)
+            .remove(&36)
+            .remove(&35);
+        assert_eq!(trimmed.slots, 35);
+        assert_eq!(stack.get(&39), Some(&0));
+        let emptied = (0..40).fold(stack, |dict, i| dict.remove(&i));
+        assert!(emptied.chunks.is_empty());
+        assert!(emptied.is_empty());
+    }
+}
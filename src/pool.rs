@@ -0,0 +1,119 @@
+use crate::dict::Dictionary;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A shared store of string keys
+/// Dictionaries keyed by `Arc<str>` can intern their keys through one pool so that a key
+/// used by thousands of dictionaries, like a field name in JSON records, is allocated once
+/// and every dictionary holds a pointer to it. The pool only holds its own handle to each
+/// key, `purge` drops keys that no dictionary uses any more.
+/// To share a pool across threads wrap it in a Mutex.
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+/// use rust_dict::pool::KeyPool;
+/// use std::sync::Arc;
+///
+/// let mut pool = KeyPool::new();
+/// let mut a = Dictionary::<Arc<str>, i32>::new();
+/// let mut b = Dictionary::<Arc<str>, i32>::new();
+/// a.push_interned(&mut pool, "id", 1);
+/// b.push_interned(&mut pool, "id", 2);
+/// assert!(Arc::ptr_eq(&a.keys()[0], &b.keys()[0]));
+/// assert_eq!(b.get_interned(&pool, "id"), Some(2));
+/// assert_eq!(pool.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct KeyPool {
+    keys: HashSet<Arc<str>>,
+}
+
+impl KeyPool {
+    pub fn new() -> KeyPool {
+        KeyPool {
+            keys: HashSet::new(),
+        }
+    }
+
+    /// get the shared handle for a key, adding it to the pool if it is new
+    pub fn intern(&mut self, key: &str) -> Arc<str> {
+        if let Some(existing) = self.keys.get(key) {
+            return existing.clone();
+        }
+        let key: Arc<str> = Arc::from(key);
+        self.keys.insert(key.clone());
+        key
+    }
+
+    /// get the shared handle for a key without adding it
+    pub fn lookup(&self, key: &str) -> Option<Arc<str>> {
+        self.keys.get(key).cloned()
+    }
+
+    /// number of distinct keys in the pool
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// drop keys that are only held by the pool, returning how many were removed
+    pub fn purge(&mut self) -> usize {
+        let before = self.keys.len();
+        self.keys.retain(|key| Arc::strong_count(key) > 1);
+        before - self.keys.len()
+    }
+}
+
+impl<V: Clone> Dictionary<Arc<str>, V> {
+    /// push_back with a key interned through the pool
+    /// # Panics
+    /// panics if the dictionary is sealed and the key is new
+    pub fn push_interned(&mut self, pool: &mut KeyPool, key: &str, value: V) -> Option<V> {
+        self.push_back(pool.intern(key), value)
+    }
+
+    /// get by a string key without allocating
+    /// a key that is not in the pool can not be in a dictionary interned through it
+    pub fn get_interned(&self, pool: &KeyPool, key: &str) -> Option<V> {
+        self.get(pool.lookup(key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_shared() {
+        let mut pool = KeyPool::new();
+        let records: Vec<Dictionary<Arc<str>, usize>> = (0..100)
+            .map(|i| {
+                let mut record = Dictionary::new();
+                record.push_interned(&mut pool, "name", i);
+                record.push_interned(&mut pool, "age", i);
+                record
+            })
+            .collect();
+        assert_eq!(pool.len(), 2);
+        // the key order and the index of every record hold one each, plus the pool
+        assert_eq!(Arc::strong_count(&records[0].keys()[0]), 201);
+        assert_eq!(records[7].get_interned(&pool, "age"), Some(7));
+        assert_eq!(records[7].get_interned(&pool, "missing"), None);
+    }
+
+    #[test]
+    fn purge_unused_keys() {
+        let mut pool = KeyPool::new();
+        let mut dict = Dictionary::<Arc<str>, i32>::new();
+        dict.push_interned(&mut pool, "kept", 1);
+        pool.intern("dropped");
+        assert_eq!(pool.purge(), 1);
+        assert_eq!(pool.len(), 1);
+        drop(dict);
+        assert_eq!(pool.purge(), 1);
+        assert!(pool.is_empty());
+    }
+}
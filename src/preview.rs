@@ -0,0 +1,155 @@
+use crate::dict::Dictionary;
+use crate::format::EntryWriter;
+use crate::index::BuildIndex;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::ops::Range;
+
+/// dictionaries with more entries than this Debug print as a preview
+pub const DEBUG_FULL_LIMIT: usize = 100;
+
+/// the number of entries from each end a Debug preview shows
+pub const DEBUG_PREVIEW_ENTRIES: usize = 5;
+
+/// A formatting proxy that shows only the ends of a Dictionary
+/// created with `Dictionary::preview`, the first and last n entries are printed
+/// with the number left out and the total in between, so formatting the proxy
+/// costs the same for a dictionary of any size.
+pub struct Preview<'a, K, V, S: BuildIndex<K>> {
+    dict: &'a Dictionary<K, V, S>,
+    n: usize,
+}
+
+impl<'a, K, V, S: BuildIndex<K>> Preview<'a, K, V, S> {
+    // the entries to print at the front and at the back
+    // the back is empty when everything fits in the front
+    fn ends(&self) -> (usize, usize) {
+        let len = self.dict.keys().len();
+        if len <= self.n.saturating_mul(2) {
+            (len, 0)
+        } else {
+            (self.n, self.n)
+        }
+    }
+
+    fn skipped(&self) -> usize {
+        let (front, back) = self.ends();
+        self.dict.keys().len() - front - back
+    }
+
+    fn entry(&self, i: usize) -> (&'a K, &'a V) {
+        let dict: &'a Dictionary<K, V, S> = self.dict;
+        (&dict.keys()[i], &dict.values()[i])
+    }
+
+    // the positions of the front entries and of the back entries
+    fn positions(&self) -> (Range<usize>, Range<usize>) {
+        let len = self.dict.keys().len();
+        let (front, back) = self.ends();
+        (0..front, len - back..len)
+    }
+}
+
+impl<'a, K: Display, V: Display, S: BuildIndex<K>> Display for Preview<'a, K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // mirrors the Display of Dictionary
+        let (front, back) = self.positions();
+        let mut out = EntryWriter::new(f)?;
+        for i in front {
+            let (key, value) = self.entry(i);
+            out.entry(key, value)?;
+        }
+        if self.skipped() > 0 {
+            out.note(&format!(
+                "... {} more, {} entries total",
+                self.skipped(),
+                self.dict.keys().len()
+            ))?;
+        }
+        for i in back {
+            let (key, value) = self.entry(i);
+            out.entry(key, value)?;
+        }
+        out.finish()
+    }
+}
+
+impl<'a, K: Debug, V: Debug, S: BuildIndex<K>> Debug for Preview<'a, K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let (front, back) = self.positions();
+        let mut parts: Vec<String> = front
+            .map(|i| format!("{:?}: {:?}", self.entry(i).0, self.entry(i).1))
+            .collect();
+        if self.skipped() > 0 {
+            parts.push(format!(
+                "... {} more, {} entries total",
+                self.skipped(),
+                self.dict.keys().len()
+            ));
+        }
+        parts.extend(back.map(|i| format!("{:?}: {:?}", self.entry(i).0, self.entry(i).1)));
+        write!(f, "{{{}}}", parts.join(", "))
+    }
+}
+
+impl<K, V, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// format only the first and last n entries
+    /// safe to log for a dictionary of any size, Debug uses it above DEBUG_FULL_LIMIT entries
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict: Dictionary<i32, i32> = (0..1000).map(|i| (i, i * 2)).collect();
+    /// assert_eq!(
+    ///     dict.preview(2).to_string(),
+    ///     "{0: 0, 1: 2, ... 996 more, 1000 entries total, 998: 1996, 999: 1998}"
+    /// );
+    /// assert_eq!(
+    ///     format!("{:?}", dict.preview(1)),
+    ///     "{0: 0, ... 998 more, 1000 entries total, 999: 1998}"
+    /// );
+    /// ```
+    pub fn preview(&self, n: usize) -> Preview<'_, K, V, S> {
+        Preview { dict: self, n }
+    }
+}
+
+impl<K: Debug, V: Debug, S: BuildIndex<K>> Debug for Dictionary<K, V, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.keys().len() > DEBUG_FULL_LIMIT {
+            return Debug::fmt(&self.preview(DEBUG_PREVIEW_ENTRIES), f);
+        }
+        f.debug_map()
+            .entries(self.keys().iter().zip(self.values().iter()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_previews_show_everything() {
+        let dict = Dictionary::from([("a", 1), ("b", 2), ("c", 3)]);
+        assert_eq!(dict.preview(2).to_string(), "{a: 1, b: 2, c: 3}");
+        assert_eq!(
+            format!("{:#}", dict.preview(1)),
+            "{\n    a: 1,\n    ... 1 more, 3 entries total,\n    c: 3,\n}"
+        );
+        assert_eq!(format!("{:?}", dict), r#"{"a": 1, "b": 2, "c": 3}"#);
+        assert_eq!(
+            format!("{:?}", dict.preview(0)),
+            "{... 3 more, 3 entries total}"
+        );
+        let empty: Dictionary<i32, i32> = Dictionary::new();
+        assert_eq!(empty.preview(3).to_string(), "{}");
+    }
+
+    #[test]
+    fn large_debug_is_truncated() {
+        let dict: Dictionary<u32, u32> = (0..100_000).map(|i| (i, i)).collect();
+        let output = format!("{:?}", dict);
+        assert!(output.starts_with("{0: 0, 1: 1, 2: 2, 3: 3, 4: 4, ... 99990 more"));
+        assert!(output.ends_with("99999: 99999}"));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::dict::Dictionary;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+/// A deque view over a Dictionary, returned by `Dictionary::as_queue`
+/// it only exposes the operations at the two ends, so code written against it
+/// reads like VecDeque code while keeping key lookups and upserts
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+///
+/// let mut jobs = Dictionary::<&str, u32>::new();
+/// let mut queue = jobs.as_queue();
+/// queue.push_back("build", 1);
+/// queue.push_back("test", 2);
+/// queue.push_front("fetch", 0);
+/// assert_eq!(queue.pop_front(), Some(("fetch", 0)));
+/// assert_eq!(queue.back(), Some((&"test", &2)));
+/// assert_eq!(jobs.keys(), &vec!["build", "test"]);
+/// ```
+pub struct DictQueue<'a, K: Hash + Eq, V> {
+    dict: &'a mut Dictionary<K, V>,
+}
+
+impl<'a, K: Hash + Eq + Clone, V: Clone> DictQueue<'a, K, V> {
+    /// add to the back, an existing key keeps its place and gets the new value
+    pub fn push_back(&mut self, key: K, value: V) -> Option<V> {
+        self.dict.push_back(key, value)
+    }
+
+    /// add to the front, an existing key keeps its place and gets the new value
+    pub fn push_front(&mut self, key: K, value: V) -> Option<V> {
+        self.dict.push_front(key, value)
+    }
+
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        self.dict.pop_front()
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        self.dict.pop_back()
+    }
+
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.dict.front()
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.dict.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
+    /// borrow the dictionary as a double ended queue
+    pub fn as_queue(&mut self) -> DictQueue<'_, K, V> {
+        DictQueue { dict: self }
+    }
+}
+
+/// the pairs in order, front to back
+impl<K: Hash + Eq, V> From<Dictionary<K, V>> for VecDeque<(K, V)> {
+    fn from(dict: Dictionary<K, V>) -> VecDeque<(K, V)> {
+        dict.into_iter().collect()
+    }
+}
+
+/// later pairs with a repeated key replace the value but keep the first position
+impl<K: Hash + Eq + Clone, V: Clone> From<VecDeque<(K, V)>> for Dictionary<K, V> {
+    fn from(queue: VecDeque<(K, V)>) -> Dictionary<K, V> {
+        queue.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_front_keeps_index() {
+        let mut dict = Dictionary::from([(3, 'c'), (4, 'd')]);
+        dict.push_front(2, 'b');
+        dict.push_front(1, 'a');
+        assert_eq!(dict.keys(), &vec![1, 2, 3, 4]);
+        assert_eq!(dict.index_of(&4), Some(3));
+        assert_eq!(dict.get(2), Some('b'));
+        // existing keys are updated in place
+        assert_eq!(dict.push_front(3, 'C'), Some('c'));
+        assert_eq!(dict.index_of(&3), Some(2));
+        assert_eq!(dict.pop_front(), Some((1, 'a')));
+        assert_eq!(dict.index_of(&4), Some(2));
+    }
+
+    #[test]
+    fn vec_deque_round_trip() {
+        let dict = Dictionary::from([(1, 'a'), (2, 'b')]);
+        let queue = VecDeque::from(dict.clone());
+        assert_eq!(queue.front(), Some(&(1, 'a')));
+        assert_eq!(Dictionary::from(queue), dict);
+        assert_eq!(Dictionary::<i32, i32>::new().front(), None);
+    }
+}
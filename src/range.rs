@@ -0,0 +1,118 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::hash::Hash;
+use std::iter::Zip;
+use std::ops::{Bound, Range, RangeBounds};
+use std::slice::Iter;
+
+/// Iterator over the entries with keys in a range, returned by `Dictionary::range`
+pub struct DictRange<'a, K, V, R> {
+    iter: Zip<Iter<'a, K>, Iter<'a, V>>,
+    range: R,
+    // the positions found by binary search, every entry in them is in range
+    // None when the keys are not sorted and each entry is checked
+    window: Option<Range<usize>>,
+}
+
+impl<'a, K, V, R> DictRange<'a, K, V, R> {
+    /// the contiguous positions of the range in the dictionary
+    /// Some when the keys were sorted and the bounds were found by binary search,
+    /// None when the range is found by scanning
+    pub fn positions(&self) -> Option<Range<usize>> {
+        self.window.clone()
+    }
+}
+
+impl<'a, K: Ord, V, R: RangeBounds<K>> Iterator for DictRange<'a, K, V, R> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, value) = self.iter.next()?;
+            if self.window.is_some() || self.range.contains(key) {
+                return Some((key, value));
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone + Ord, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// iterate over the entries whose keys fall in the range, in dictionary order
+    /// right after sort_by_keys the entries are found with a binary search in O(log n),
+    /// otherwise every entry is checked against the range
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut dict = Dictionary::from([(5, 'e'), (1, 'a'), (3, 'c'), (2, 'b')]);
+    /// dict.sort_by_keys();
+    /// let keys: Vec<&i32> = dict.range(2..5).map(|(k, _)| k).collect();
+    /// assert_eq!(keys, vec![&2, &3]);
+    /// assert_eq!(dict.range(2..5).positions(), Some(1..3));
+    /// ```
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> DictRange<'_, K, V, R> {
+        let keys = self.keys();
+        let values = self.values();
+        if !self.is_sorted() {
+            return DictRange {
+                iter: keys.iter().zip(values.iter()),
+                range,
+                window: None,
+            };
+        }
+        let start = match range.start_bound() {
+            Bound::Included(a) => keys.partition_point(|k| k < a),
+            Bound::Excluded(a) => keys.partition_point(|k| k <= a),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(b) => keys.partition_point(|k| k <= b),
+            Bound::Excluded(b) => keys.partition_point(|k| k < b),
+            Bound::Unbounded => keys.len(),
+        };
+        // a range that ends before it starts is empty
+        let end = end.max(start);
+        DictRange {
+            iter: keys[start..end].iter().zip(values[start..end].iter()),
+            range,
+            window: Some(start..end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys<'a, I: Iterator<Item = (&'a i32, &'a i32)>>(iter: I) -> Vec<i32> {
+        iter.map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn sorted_and_scanned_ranges_agree() {
+        let mut dict: Dictionary<i32, i32> = [9, 2, 7, 4, 1, 8].iter().map(|&k| (k, k)).collect();
+        assert!(!dict.is_sorted());
+        assert_eq!(keys(dict.range(2..8)), vec![2, 7, 4]);
+        dict.sort_by_keys();
+        assert!(dict.is_sorted());
+        assert_eq!(keys(dict.range(2..8)), vec![2, 4, 7]);
+        assert_eq!(dict.range(2..8).positions(), Some(1..4));
+        assert_eq!(keys(dict.range(2..=8)), vec![2, 4, 7, 8]);
+        assert_eq!(keys(dict.range(..3)), vec![1, 2]);
+        assert_eq!(keys(dict.range(8..)), vec![8, 9]);
+        assert_eq!(
+            keys(dict.range((Bound::Excluded(4), Bound::Unbounded))),
+            vec![7, 8, 9]
+        );
+        assert!(keys(dict.range((Bound::Included(8), Bound::Excluded(3)))).is_empty());
+    }
+
+    #[test]
+    fn mutation_clears_sorted() {
+        let mut dict = Dictionary::from([(2, 0), (1, 0)]);
+        dict.sort_by_keys();
+        dict.push_back(0, 0);
+        assert!(!dict.is_sorted());
+        assert_eq!(keys(dict.range(..2)), vec![1, 0]);
+        assert_eq!(dict.range(..2).positions(), None);
+    }
+}
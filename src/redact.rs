@@ -0,0 +1,120 @@
+use crate::dict::Dictionary;
+use crate::format::EntryWriter;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+/// the text printed in place of a redacted value
+pub const REDACTED: &str = "***";
+
+/// A formatting proxy over a Dictionary that masks the values of matching keys
+/// created with `Dictionary::display_redacted`
+/// both Display and Debug go through the mask so the proxy is safe to hand to a logger
+pub struct Redacted<'a, K: Hash + Eq, V, F> {
+    dict: &'a Dictionary<K, V>,
+    should_redact: F,
+    max_value_width: Option<usize>,
+}
+
+impl<'a, K: Hash + Eq, V, F> Redacted<'a, K, V, F> {
+    /// truncate printed values to at most `width` characters
+    /// truncated values end in `...`
+    pub fn max_value_width(mut self, width: usize) -> Self {
+        self.max_value_width = Some(width);
+        self
+    }
+
+    fn render(&self, value: String) -> String {
+        match self.max_value_width {
+            Some(width) if value.chars().count() > width => {
+                let mut truncated: String = value.chars().take(width).collect();
+                truncated.push_str("...");
+                truncated
+            }
+            _ => value,
+        }
+    }
+}
+
+impl<'a, K, V, F> Display for Redacted<'a, K, V, F>
+where
+    K: Hash + Eq + Clone + Display,
+    V: Clone + Display,
+    F: Fn(&K) -> bool,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // mirrors the Display of Dictionary
+        let mut out = EntryWriter::new(f)?;
+        for (key, val) in self.dict.iter() {
+            let val = if (self.should_redact)(key) {
+                REDACTED.to_string()
+            } else {
+                self.render(val.to_string())
+            };
+            out.entry(key, &val)?;
+        }
+        out.finish()
+    }
+}
+
+impl<'a, K, V, F> Debug for Redacted<'a, K, V, F>
+where
+    K: Hash + Eq + Clone + Debug,
+    V: Clone + Debug,
+    F: Fn(&K) -> bool,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (key, val) in self.dict.iter() {
+            if (self.should_redact)(key) {
+                map.entry(key, &format_args!("{}", REDACTED));
+            } else {
+                map.entry(key, &format_args!("{}", self.render(format!("{:?}", val))));
+            }
+        }
+        map.finish()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Dictionary<K, V> {
+    /// Format the dictionary with the values of some keys masked
+    /// the predicate is called with each key, values of keys it returns true for print as `***`
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let mut config = Dictionary::<String, String>::new();
+    /// config.push_back("user".into(), "admin".into());
+    /// config.push_back("db_secret".into(), "hunter2".into());
+    /// let output = config.display_redacted(|k| k.ends_with("_secret")).to_string();
+    /// assert_eq!(output, "{user: admin, db_secret: ***}");
+    /// ```
+    pub fn display_redacted<F: Fn(&K) -> bool>(&self, should_redact: F) -> Redacted<'_, K, V, F> {
+        Redacted {
+            dict: self,
+            should_redact,
+            max_value_width: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted_debug() {
+        let dict = Dictionary::from([("token", "abc"), ("name", "me")]);
+        let output = format!("{:?}", dict.display_redacted(|k| *k == "token"));
+        assert_eq!(output, r#"{"token": ***, "name": "me"}"#);
+    }
+
+    #[test]
+    fn redacted_truncation() {
+        let dict = Dictionary::from([(1, "a long value"), (2, "short")]);
+        let output = dict
+            .display_redacted(|k| *k == 3)
+            .max_value_width(6)
+            .to_string();
+        assert_eq!(output, "{1: a long..., 2: short}");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::concurrent::ConcurrentDictionary;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A handle to a dictionary shared across threads
+pub type SharedDict<K, V> = Arc<ConcurrentDictionary<K, V>>;
+
+// one entry per name and value type, the box holds a SharedDict<String, V>
+type Registry = HashMap<(String, TypeId), Box<dyn Any + Send + Sync>>;
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// the process wide dictionary with this name, created empty on first use
+/// every call with the same name and value type returns a handle to the same
+/// dictionary, the same name with another value type is a separate dictionary.
+/// The registry lock is only held to look the handle up, reads and writes go
+/// through the handle's own shard locks
+/// # Example
+/// ```
+/// use rust_dict::registry;
+///
+/// registry::global::<u64>("doc_counters").push_back("requests".into(), 1);
+/// let counters = registry::global::<u64>("doc_counters");
+/// counters.update(&"requests".into(), |n| *n += 1);
+/// assert_eq!(counters.get(&"requests".into()), Some(2));
+/// assert!(registry::global::<String>("doc_counters").is_empty());
+/// ```
+pub fn global<V: Clone + Send + Sync + 'static>(name: &str) -> SharedDict<String, V> {
+    let key = (name.to_string(), TypeId::of::<V>());
+    // a panic can not happen while the lock is held, so a poisoned lock is still sound
+    let mut registry = registry().lock().unwrap_or_else(|p| p.into_inner());
+    let entry = registry.entry(key).or_insert_with(|| {
+        let dict: SharedDict<String, V> = Arc::new(ConcurrentDictionary::new());
+        Box::new(dict)
+    });
+    Arc::clone(entry.downcast_ref::<SharedDict<String, V>>().unwrap())
+}
+
+/// drop the registry's handle to the named dictionary and return it
+/// handles already given out keep working, the next `global` call creates a new one
+pub fn forget<V: Clone + Send + Sync + 'static>(name: &str) -> Option<SharedDict<String, V>> {
+    let key = (name.to_string(), TypeId::of::<V>());
+    let mut registry = registry().lock().unwrap_or_else(|p| p.into_inner());
+    let entry = registry.remove(&key)?;
+    entry
+        .downcast::<SharedDict<String, V>>()
+        .ok()
+        .map(|dict| *dict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn same_name_same_dictionary() {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                thread::spawn(move || global::<i32>("test_shared").push_back(i.to_string(), i))
+            })
+            .collect();
+        handles.into_iter().for_each(|h| {
+            h.join().unwrap();
+        });
+        assert_eq!(global::<i32>("test_shared").len(), 8);
+        assert!(Arc::ptr_eq(
+            &global::<i32>("test_shared"),
+            &global::<i32>("test_shared")
+        ));
+        assert!(global::<u8>("test_shared").is_empty());
+    }
+
+    #[test]
+    fn forget_starts_over() {
+        let old = global::<&'static str>("test_forget");
+        old.push_back("a".into(), "x");
+        let forgotten = forget::<&'static str>("test_forget").unwrap();
+        assert!(Arc::ptr_eq(&old, &forgotten));
+        assert_eq!(old.get(&"a".into()), Some("x"));
+        assert!(global::<&'static str>("test_forget").is_empty());
+        assert!(forget::<bool>("test_forget").is_none());
+    }
+}
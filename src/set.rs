@@ -0,0 +1,307 @@
+use crate::dict::{DictIntoIter, Dictionary};
+use std::fmt::{self, Debug, Formatter};
+use std::hash::Hash;
+use std::iter::FusedIterator;
+use std::slice::Iter;
+
+/// An insertion ordered set that can be indexed
+/// a Dictionary with no values, items keep the position they were first inserted at
+/// # Example
+/// ```
+/// use rust_dict::set::OrderedSet;
+///
+/// let mut seen = OrderedSet::new();
+/// assert!(seen.insert("b"));
+/// assert!(seen.insert("a"));
+/// assert!(!seen.insert("b"));
+/// assert_eq!(seen.as_slice(), &["b", "a"]);
+/// assert_eq!(seen.get_index(1), Some(&"a"));
+/// ```
+#[derive(Clone)]
+pub struct OrderedSet<T: Hash + Eq> {
+    items: Dictionary<T, ()>,
+}
+
+impl<T: Hash + Eq + Clone> OrderedSet<T> {
+    pub fn new() -> OrderedSet<T> {
+        OrderedSet {
+            items: Dictionary::new(),
+        }
+    }
+
+    pub fn with_capacity(size: usize) -> OrderedSet<T> {
+        OrderedSet {
+            items: Dictionary::with_capacity(size),
+        }
+    }
+
+    /// add an item to the back, false if it was already in the set
+    /// an item already in the set keeps its position
+    pub fn insert(&mut self, item: T) -> bool {
+        self.items.push_back(item, ()).is_none()
+    }
+
+    /// add an item to the front, false if it was already in the set
+    pub fn insert_front(&mut self, item: T) -> bool {
+        self.items.push_front(item, ()).is_none()
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.items.contains_key(item)
+    }
+
+    /// remove an item, shifting later items down by one
+    pub fn remove(&mut self, item: &T) -> bool {
+        self.items.remove(item.clone()).is_some()
+    }
+
+    /// remove an item by swapping the last item into its position, O(1)
+    pub fn swap_remove(&mut self, item: &T) -> bool {
+        self.items.swap_remove(item.clone()).is_some()
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.items.pop_back().map(|(item, _)| item)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.items.pop_front().map(|(item, _)| item)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.items.keys().first()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.items.keys().last()
+    }
+
+    pub fn get_index(&self, i: usize) -> Option<&T> {
+        self.items.keys().get(i)
+    }
+
+    pub fn index_of(&self, item: &T) -> Option<usize> {
+        self.items.index_of(item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// the items in order
+    pub fn as_slice(&self) -> &[T] {
+        self.items.keys()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.keys().iter()
+    }
+
+    /// keep only the items the predicate returns true for
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.items.retain(|item, _| keep(item));
+    }
+
+    pub fn reverse(&mut self) {
+        self.items.reverse();
+    }
+
+    /// stable sort of the items with a comparator
+    pub fn sort_by<F: FnMut(&T, &T) -> std::cmp::Ordering>(&mut self, mut compare: F) {
+        self.items.sort_by(|(a, _), (b, _)| compare(a, b));
+    }
+
+    /// stable sort of the items by a key extracted from each
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.items.sort_by_key(|item, _| f(item));
+    }
+
+    /// true if every item in self is also in other
+    pub fn is_subset(&self, other: &OrderedSet<T>) -> bool {
+        self.items.is_subset_of(&other.items)
+    }
+
+    /// true if every item in other is also in self
+    pub fn is_superset(&self, other: &OrderedSet<T>) -> bool {
+        self.items.is_superset_of(&other.items)
+    }
+
+    /// true if the sets have no items in common
+    pub fn is_disjoint(&self, other: &OrderedSet<T>) -> bool {
+        self.iter().all(|item| !other.contains(item))
+    }
+
+    /// the items of self followed by the items of other that are not in self
+    /// # Example
+    /// ```
+    /// use rust_dict::set::OrderedSet;
+    ///
+    /// let a: OrderedSet<i32> = [3, 1, 2].into_iter().collect();
+    /// let b: OrderedSet<i32> = [2, 4, 3].into_iter().collect();
+    /// assert_eq!(a.union(&b).as_slice(), &[3, 1, 2, 4]);
+    /// assert_eq!(a.intersection(&b).as_slice(), &[3, 2]);
+    /// assert_eq!(a.difference(&b).as_slice(), &[1]);
+    /// assert_eq!(a.symmetric_difference(&b).as_slice(), &[1, 4]);
+    /// ```
+    pub fn union(&self, other: &OrderedSet<T>) -> OrderedSet<T> {
+        let mut union = self.clone();
+        union.extend(other.iter().cloned());
+        union
+    }
+
+    /// the items of self that are also in other, in the order of self
+    pub fn intersection(&self, other: &OrderedSet<T>) -> OrderedSet<T> {
+        self.iter()
+            .filter(|item| other.contains(item))
+            .cloned()
+            .collect()
+    }
+
+    /// the items of self that are not in other, in the order of self
+    pub fn difference(&self, other: &OrderedSet<T>) -> OrderedSet<T> {
+        self.iter()
+            .filter(|item| !other.contains(item))
+            .cloned()
+            .collect()
+    }
+
+    /// the items in exactly one of the sets, those of self first
+    pub fn symmetric_difference(&self, other: &OrderedSet<T>) -> OrderedSet<T> {
+        let mut difference = self.difference(other);
+        difference.extend(other.iter().filter(|item| !self.contains(item)).cloned());
+        difference
+    }
+}
+
+impl<T: Hash + Eq + Clone + Ord> OrderedSet<T> {
+    /// sort the items in ascending order
+    pub fn sort(&mut self) {
+        self.items.sort_by_keys();
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for OrderedSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone> PartialEq for OrderedSet<T> {
+    /// sets with the same items in the same order are equal
+    fn eq(&self, rhs: &Self) -> bool {
+        self.items == rhs.items
+    }
+}
+
+impl<T: Hash + Eq + Clone + Debug> Debug for OrderedSet<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.items.keys()).finish()
+    }
+}
+
+impl<T: Hash + Eq + Clone> FromIterator<T> for OrderedSet<T> {
+    /// repeated items keep their first position
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> OrderedSet<T> {
+        let mut set = OrderedSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Hash + Eq + Clone> Extend<T> for OrderedSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter.into_iter().map(|item| (item, ())));
+    }
+}
+
+impl<T: Hash + Eq + Clone, const N: usize> From<[T; N]> for OrderedSet<T> {
+    fn from(items: [T; N]) -> OrderedSet<T> {
+        items.into_iter().collect()
+    }
+}
+
+/// An owned iterator over the items of an OrderedSet, in order
+pub struct SetIntoIter<T> {
+    iter: DictIntoIter<T, ()>,
+}
+
+impl<T> Iterator for SetIntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|(item, _)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for SetIntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|(item, _)| item)
+    }
+}
+
+impl<T> ExactSizeIterator for SetIntoIter<T> {}
+
+impl<T> FusedIterator for SetIntoIter<T> {}
+
+impl<T: Hash + Eq> IntoIterator for OrderedSet<T> {
+    type Item = T;
+    type IntoIter = SetIntoIter<T>;
+    fn into_iter(self) -> SetIntoIter<T> {
+        SetIntoIter {
+            iter: self.items.into_iter(),
+        }
+    }
+}
+
+impl<'a, T: Hash + Eq + Clone> IntoIterator for &'a OrderedSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.items.keys().iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_remove_positions() {
+        let mut set = OrderedSet::from([5, 3, 8, 3]);
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.index_of(&8), Some(2));
+        assert!(set.remove(&5));
+        assert!(!set.remove(&5));
+        assert_eq!(set.as_slice(), &[3, 8]);
+        assert!(set.insert_front(1));
+        assert_eq!(set.first(), Some(&1));
+        assert!(set.swap_remove(&1));
+        assert_eq!(set.as_slice(), &[8, 3]);
+        set.sort();
+        assert_eq!(set.into_iter().collect::<Vec<i32>>(), vec![3, 8]);
+    }
+
+    #[test]
+    fn set_relations() {
+        let a = OrderedSet::from(["x", "y"]);
+        let b = OrderedSet::from(["y", "x", "z"]);
+        assert!(a.is_subset(&b));
+        assert!(b.is_superset(&a));
+        assert!(a.is_disjoint(&OrderedSet::from(["w"])));
+        assert_eq!(format!("{:?}", a), r#"{"x", "y"}"#);
+        let mut c = b.clone();
+        c.sort_by_key(|s| std::cmp::Reverse(*s));
+        assert_eq!(c.as_slice(), &["z", "y", "x"]);
+    }
+}
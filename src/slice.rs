@@ -0,0 +1,203 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::fmt::{self, Debug, Formatter};
+use std::iter::Zip;
+use std::ops::{Bound, Range, RangeBounds};
+use std::slice::Iter;
+
+/// A borrowed run of consecutive entries, created with `Dictionary::as_slice` or `get_range`
+/// like `indexmap::map::Slice`, positions count from the start of the slice and
+/// slicing again borrows a narrower window of the same entries, nothing is copied.
+/// The slice has no index of its own, look keys up with `binary_search_keys`
+/// on sorted keys or in the dictionary it came from
+pub struct DictSlice<'a, K, V> {
+    keys: &'a [K],
+    values: &'a [V],
+}
+
+impl<'a, K, V> Clone for DictSlice<'a, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, K, V> Copy for DictSlice<'a, K, V> {}
+
+impl<'a, K, V> DictSlice<'a, K, V> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> &'a [K] {
+        self.keys
+    }
+
+    pub fn values(&self) -> &'a [V] {
+        self.values
+    }
+
+    pub fn iter(&self) -> Zip<Iter<'a, K>, Iter<'a, V>> {
+        self.keys.iter().zip(self.values.iter())
+    }
+
+    /// the key and value at a position in the slice, borrowed rather than cloned
+    pub fn get_index(&self, i: usize) -> Option<(&'a K, &'a V)> {
+        Some((self.keys.get(i)?, self.values.get(i)?))
+    }
+
+    pub fn first(&self) -> Option<(&'a K, &'a V)> {
+        self.get_index(0)
+    }
+
+    pub fn last(&self) -> Option<(&'a K, &'a V)> {
+        self.get_index(self.len().checked_sub(1)?)
+    }
+
+    /// a narrower window, positions are relative to this slice
+    /// None if the range is out of bounds or ends before it starts
+    pub fn get_range<R: RangeBounds<usize>>(&self, range: R) -> Option<DictSlice<'a, K, V>> {
+        let range = to_range(range, self.len())?;
+        Some(DictSlice {
+            keys: &self.keys[range.clone()],
+            values: &self.values[range],
+        })
+    }
+
+    /// the entries before position mid and the entries from it on
+    /// # Panics
+    /// panics if mid is greater than the length
+    pub fn split_at(&self, mid: usize) -> (DictSlice<'a, K, V>, DictSlice<'a, K, V>) {
+        let (front_keys, back_keys) = self.keys.split_at(mid);
+        let (front_values, back_values) = self.values.split_at(mid);
+        (
+            DictSlice {
+                keys: front_keys,
+                values: front_values,
+            },
+            DictSlice {
+                keys: back_keys,
+                values: back_values,
+            },
+        )
+    }
+}
+
+impl<'a, K: Ord, V> DictSlice<'a, K, V> {
+    /// binary search sorted keys, like `slice::binary_search`
+    /// Ok with the position of the key, or Err with the position it would be inserted at.
+    /// The result is meaningless if the keys of the slice are not sorted
+    pub fn binary_search_keys(&self, key: &K) -> Result<usize, usize> {
+        self.keys.binary_search(key)
+    }
+}
+
+impl<'a, K, V> IntoIterator for DictSlice<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Zip<Iter<'a, K>, Iter<'a, V>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: PartialEq, V: PartialEq> PartialEq for DictSlice<'a, K, V> {
+    /// the same entries in the same order
+    fn eq(&self, rhs: &Self) -> bool {
+        self.keys == rhs.keys && self.values == rhs.values
+    }
+}
+
+impl<'a, K: Debug, V: Debug> Debug for DictSlice<'a, K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// the positions a range covers in a run of len entries, None if it does not fit
+fn to_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&a) => a,
+        Bound::Excluded(&a) => a.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&b) => b.checked_add(1)?,
+        Bound::Excluded(&b) => b,
+        Bound::Unbounded => len,
+    };
+    if start <= end && end <= len {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+impl<K, V, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// borrow every entry as a DictSlice
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// let middle = dict.get_range(1..3).unwrap();
+    /// assert_eq!(middle.keys(), &["b", "c"]);
+    /// assert_eq!(middle.first(), Some((&"b", &2)));
+    /// assert_eq!(middle.get_range(1..).unwrap().get_index(0), Some((&"c", &3)));
+    /// assert_eq!(dict.as_slice().binary_search_keys(&"c"), Ok(2));
+    /// assert!(dict.get_range(3..9).is_none());
+    /// ```
+    pub fn as_slice(&self) -> DictSlice<'_, K, V> {
+        DictSlice {
+            keys: self.keys(),
+            values: self.values(),
+        }
+    }
+
+    /// borrow the entries at a range of positions as a DictSlice
+    /// None if the range is out of bounds or ends before it starts
+    pub fn get_range<R: RangeBounds<usize>>(&self, range: R) -> Option<DictSlice<'_, K, V>> {
+        self.as_slice().get_range(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subslices_share_positions() {
+        let dict: Dictionary<u32, char> = (0..10).zip('a'..).collect();
+        let slice = dict.get_range(2..=7).unwrap();
+        assert_eq!(slice.len(), 6);
+        assert_eq!(slice.last(), Some((&7, &'h')));
+        let inner = slice.get_range(..2).unwrap();
+        assert_eq!(
+            inner.iter().map(|(k, _)| *k).collect::<Vec<u32>>(),
+            vec![2, 3]
+        );
+        let (front, back) = slice.split_at(4);
+        assert_eq!(front.len() + back.len(), slice.len());
+        assert_eq!(back.first(), Some((&6, &'g')));
+        assert_eq!(slice.binary_search_keys(&5), Ok(3));
+        assert_eq!(slice.binary_search_keys(&9), Err(6));
+        assert_eq!(format!("{:?}", inner), "{2: 'c', 3: 'd'}");
+    }
+
+    #[test]
+    fn out_of_bounds_ranges() {
+        let dict = Dictionary::from([(1, 1), (2, 2)]);
+        assert!(dict.get_range(0..3).is_none());
+        assert!(dict.get_range(..=2).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let backwards = dict.get_range(2..1);
+        assert!(backwards.is_none());
+        let empty = dict.get_range(2..).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+        assert_eq!(dict.get_range(..), Some(dict.as_slice()));
+    }
+}
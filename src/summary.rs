@@ -0,0 +1,130 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::mem::size_of;
+
+/// A quick overview of the contents of a Dictionary
+/// returned by `Dictionary::summary`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictSummary<K, V> {
+    /// number of key value pairs
+    pub len: usize,
+    /// number of pairs the dictionary can hold before resizing
+    pub capacity: usize,
+    /// number of unique values
+    pub distinct_values: usize,
+    /// smallest key, None when empty
+    pub min_key: Option<K>,
+    /// largest key, None when empty
+    pub max_key: Option<K>,
+    /// the most frequent value with its count
+    /// ties go to the value that appears first
+    pub most_common: Option<(V, usize)>,
+    /// rough size in bytes of the dictionary storage
+    /// this only counts the inline size of K and V, not anything they own on the heap
+    pub memory_estimate: usize,
+}
+
+impl<K: Display, V: Display> Display for DictSummary<K, V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "len: {}", self.len)?;
+        writeln!(f, "capacity: {}", self.capacity)?;
+        writeln!(f, "distinct values: {}", self.distinct_values)?;
+        match (&self.min_key, &self.max_key) {
+            (Some(min), Some(max)) => {
+                writeln!(f, "min key: {}", min)?;
+                writeln!(f, "max key: {}", max)?;
+            }
+            _ => {
+                writeln!(f, "min key: -")?;
+                writeln!(f, "max key: -")?;
+            }
+        }
+        match &self.most_common {
+            Some((value, count)) => writeln!(f, "most common: {} ({})", value, count)?,
+            None => writeln!(f, "most common: -")?,
+        }
+        write!(f, "memory estimate: {} bytes", self.memory_estimate)
+    }
+}
+
+impl<
+        K: Hash + Eq + Clone + Ord,
+        V: Clone + Ord + PartialEq + PartialOrd + Eq,
+        S: BuildIndex<K>,
+    > Dictionary<K, V, S>
+{
+    /// Summarize the dictionary for a quick look in logs or tests
+    /// similar in spirit to `df.describe()`
+    /// this is O(n log n) in the number of values
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    ///
+    /// let dict = Dictionary::from([(3, 'a'), (1, 'b'), (2, 'a')]);
+    /// let summary = dict.summary();
+    /// assert_eq!(summary.distinct_values, 2);
+    /// assert_eq!(summary.min_key, Some(1));
+    /// assert_eq!(summary.most_common, Some(('a', 2)));
+    /// ```
+    pub fn summary(&self) -> DictSummary<K, V> {
+        let min_key = self.keys().iter().min().cloned();
+        let max_key = self.keys().iter().max().cloned();
+
+        // value -> (count, first index) so ties resolve by position
+        let mut counts: BTreeMap<&V, (usize, usize)> = BTreeMap::new();
+        for (i, value) in self.values().iter().enumerate() {
+            counts.entry(value).or_insert((0, i)).0 += 1;
+        }
+        let most_common = counts
+            .iter()
+            .max_by(|(_, (c1, i1)), (_, (c2, i2))| c1.cmp(c2).then(i2.cmp(i1)))
+            .map(|(value, (count, _))| ((*value).clone(), *count));
+
+        DictSummary {
+            len: self.len(),
+            capacity: self.capacity(),
+            distinct_values: counts.len(),
+            min_key,
+            max_key,
+            most_common,
+            memory_estimate: memory_estimate::<K, V>(self.capacity()),
+        }
+    }
+}
+
+fn memory_estimate<K: Hash + Eq, V>(capacity: usize) -> usize {
+    // keys vec + values vec + one (K, usize) slot plus a control byte per map bucket
+    let map_slot = size_of::<(K, usize)>() + 1;
+    size_of::<Dictionary<K, V>>() + capacity * (size_of::<K>() + size_of::<V>() + map_slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let dict = Dictionary::from([(5, 1), (2, 7), (9, 7), (4, 1), (3, 2)]);
+        let summary = dict.summary();
+        assert_eq!(summary.len, 5);
+        assert_eq!(summary.distinct_values, 3);
+        assert_eq!(summary.min_key, Some(2));
+        assert_eq!(summary.max_key, Some(9));
+        // 1 and 7 both appear twice, 1 appears first
+        assert_eq!(summary.most_common, Some((1, 2)));
+        assert!(summary.memory_estimate > 0);
+    }
+
+    #[test]
+    fn summary_empty() {
+        let dict = Dictionary::<i32, i32>::new();
+        let summary = dict.summary();
+        assert_eq!(summary.len, 0);
+        assert_eq!(summary.min_key, None);
+        assert_eq!(summary.most_common, None);
+        assert!(summary.to_string().contains("min key: -"));
+    }
+}
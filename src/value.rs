@@ -0,0 +1,201 @@
+use crate::dict::Dictionary;
+
+/// A dynamically typed value for nested, order preserving documents
+/// the shape of parsed JSON, with objects kept as ordered Dictionaries
+#[derive(Debug, Clone, PartialEq)]
+pub enum DictValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<DictValue>),
+    /// boxed, a Dictionary is far larger than the other variants
+    Dict(Box<Dictionary<String, DictValue>>),
+}
+
+impl DictValue {
+    /// the nested dictionary, if this is a Dict
+    pub fn as_dict(&self) -> Option<&Dictionary<String, DictValue>> {
+        match self {
+            DictValue::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// the nested dictionary, if this is a Dict
+    pub fn as_dict_mut(&mut self) -> Option<&mut Dictionary<String, DictValue>> {
+        match self {
+            DictValue::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// the items, if this is a List
+    pub fn as_list(&self) -> Option<&Vec<DictValue>> {
+        match self {
+            DictValue::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// the items, if this is a List
+    pub fn as_list_mut(&mut self) -> Option<&mut Vec<DictValue>> {
+        match self {
+            DictValue::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// look up a nested value by an RFC 6901 JSON Pointer, like `serde_json::Value::pointer`
+    /// the pointer is "" for the value itself, or a `/` before each dict key or list index,
+    /// with `~1` for a `/` and `~0` for a `~` inside a key
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::value::DictValue;
+    ///
+    /// let mut server = Dictionary::new();
+    /// server.push_back(String::from("ports"), DictValue::from(vec![DictValue::Int(80)]));
+    /// server.push_back(String::from("a/b"), DictValue::Bool(true));
+    /// let doc = DictValue::from(server);
+    /// assert_eq!(doc.pointer("/ports/0"), Some(&DictValue::Int(80)));
+    /// assert_eq!(doc.pointer("/a~1b"), Some(&DictValue::Bool(true)));
+    /// assert_eq!(doc.pointer("/ports/1"), None);
+    /// assert_eq!(doc.pointer(""), Some(&doc));
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&DictValue> {
+        let mut target = self;
+        for token in pointer_tokens(pointer)? {
+            target = match target {
+                DictValue::Dict(dict) => dict.get_full(&token).map(|(_, _, value)| value)?,
+                DictValue::List(items) => items.get(list_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+
+    /// a mutable reference to a nested value by an RFC 6901 JSON Pointer, see pointer
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut DictValue> {
+        let mut target = self;
+        for token in pointer_tokens(pointer)? {
+            target = match target {
+                DictValue::Dict(dict) => dict.get_mut(token)?,
+                DictValue::List(items) => items.get_mut(list_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(target)
+    }
+}
+
+// the unescaped reference tokens, None if the pointer is neither empty nor starts with /
+fn pointer_tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let tokens = pointer.strip_prefix('/')?.split('/');
+    // ~1 first, so ~01 becomes ~1 and not /
+    Some(
+        tokens
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+// a list index is digits with no leading zero, `-` (past the end) never matches
+fn list_index(token: &str) -> Option<usize> {
+    if token.is_empty() || !token.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl From<bool> for DictValue {
+    fn from(value: bool) -> DictValue {
+        DictValue::Bool(value)
+    }
+}
+
+impl From<i64> for DictValue {
+    fn from(value: i64) -> DictValue {
+        DictValue::Int(value)
+    }
+}
+
+impl From<f64> for DictValue {
+    fn from(value: f64) -> DictValue {
+        DictValue::Float(value)
+    }
+}
+
+impl From<&str> for DictValue {
+    fn from(value: &str) -> DictValue {
+        DictValue::String(value.to_string())
+    }
+}
+
+impl From<String> for DictValue {
+    fn from(value: String) -> DictValue {
+        DictValue::String(value)
+    }
+}
+
+impl From<Vec<DictValue>> for DictValue {
+    fn from(value: Vec<DictValue>) -> DictValue {
+        DictValue::List(value)
+    }
+}
+
+impl From<Dictionary<String, DictValue>> for DictValue {
+    fn from(value: Dictionary<String, DictValue>) -> DictValue {
+        DictValue::Dict(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc() -> DictValue {
+        let mut inner = Dictionary::new();
+        inner.push_back(String::from("~key"), DictValue::Int(1));
+        inner.push_back(String::from(""), DictValue::Int(2));
+        let mut outer = Dictionary::new();
+        outer.push_back(String::from("inner"), DictValue::from(inner));
+        outer.push_back(
+            String::from("list"),
+            DictValue::from(vec![DictValue::Null, DictValue::from("x")]),
+        );
+        DictValue::from(outer)
+    }
+
+    #[test]
+    fn pointer_tokens_and_indices() {
+        let doc = doc();
+        assert_eq!(doc.pointer("/inner/~0key"), Some(&DictValue::Int(1)));
+        assert_eq!(doc.pointer("/inner/"), Some(&DictValue::Int(2)));
+        assert_eq!(doc.pointer("/list/1"), Some(&DictValue::from("x")));
+        assert_eq!(doc.pointer("/list/01"), None);
+        assert_eq!(doc.pointer("/list/-"), None);
+        assert_eq!(doc.pointer("/list/0/deeper"), None);
+        assert_eq!(doc.pointer("inner"), None);
+    }
+
+    #[test]
+    fn pointer_mut_edits_in_place() {
+        let mut doc = doc();
+        *doc.pointer_mut("/list/0").unwrap() = DictValue::Bool(false);
+        doc.pointer_mut("/inner")
+            .and_then(DictValue::as_dict_mut)
+            .unwrap()
+            .push_back(String::from("added"), DictValue::Int(3));
+        assert_eq!(doc.pointer("/list/0"), Some(&DictValue::Bool(false)));
+        assert_eq!(doc.pointer("/inner/added"), Some(&DictValue::Int(3)));
+        assert!(doc.pointer_mut("/missing").is_none());
+    }
+}
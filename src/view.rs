@@ -0,0 +1,175 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+/// Error returned when reading through a view whose dictionary has changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stale {
+    /// the version the view was created at
+    pub view_version: u64,
+    /// the version the dictionary is at now
+    pub dict_version: u64,
+}
+
+impl Display for Stale {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "view created at version {} but dictionary is at version {}",
+            self.view_version, self.dict_version
+        )
+    }
+}
+
+impl Error for Stale {}
+
+/// A lightweight handle that remembers the version of a Dictionary
+/// The view does not borrow the dictionary, it is handed the dictionary on every read
+/// and refuses to read with `Err(Stale)` once the dictionary has been mutated.
+/// This is useful for caching positions computed against the dictionary,
+/// the cache can be checked before an old index is used against changed data.
+/// # Example
+/// ```
+/// use rust_dict::dict::Dictionary;
+///
+/// let mut dict = Dictionary::from([(1, 10), (2, 20)]);
+/// let view = dict.weak_view();
+/// assert_eq!(view.get_index(&dict, 1), Ok(Some(20)));
+/// dict.remove(1);
+/// assert!(view.get_index(&dict, 1).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DictWeakView {
+    version: u64,
+}
+
+impl DictWeakView {
+    /// the dictionary version this view was created at
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// check the view against the dictionary
+    pub fn check<K, V, S>(&self, dict: &Dictionary<K, V, S>) -> Result<(), Stale>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        S: BuildIndex<K>,
+    {
+        if dict.version() == self.version {
+            Ok(())
+        } else {
+            Err(Stale {
+                view_version: self.version,
+                dict_version: dict.version(),
+            })
+        }
+    }
+
+    /// true if the dictionary has been mutated since the view was created
+    pub fn is_stale<K, V, S>(&self, dict: &Dictionary<K, V, S>) -> bool
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        S: BuildIndex<K>,
+    {
+        self.check(dict).is_err()
+    }
+
+    /// get value by key, if the view is still valid
+    pub fn get<K, V, S>(&self, dict: &Dictionary<K, V, S>, key: K) -> Result<Option<V>, Stale>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        S: BuildIndex<K>,
+    {
+        self.check(dict)?;
+        Ok(dict.get(key))
+    }
+
+    /// get value by index, if the view is still valid
+    pub fn get_index<K, V, S>(
+        &self,
+        dict: &Dictionary<K, V, S>,
+        i: usize,
+    ) -> Result<Option<V>, Stale>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        S: BuildIndex<K>,
+    {
+        self.check(dict)?;
+        Ok(dict.get_index(i))
+    }
+
+    /// the number of key value pairs, if the view is still valid
+    pub fn len<K, V, S>(&self, dict: &Dictionary<K, V, S>) -> Result<usize, Stale>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        S: BuildIndex<K>,
+    {
+        self.check(dict)?;
+        Ok(dict.len())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// create a view that records the current version of the dictionary
+    pub fn weak_view(&self) -> DictWeakView {
+        DictWeakView {
+            version: self.version(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_reads_until_mutation() {
+        let mut dict = Dictionary::from([(1, 10), (2, 20)]);
+        let view = dict.weak_view();
+        assert_eq!(view.get(&dict, 2), Ok(Some(20)));
+        assert_eq!(view.len(&dict), Ok(2));
+        assert!(!view.is_stale(&dict));
+
+        dict.push_back(3, 30);
+        assert!(view.is_stale(&dict));
+        assert_eq!(
+            view.get(&dict, 2),
+            Err(Stale {
+                view_version: view.version(),
+                dict_version: dict.version(),
+            })
+        );
+        assert!(!dict.weak_view().is_stale(&dict));
+    }
+
+    #[test]
+    fn every_mutation_invalidates() {
+        let mut dict = Dictionary::from([(3, 1), (1, 2), (2, 3)]);
+        let view = dict.weak_view();
+        // reading does not change anything
+        let _ = dict.get(3);
+        assert!(!view.is_stale(&dict));
+
+        dict.push_back(3, 4);
+        assert!(view.is_stale(&dict));
+        let view = dict.weak_view();
+        dict.sort_by_keys();
+        assert!(view.is_stale(&dict));
+        let view = dict.weak_view();
+        dict.sort_by_values();
+        assert!(view.is_stale(&dict));
+        let view = dict.weak_view();
+        dict.iter_mut().for_each(|(_, v)| *v += 1);
+        assert!(view.is_stale(&dict));
+        let view = dict.weak_view();
+        assert_eq!(dict.insert(4, 4, 0), Ok(true));
+        assert!(view.is_stale(&dict));
+    }
+}
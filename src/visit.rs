@@ -0,0 +1,141 @@
+use crate::dict::Dictionary;
+use crate::index::BuildIndex;
+use crate::value::DictValue;
+use std::hash::Hash;
+
+/// A stable traversal API over dictionaries
+/// Exporters, formatters and validators implement this once and are handed the
+/// entries in order by `Dictionary::walk` (flat) or `DictValue::walk` (nested).
+/// Only visit_entry is required, the enter and leave hooks default to doing nothing.
+pub trait Visitor<K, V> {
+    /// called before the entries of a dictionary with its length
+    fn enter_dict(&mut self, _len: usize) {}
+
+    /// called for every entry in order
+    /// for nested values this is called before descending into the value
+    fn visit_entry(&mut self, index: usize, key: &K, value: &V);
+
+    /// called after the last entry of a dictionary
+    fn leave_dict(&mut self) {}
+
+    /// called before the items of a nested list with its length
+    fn enter_list(&mut self, _len: usize) {}
+
+    /// called for every item of a nested list in order
+    /// for nested values this is called before descending into the item
+    fn visit_item(&mut self, _index: usize, _value: &V) {}
+
+    /// called after the last item of a nested list
+    fn leave_list(&mut self) {}
+}
+
+impl<K: Hash + Eq + Clone, V: Clone, S: BuildIndex<K>> Dictionary<K, V, S> {
+    /// walk the entries in order
+    /// # Example
+    /// ```
+    /// use rust_dict::dict::Dictionary;
+    /// use rust_dict::visit::Visitor;
+    ///
+    /// struct Lines(Vec<String>);
+    /// impl Visitor<i32, &str> for Lines {
+    ///     fn visit_entry(&mut self, index: usize, key: &i32, value: &&str) {
+    ///         self.0.push(format!("{} {}={}", index, key, value));
+    ///     }
+    /// }
+    ///
+    /// let dict = Dictionary::from([(2, "b"), (1, "a")]);
+    /// let mut lines = Lines(Vec::new());
+    /// dict.walk(&mut lines);
+    /// assert_eq!(lines.0, vec!["0 2=b", "1 1=a"]);
+    /// ```
+    pub fn walk<W: Visitor<K, V>>(&self, visitor: &mut W) {
+        visitor.enter_dict(self.len());
+        for (i, (key, value)) in self.iter().enumerate() {
+            visitor.visit_entry(i, key, value);
+        }
+        visitor.leave_dict();
+    }
+}
+
+impl DictValue {
+    /// walk a nested value depth first, in order
+    /// entries and items are visited before the dictionaries and lists they hold are entered,
+    /// scalars at the top level are not visited
+    pub fn walk<W: Visitor<String, DictValue>>(&self, visitor: &mut W) {
+        match self {
+            DictValue::Dict(dict) => {
+                visitor.enter_dict(dict.len());
+                for (i, (key, value)) in dict.iter().enumerate() {
+                    visitor.visit_entry(i, key, value);
+                    value.walk(visitor);
+                }
+                visitor.leave_dict();
+            }
+            DictValue::List(items) => {
+                visitor.enter_list(items.len());
+                for (i, item) in items.iter().enumerate() {
+                    visitor.visit_item(i, item);
+                    item.walk(visitor);
+                }
+                visitor.leave_list();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl Visitor<String, DictValue> for Trace {
+        fn enter_dict(&mut self, len: usize) {
+            self.0.push(format!("{{{}", len));
+        }
+        fn visit_entry(&mut self, index: usize, key: &String, _value: &DictValue) {
+            self.0.push(format!("{}:{}", index, key));
+        }
+        fn leave_dict(&mut self) {
+            self.0.push("}".into());
+        }
+        fn enter_list(&mut self, len: usize) {
+            self.0.push(format!("[{}", len));
+        }
+        fn visit_item(&mut self, index: usize, _value: &DictValue) {
+            self.0.push(format!("{}", index));
+        }
+        fn leave_list(&mut self) {
+            self.0.push("]".into());
+        }
+    }
+
+    #[test]
+    fn walk_nested() {
+        let mut inner = Dictionary::new();
+        inner.push_back("x".to_string(), DictValue::Int(1));
+        let mut outer = Dictionary::new();
+        outer.push_back("a".to_string(), DictValue::Bool(true));
+        outer.push_back(
+            "b".to_string(),
+            DictValue::List(vec![DictValue::Null, DictValue::from(inner)]),
+        );
+        let mut trace = Trace::default();
+        DictValue::from(outer).walk(&mut trace);
+        assert_eq!(
+            trace.0,
+            vec!["{2", "0:a", "1:b", "[2", "0", "1", "{1", "0:x", "}", "]", "}"]
+        );
+    }
+
+    #[test]
+    fn walk_flat() {
+        let mut dict = Dictionary::new();
+        dict.push_back("a".to_string(), DictValue::Int(1));
+        let mut trace = Trace::default();
+        dict.walk(&mut trace);
+        assert_eq!(trace.0, vec!["{1", "0:a", "}"]);
+    }
+}
@@ -0,0 +1,47 @@
+// a global allocator replaces the allocator of the whole test binary, so it gets
+// a binary of its own instead of living with the unit tests
+use rust_dict::dict::Dictionary;
+use rust_dict::index::BTreeIndex;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations of the current thread, so tests running in parallel
+// do not see each other's
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+fn allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.with(|n| n.get());
+    let value = f();
+    (value, ALLOCATIONS.with(|n| n.get()) - before)
+}
+
+#[test]
+fn new_does_not_allocate() {
+    let (mut dict, count) = allocations(Dictionary::<String, Vec<u8>>::new);
+    assert_eq!(count, 0);
+    let (_, count) = allocations(|| Dictionary::<u32, u32>::with_capacity(0));
+    assert_eq!(count, 0);
+    let (btree, count) = allocations(|| Dictionary::<u32, u32, _>::with_index(BTreeIndex));
+    assert_eq!(count, 0);
+    drop(btree);
+    let (_, count) = allocations(|| dict.push_back(String::new(), Vec::new()));
+    assert!(count > 0);
+}